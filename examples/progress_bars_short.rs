@@ -14,7 +14,7 @@ pub fn main() {
 
 	// Initialize a thread pool where workers own their
 	// respective indicatif progress bars.
-	let mut pool = ThreadPool::new(
+	let pool = ThreadPool::new(
 		(0..cores)
 			.map(|_| multi_pb.add(ProgressBar::new(10)))
 			.collect::<Vec<_>>(),
@@ -30,7 +30,7 @@ pub fn main() {
 	);
 	main_pb.enable_steady_tick(Duration::from_millis(100));
 
-	let mut pool = ThreadPool::new(
+	let pool = ThreadPool::new(
 		(0..cores)
 			.map(|i| {
 				multi_pb.add(
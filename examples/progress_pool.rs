@@ -0,0 +1,39 @@
+use indicatif::{MultiProgress, ProgressStyle};
+use lending_thread_pool::ProgressPool;
+use std::{thread::sleep, time::Duration};
+
+pub fn main() {
+	// Assuming we have 4 cores
+	let cores = 4;
+	// Simulating 16 expensive things to do in parallel
+	let tasks = 16;
+
+	let multi_pb = MultiProgress::new();
+
+	// Initialize a pool where workers own their respective indicatif
+	// progress bars, plus a main bar tracking all the tasks.
+	let pool = ProgressPool::new(&multi_pb, vec![(); cores]);
+	pool.main_bar().set_style(
+		ProgressStyle::with_template("[{bar:40}] {pos}/{len}")
+			.unwrap()
+			.progress_chars("=>-"),
+	);
+
+	for _ in 0..tasks {
+		// Simulate a long operation on the main thread
+		sleep(Duration::from_millis(100));
+
+		// Worker bars are reset after each task, and the main
+		// bar is updated automatically
+		pool.enqueue(move |worker| {
+			worker.bar.set_length(10);
+			// Simulate a series of long operations on the worker thread
+			for _ in 0..10 {
+				sleep(Duration::from_millis(10));
+				worker.bar.inc(1);
+			}
+		});
+	}
+
+	pool.join();
+}
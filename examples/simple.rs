@@ -10,7 +10,7 @@ pub fn main() {
 
 	let cores = num_cpus::get() as u64;
 
-	let mut pool = ThreadPool::new(
+	let pool = ThreadPool::new(
 		(0..cores)
 			.map(|i| format!("Hello from worker {i}"))
 			.collect::<Vec<_>>(),
@@ -1,5 +1,7 @@
 #![doc = include_str!("../README.md")]
 
+#[macro_use]
+mod macros;
 mod thread_pool;
 
 pub use thread_pool::*;
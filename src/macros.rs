@@ -0,0 +1,64 @@
+/// Internal diagnostics at the given level. Expands to the macros of [`log`] when the `log`
+/// feature is enabled, to the ones of [`tracing`] when only the `tracing` feature is enabled,
+/// and to dead code (so that arguments still count as used) otherwise.
+///
+/// Besides format arguments, accepts a message followed by `key = value` fields, recorded as
+/// structured fields by `tracing` and appended to the message as `key=value` by `log`, e.g.
+/// `event!(trace, "task started", task = seq, worker = index)`. Values are formatted with
+/// [`Debug`].
+macro_rules! event {
+	($level:ident, $message:literal $(, $key:ident = $value:expr)+ $(,)?) => {
+		#[cfg(feature = "log")]
+		::log::$level!(
+			::std::concat!($message $(, " ", ::std::stringify!($key), "={:?}")+)
+			$(, $value)+
+		);
+		#[cfg(all(feature = "tracing", not(feature = "log")))]
+		::tracing::$level!($($key = ?$value,)+ $message);
+		#[cfg(not(any(feature = "tracing", feature = "log")))]
+		if false {
+			let _ = ::std::format_args!("{:?}", ($(&$value,)+));
+		}
+	};
+	($level:ident, $($arg:tt)*) => {
+		#[cfg(feature = "log")]
+		::log::$level!($($arg)*);
+		#[cfg(all(feature = "tracing", not(feature = "log")))]
+		::tracing::$level!($($arg)*);
+		#[cfg(not(any(feature = "tracing", feature = "log")))]
+		if false {
+			let _ = ::std::format_args!($($arg)*);
+		}
+	};
+}
+
+/// Per-task events, e.g. a task being enqueued, started or completed, so that enabling the
+/// trace level yields a log of the execution of the pool. See [`event!`].
+macro_rules! trace {
+	($($arg:tt)*) => {
+		event!(trace, $($arg)*)
+	};
+}
+
+/// Events of the pool and its workers, e.g. shutting down. See [`event!`].
+macro_rules! debug {
+	($($arg:tt)*) => {
+		event!(debug, $($arg)*)
+	};
+}
+
+/// Like [`debug!`], at the warning level.
+macro_rules! warn {
+	($($arg:tt)*) => {
+		event!(warn, $($arg)*)
+	};
+}
+
+/// Opens a profiler zone until the end of the enclosing scope, with the `profiling` feature
+/// enabled. `$data` is shown alongside the zone, e.g. the name of a task.
+macro_rules! profile_scope {
+	($name:literal, $data:expr) => {
+		#[cfg(feature = "profiling")]
+		::profiling::scope!($name, $data);
+	};
+}
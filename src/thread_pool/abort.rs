@@ -0,0 +1,66 @@
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+
+use super::ThreadPool;
+
+/// A read-only view of the kill switch of a pool, see [`ThreadPool::abort`].
+///
+/// Long-running tasks can capture it and periodically check [`AbortSignal::is_aborted`]
+/// to bail out early, bounding the time it takes to shut the pool down.
+///
+/// Cloning an `AbortSignal` yields a handle to the same flag.
+#[derive(Debug, Clone)]
+pub struct AbortSignal {
+	pub(crate) aborted: Arc<AtomicBool>,
+}
+
+impl AbortSignal {
+	/// Whether the pool has been aborted.
+	#[must_use]
+	pub fn is_aborted(&self) -> bool {
+		self.aborted.load(Ordering::Relaxed)
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Stop accepting tasks, discard the pending ones and raise the kill switch observed
+	/// by [`AbortSignal`]s. Workers exit as soon as they're done with their current task.
+	///
+	/// Running tasks are not interrupted: it's up to them to check the signal
+	/// obtained through [`ThreadPool::abort_signal`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{thread::sleep, time::Duration};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	///
+	/// for _ in 0..4 {
+	/// 	let signal = pool.abort_signal();
+	/// 	pool.enqueue(move |()| {
+	/// 		while !signal.is_aborted() {
+	/// 			sleep(Duration::from_millis(1));
+	/// 		}
+	/// 	});
+	/// }
+	/// pool.abort();
+	/// pool.join();
+	/// ```
+	pub fn abort(&self) {
+		debug!("aborting...");
+		self.inner.aborted.store(true, Ordering::Relaxed);
+		self.inner.shutdown();
+	}
+
+	/// Obtain a handle to the kill switch of the pool, raised by [`ThreadPool::abort`].
+	#[must_use]
+	pub fn abort_signal(&self) -> AbortSignal {
+		AbortSignal {
+			aborted: self.inner.aborted.clone(),
+		}
+	}
+}
@@ -0,0 +1,177 @@
+use std::{
+	error, fmt,
+	sync::{atomic::Ordering, Arc},
+};
+
+use super::{
+	meta::Metadata, PoolEvent, Task, TaskMeta, ThreadPool, ThreadPoolBuilder, ThreadPoolShared,
+};
+
+type AdmissionFn = dyn Fn(Option<TaskMeta<'_>>) -> bool + Send + Sync;
+
+/// Decides which tasks the pool accepts, see [`ThreadPoolBuilder::admission`].
+pub(crate) struct Admission(Box<AdmissionFn>);
+
+impl Admission {
+	pub(crate) fn admits(&self, meta: Option<TaskMeta<'_>>) -> bool {
+		(self.0)(meta)
+	}
+}
+
+impl fmt::Debug for Admission {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Admission").finish_non_exhaustive()
+	}
+}
+
+/// A task turned down by the admission predicate of the pool, as returned by
+/// [`ThreadPool::try_enqueue_with_meta`], handing the task and its metadata back.
+pub struct AdmissionRejected<Meta, Task> {
+	/// The metadata the task was enqueued with.
+	pub meta: Meta,
+	/// The task, e.g. to be enqueued again later or on another pool.
+	pub task: Task,
+}
+
+impl<Meta: fmt::Debug, Task> fmt::Debug for AdmissionRejected<Meta, Task> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AdmissionRejected")
+			.field("meta", &self.meta)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<Meta: fmt::Debug, Task> fmt::Display for AdmissionRejected<Meta, Task> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "task rejected by the pool: {:?}", self.meta)
+	}
+}
+
+impl<Meta: fmt::Debug, Task> error::Error for AdmissionRejected<Meta, Task> {}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Whether the admission predicate accepts the given task, if it wasn't let in already.
+	/// Rejected tasks are reported, and are to be dropped by the caller.
+	pub(crate) fn admit(&self, task: &Task<WorkerData>) -> bool {
+		let admitted = task.admitted
+			|| self
+				.admission
+				.as_ref()
+				.is_none_or(|admission| admission.admits(task.meta.as_ref().map(TaskMeta::new)));
+		if !admitted {
+			self.reject(task);
+		}
+		admitted
+	}
+
+	fn reject(&self, task: &Task<WorkerData>) {
+		debug!(
+			"task rejected by the admission predicate",
+			location = task.location
+		);
+		self.counters.discarded.fetch_add(1, Ordering::Relaxed);
+		self.listeners.emit(PoolEvent::TaskRejected {
+			name: task.name.as_deref(),
+			meta: task.meta.as_ref().map(TaskMeta::new),
+		});
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Like [`ThreadPool::enqueue_with_meta`], but returns the task and its metadata if the
+	/// admission predicate of the pool turns it down, see [`ThreadPoolBuilder::admission`],
+	/// instead of discarding it.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning, once the task has been admitted.
+	///
+	/// # Errors
+	/// - if the admission predicate rejected the task
+	#[allow(clippy::missing_panics_doc)]
+	#[track_caller]
+	pub fn try_enqueue_with_meta<Meta, Task>(
+		&self,
+		meta: Meta,
+		task: Task,
+	) -> Result<(), AdmissionRejected<Meta, Task>>
+	where
+		Meta: fmt::Debug + Send + Sync + 'static,
+		Task: FnOnce(&mut WorkerData) + Send + 'static,
+	{
+		let meta = Arc::new(meta);
+		let erased: Arc<dyn Metadata> = meta.clone();
+		if let Some(admission) = &self.inner.admission {
+			if !admission.admits(Some(TaskMeta::new(&erased))) {
+				debug!("task rejected by the admission predicate");
+				drop(erased);
+				let meta = Arc::into_inner(meta).unwrap();
+				return Err(AdmissionRejected { meta, task });
+			}
+		}
+		self.enqueue_task(self::Task {
+			meta: Some(erased),
+			admitted: true,
+			..self::Task::new(task)
+		});
+		Ok(())
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Decide whether to accept each task as it's enqueued, given the metadata it was enqueued
+	/// with, if any (see [`ThreadPool::enqueue_with_meta`]), e.g. to shed load while the pool
+	/// is overloaded or to enforce per-tenant quotas.
+	///
+	/// Rejected tasks are dropped without being run, counted as discarded and reported to the
+	/// [`PoolEventListener`](crate::PoolEventListener)s as [`PoolEvent::TaskRejected`].
+	/// [`ThreadPool::try_enqueue_with_meta`] and [`ThreadPool::try_enqueue_many`] hand them
+	/// back instead. The predicate runs on the
+	/// enqueuing thread, before waiting for room in the queue, once per task. Tasks the pool
+	/// puts back in the queue by itself, e.g. retries, aren't subject to it.
+	///
+	/// Defaults to accepting every task.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::{
+	/// 	atomic::{AtomicUsize, Ordering},
+	/// 	Arc,
+	/// };
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// #[derive(Debug)]
+	/// struct Tenant(&'static str);
+	///
+	/// let guest_tasks = Arc::new(AtomicUsize::new(0));
+	/// let pool = ThreadPool::builder(vec![(); 2])
+	/// 	.admission({
+	/// 		let guest_tasks = guest_tasks.clone();
+	/// 		move |meta| match meta.and_then(|meta| meta.downcast_ref::<Tenant>()) {
+	/// 			// Up to 3 tasks from guests.
+	/// 			Some(Tenant("guest")) => guest_tasks.fetch_add(1, Ordering::Relaxed) < 3,
+	/// 			Some(_) => true,
+	/// 			None => false,
+	/// 		}
+	/// 	})
+	/// 	.build();
+	///
+	/// let rejected = (0..5)
+	/// 	.filter(|_| pool.try_enqueue_with_meta(Tenant("guest"), |()| {}).is_err())
+	/// 	.count();
+	/// assert_eq!(rejected, 2);
+	/// assert!(pool.try_enqueue_with_meta(Tenant("acme"), |()| {}).is_ok());
+	///
+	/// // Tasks without metadata are turned down, and discarded.
+	/// pool.enqueue(|()| unreachable!());
+	/// ```
+	pub fn admission(
+		mut self,
+		predicate: impl Fn(Option<TaskMeta<'_>>) -> bool + Send + Sync + 'static,
+	) -> Self {
+		self.admission = Some(Admission(Box::new(predicate)));
+		self
+	}
+}
@@ -0,0 +1,80 @@
+use std::sync::{Arc, Barrier};
+
+use super::{PoolQueue, Task, ThreadPool, WorkerState};
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a synchronization point on every worker and block until all of them have reached it.
+	///
+	/// When this method returns, every task enqueued before the barrier has completed
+	/// (see [`ThreadPool::flush`]) and all workers are aligned, which is useful between phases
+	/// that change shared assumptions.
+	///
+	/// If the pool has been shut down, this method returns immediately.
+	/// Workers brought down by a panic (see [`ThreadPool::dead_workers`]) are not waited for.
+	/// Barrier tasks don't count against the [`ThreadBudget`](crate::ThreadBudget) of the pool, if any.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::{
+	/// 	atomic::{AtomicUsize, Ordering},
+	/// 	Arc,
+	/// };
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 4]);
+	/// let counter = Arc::new(AtomicUsize::new(0));
+	///
+	/// for _ in 0..16 {
+	/// 	let counter = counter.clone();
+	/// 	pool.enqueue(move |()| {
+	/// 		counter.fetch_add(1, Ordering::Relaxed);
+	/// 	});
+	/// }
+	/// pool.barrier();
+	///
+	/// assert_eq!(counter.load(Ordering::Relaxed), 16);
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	pub fn barrier(&self) {
+		self.flush();
+
+		// Synchronization tasks target each worker directly, as workers that are checked out
+		// or belong to a group don't serve the shared queue.
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			debug!("pool is shut down, skipping barrier");
+			return;
+		};
+		let alive = self
+			.inner
+			.workers()
+			.iter()
+			.enumerate()
+			.filter(|(_, worker)| {
+				!matches!(*worker.state.lock().unwrap(), WorkerState::Panicked { .. })
+			})
+			.map(|(i, _)| i)
+			.collect::<Vec<_>>();
+		let barrier = Arc::new(Barrier::new(alive.len() + 1));
+		for &worker in &alive {
+			let barrier = barrier.clone();
+			tasks.push_to_worker(
+				worker,
+				Task {
+					budgeted: false,
+					..Task::new(move |_: &mut WorkerData| {
+						barrier.wait();
+					})
+				},
+			);
+		}
+		drop(guard);
+		self.inner.wake_all_workers();
+		debug!(
+			"waiting for workers to reach the barrier",
+			workers = alive.len()
+		);
+		barrier.wait();
+	}
+}
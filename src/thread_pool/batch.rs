@@ -0,0 +1,449 @@
+use std::{
+	any::Any,
+	collections::BTreeSet,
+	convert::Infallible,
+	error, fmt,
+	panic::{self, AssertUnwindSafe},
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, Condvar, Mutex, PoisonError,
+	},
+};
+
+use super::{PoolQueue, Task, ThreadPool, ThreadPoolShared};
+
+/// Batch of the tasks enqueued outside of a [`Batch`].
+pub(crate) const NO_BATCH: u64 = 0;
+
+static NEXT_BATCH: AtomicU64 = AtomicU64::new(NO_BATCH + 1);
+
+/// A group of tasks submitted together, which can be awaited as a whole while other tasks
+/// keep flowing through the pool. This can be constructed using the [`ThreadPool::batch`]
+/// and [`ThreadPool::fallible_batch`] methods.
+///
+/// Fallible batches are fail-fast: as soon as one of their tasks fails (by returning
+/// an error, panicking or being discarded by the pool without running), the tasks of the
+/// batch that haven't started yet are skipped and [`Batch::wait`] returns the error. With
+/// [`Batch::abort_on_failure`], they're also removed from the queue right away.
+///
+/// Plain batches are latches instead: all of their tasks run, and [`Batch::wait`] reports
+/// the first failure once they're all done.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::ThreadPool;
+///
+/// let pool = ThreadPool::new(vec![(); 4]);
+///
+/// let batch = pool.batch();
+/// for i in 0..16 {
+/// 	batch.enqueue(move |()| println!("phase 1, task {i}"));
+/// }
+/// // Only waits for the 16 tasks above.
+/// batch.wait().unwrap();
+///
+/// println!("phase 1 done");
+/// ```
+///
+/// ```
+/// use lending_thread_pool::{BatchError, ThreadPool};
+///
+/// let pool = ThreadPool::new(vec![(); 4]);
+///
+/// let batch = pool.fallible_batch();
+/// for i in 0..16 {
+/// 	batch.enqueue_fallible(move |()| {
+/// 		if i == 3 {
+/// 			Err(format!("failed to compile unit {i}"))
+/// 		} else {
+/// 			Ok(())
+/// 		}
+/// 	});
+/// }
+///
+/// assert!(matches!(batch.wait(), Err(BatchError::Failed(_))));
+/// ```
+pub struct Batch<'pool, WorkerData: Send, Error = Infallible> {
+	pool: &'pool ThreadPool<WorkerData>,
+	id: u64,
+	shared: Arc<BatchShared<Error>>,
+}
+
+/// Removes the queued tasks of a batch from its pool, returning how many were removed.
+/// Shared so that it can be called without holding the lock on the batch state, as the
+/// removed tasks settle themselves when dropped.
+type Abort = Arc<dyn Fn() -> usize + Send + Sync>;
+
+struct BatchShared<Error> {
+	state: Mutex<BatchState<Error>>,
+	condvar: Condvar,
+}
+
+struct BatchState<Error> {
+	pending: usize,
+	failure: Option<BatchError<Error>>,
+	failed: bool,
+	/// Number of tasks enqueued so far, i.e. the index of the next one.
+	enqueued: usize,
+	/// Indices of the tasks that haven't started yet, see [`Batch::never_ran`].
+	unstarted: BTreeSet<usize>,
+	/// Indices of the tasks dropped by the pool without running.
+	discarded: BTreeSet<usize>,
+	/// Whether a failure skips the tasks that haven't started yet.
+	fail_fast: bool,
+	/// Set by [`Batch::abort_on_failure`].
+	abort: Option<Abort>,
+}
+
+/// The reason a [`Batch`] failed.
+pub enum BatchError<Error> {
+	/// A task returned an error.
+	Failed(Error),
+	/// A task panicked. Contains the panic payload.
+	Panicked(Box<dyn Any + Send>),
+	/// A task was dropped by the pool without running, e.g. because the pool was aborted
+	/// or shut down, or because its admission predicate rejected it.
+	Discarded,
+}
+
+impl<Error: fmt::Debug> fmt::Debug for BatchError<Error> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Failed(error) => f.debug_tuple("Failed").field(error).finish(),
+			Self::Panicked(_) => f.debug_tuple("Panicked").finish_non_exhaustive(),
+			Self::Discarded => f.write_str("Discarded"),
+		}
+	}
+}
+
+impl<Error: fmt::Display> fmt::Display for BatchError<Error> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Failed(error) => write!(f, "batch task failed: {error}"),
+			Self::Panicked(_) => write!(f, "batch task panicked"),
+			Self::Discarded => write!(f, "batch task discarded without running"),
+		}
+	}
+}
+
+impl<Error: error::Error + 'static> error::Error for BatchError<Error> {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			Self::Failed(error) => Some(error),
+			Self::Panicked(_) | Self::Discarded => None,
+		}
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Construct a [`Batch`] of tasks running on this pool, used as a completion latch:
+	/// a task panicking doesn't keep the others from running, and [`Batch::wait`] only
+	/// returns once they're all done.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::atomic::{AtomicUsize, Ordering};
+	///
+	/// use lending_thread_pool::{BatchError, ThreadPool};
+	///
+	/// static DONE: AtomicUsize = AtomicUsize::new(0);
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	///
+	/// let batch = pool.batch();
+	/// for i in 0..8 {
+	/// 	batch.enqueue(move |()| {
+	/// 		assert_ne!(i, 0, "task {i} failed");
+	/// 		DONE.fetch_add(1, Ordering::Relaxed);
+	/// 	});
+	/// }
+	///
+	/// assert!(matches!(batch.wait(), Err(BatchError::Panicked(_))));
+	/// assert_eq!(DONE.load(Ordering::Relaxed), 7);
+	/// ```
+	#[must_use]
+	pub fn batch(&self) -> Batch<'_, WorkerData> {
+		self.new_batch(false)
+	}
+
+	/// Construct a fail-fast [`Batch`] of fallible tasks running on this pool.
+	#[must_use]
+	pub fn fallible_batch<Error: Send + 'static>(&self) -> Batch<'_, WorkerData, Error> {
+		self.new_batch(true)
+	}
+
+	fn new_batch<Error: Send + 'static>(&self, fail_fast: bool) -> Batch<'_, WorkerData, Error> {
+		Batch {
+			pool: self,
+			id: NEXT_BATCH.fetch_add(1, Ordering::Relaxed),
+			shared: Arc::new(BatchShared {
+				state: Mutex::new(BatchState {
+					pending: 0,
+					failure: None,
+					failed: false,
+					enqueued: 0,
+					unstarted: BTreeSet::new(),
+					discarded: BTreeSet::new(),
+					fail_fast,
+					abort: None,
+				}),
+				condvar: Condvar::default(),
+			}),
+		}
+	}
+}
+
+impl<WorkerData: Send, Error: Send + 'static> Batch<'_, WorkerData, Error> {
+	/// Enqueue a task as part of this batch. If the batch is fail-fast and another task
+	/// of the batch has already failed by the time this one is dequeued, it is skipped.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	#[track_caller]
+	pub fn enqueue<Task>(&self, task: Task)
+	where
+		Task: FnOnce(&mut WorkerData) + Send + 'static,
+	{
+		self.enqueue_fallible(move |worker_data| {
+			task(worker_data);
+			Ok(())
+		});
+	}
+
+	/// Enqueue a fallible task as part of this batch. If the batch is fail-fast and another
+	/// task of the batch has already failed by the time this one is dequeued, it is skipped.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	#[allow(clippy::missing_panics_doc)]
+	#[track_caller]
+	pub fn enqueue_fallible<Task>(&self, task: Task)
+	where
+		Task: FnOnce(&mut WorkerData) -> Result<(), Error> + Send + 'static,
+	{
+		let mut state = self.shared.state.lock().unwrap();
+		state.pending += 1;
+		let index = state.enqueued;
+		state.enqueued += 1;
+		state.unstarted.insert(index);
+		drop(state);
+		let unrun = Unrun(Some(self.shared.clone()), index);
+		let run = move |worker_data: &mut WorkerData| {
+			let shared = unrun.disarm();
+			let mut state = shared.state.lock().unwrap();
+			let skip = state.fail_fast && state.failed;
+			if !skip {
+				state.unstarted.remove(&index);
+			}
+			drop(state);
+			let failure = if skip {
+				debug!("batch failed, skipping task");
+				None
+			} else {
+				match panic::catch_unwind(AssertUnwindSafe(|| task(worker_data))) {
+					Ok(Ok(())) => None,
+					Ok(Err(error)) => Some(BatchError::Failed(error)),
+					Err(payload) => Some(BatchError::Panicked(payload)),
+				}
+			};
+			let mut state = shared.state.lock().unwrap();
+			state.pending -= 1;
+			let abort = failure.and_then(|failure| {
+				debug!("batch task failed");
+				if state.failed {
+					return None;
+				}
+				state.failed = true;
+				state.failure = Some(failure);
+				state.abort.clone()
+			});
+			drop(state);
+			shared.condvar.notify_all();
+			// The removed tasks lock the state when dropped.
+			if let Some(abort) = abort {
+				let removed = abort();
+				debug!("batch failed, removed its queued tasks", count = removed);
+			}
+		};
+		self.pool.enqueue_task(self::Task {
+			batch: self.id,
+			..self::Task::new(run)
+		});
+	}
+
+	/// The indices of the tasks of the batch, in the order they were enqueued, that didn't
+	/// and won't run: the ones the pool discarded without running and, for a fail-fast
+	/// batch, the ones skipped because another task failed, either when dequeued or by
+	/// being removed from the queue with [`Batch::abort_on_failure`]. Empty unless the
+	/// batch failed.
+	///
+	/// Tasks still queued when this is called are included if the batch is fail-fast,
+	/// as they won't run either.
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn never_ran(&self) -> Vec<usize> {
+		let state = self.shared.state.lock().unwrap();
+		if state.fail_fast && state.failed {
+			state.unstarted.union(&state.discarded).copied().collect()
+		} else {
+			state.discarded.iter().copied().collect()
+		}
+	}
+
+	/// Block until all the tasks enqueued through this batch have completed or, for a
+	/// fail-fast batch, until one of them fails. Tasks enqueued in the pool by other means
+	/// are not waited for.
+	///
+	/// The batch can still be used afterwards, e.g. to find out which tasks
+	/// [never ran](Batch::never_ran).
+	///
+	/// # Errors
+	/// - the error returned by the first task that failed, or the payload of the first task
+	///   that panicked. Only the first call after the failure returns it.
+	/// - [`BatchError::Discarded`] if the pool dropped one of the tasks without running it.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	///
+	/// use lending_thread_pool::{BatchError, ThreadPool};
+	///
+	/// let pool = ThreadPool::new_with_queue_size(vec![(); 1], 4);
+	///
+	/// let (started_tx, started) = mpsc::channel();
+	/// let (release, released) = mpsc::channel::<()>();
+	/// pool.enqueue(move |()| {
+	/// 	started_tx.send(()).unwrap();
+	/// 	released.recv().ok();
+	/// });
+	/// started.recv().unwrap();
+	///
+	/// let batch = pool.batch();
+	/// for i in 0..4 {
+	/// 	batch.enqueue(move |()| println!("task {i}"));
+	/// }
+	/// // The queued tasks of the batch are dropped, which doesn't leave it hanging.
+	/// pool.abort();
+	///
+	/// assert!(matches!(batch.wait(), Err(BatchError::Discarded)));
+	/// assert_eq!(batch.never_ran(), vec![0, 1, 2, 3]);
+	/// release.send(()).unwrap();
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	pub fn wait(&self) -> Result<(), BatchError<Error>> {
+		let mut state = self.shared.state.lock().unwrap();
+		while state.pending > 0 && !(state.fail_fast && state.failed) {
+			state = self.shared.condvar.wait(state).unwrap();
+		}
+		state.failure.take().map_or(Ok(()), Err)
+	}
+}
+
+impl<WorkerData: Send + 'static, Error: Send + 'static> Batch<'_, WorkerData, Error> {
+	/// Remove the queued tasks of the batch as soon as one of its tasks fails, freeing
+	/// their room in the queue, instead of skipping them once they're dequeued.
+	/// See [`Batch::never_ran`] to find out which tasks were removed. This makes
+	/// a batch built with [`ThreadPool::batch`] fail-fast too.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 1]);
+	///
+	/// let batch = pool.fallible_batch().abort_on_failure();
+	/// for i in 0..8 {
+	/// 	batch.enqueue_fallible(move |()| {
+	/// 		if i == 2 {
+	/// 			Err(format!("failed to upload chunk {i}"))
+	/// 		} else {
+	/// 			Ok(())
+	/// 		}
+	/// 	});
+	/// }
+	///
+	/// assert!(batch.wait().is_err());
+	/// assert_eq!(batch.never_ran(), vec![3, 4, 5, 6, 7]);
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn abort_on_failure(self) -> Self {
+		let pool = Arc::downgrade(&self.pool.inner);
+		let id = self.id;
+		let mut state = self.shared.state.lock().unwrap();
+		state.fail_fast = true;
+		state.abort = Some(Arc::new(move || {
+			pool.upgrade().map_or(0, |pool| pool.remove_batch(id))
+		}));
+		drop(state);
+		self
+	}
+}
+
+/// Settles a batch task that is dropped without running, e.g. when the pool is aborted,
+/// so that [`Batch::wait`] doesn't wait for it forever. The pool never drops tasks while
+/// holding its queue lock, so this doesn't deadlock with [`Batch::abort_on_failure`].
+struct Unrun<Error>(Option<Arc<BatchShared<Error>>>, usize);
+
+impl<Error> Unrun<Error> {
+	/// The task is about to run and settle itself.
+	fn disarm(mut self) -> Arc<BatchShared<Error>> {
+		self.0.take().unwrap()
+	}
+}
+
+impl<Error> Drop for Unrun<Error> {
+	fn drop(&mut self) {
+		let Some(shared) = self.0.take() else {
+			return;
+		};
+		let mut state = shared.state.lock().unwrap_or_else(PoisonError::into_inner);
+		state.pending -= 1;
+		state.unstarted.remove(&self.1);
+		state.discarded.insert(self.1);
+		if !state.failed {
+			debug!("batch task discarded without running");
+			state.failed = true;
+			state.failure = Some(BatchError::Discarded);
+		}
+		drop(state);
+		shared.condvar.notify_all();
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Remove the queued tasks of the given batch, returning how many were removed.
+	fn remove_batch(&self, batch: u64) -> usize {
+		let mut guard = self.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			return 0;
+		};
+		let removed = tasks.remove_batch(batch);
+		drop(guard);
+		if !removed.is_empty() {
+			// Blocked enqueuers may fit now.
+			self.notify_room();
+		}
+		removed.len()
+	}
+}
+
+impl<WorkerData: Send, Error> fmt::Debug for Batch<'_, WorkerData, Error> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let state = self.shared.state.lock().unwrap();
+		f.debug_struct("Batch")
+			.field("pending", &state.pending)
+			.field("failed", &state.failed)
+			.field("fail_fast", &state.fail_fast)
+			.field("abort_on_failure", &state.abort.is_some())
+			.finish()
+	}
+}
@@ -0,0 +1,299 @@
+use std::{
+	collections::{hash_map::Entry, HashMap},
+	fmt,
+	hash::Hash,
+	mem,
+	panic::Location,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Condvar, Mutex, Weak,
+	},
+	time::{Duration, Instant},
+};
+
+use super::{Clock, Task, ThreadPool, ThreadPoolShared};
+
+type BatchHandler<WorkerData, Payload> = dyn Fn(&mut WorkerData, Vec<Payload>) + Send + Sync;
+
+/// Accumulates the payloads pushed for each key and hands them to a handler as a single
+/// task, once `max_len` of them have accumulated or `window` has passed since the first one,
+/// whichever comes first. This trades a little latency for a lot less per-item overhead when
+/// producers are chatty. This can be constructed using the [`ThreadPool::keyed_batcher`] method.
+///
+/// The window is measured by the [`Clock`] of the pool, on a background thread spawned
+/// like the workers, see [`ThreadPoolBuilder::thread_spawner`]. If the thread can't be
+/// spawned and the pool [degrades](crate::SpawnFailurePolicy::Degrade), the batches whose
+/// window is over are handed to the pool by the next [`push`](Self::push) instead. The
+/// remaining payloads are flushed when the batcher is dropped.
+///
+/// [`ThreadPoolBuilder::thread_spawner`]: crate::ThreadPoolBuilder::thread_spawner
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::mpsc, time::Duration};
+/// use lending_thread_pool::ThreadPool;
+///
+/// let pool = ThreadPool::new(vec![(); 2]);
+/// let (tx, rx) = mpsc::channel();
+///
+/// let batcher = pool.keyed_batcher(Duration::from_millis(10), 100, move |(), rows: Vec<u32>| {
+/// 	tx.send(rows.len()).unwrap();
+/// });
+/// for row in 0..250 {
+/// 	batcher.push(row % 2, row);
+/// }
+/// drop(batcher);
+///
+/// assert_eq!(rx.iter().sum::<usize>(), 250);
+/// ```
+pub struct KeyedBatcher<Key, Payload: Send + 'static, WorkerData: Send + 'static> {
+	shared: Arc<BatcherShared<Key, Payload, WorkerData>>,
+}
+
+struct BatcherShared<Key, Payload: Send + 'static, WorkerData: Send + 'static> {
+	pool: Weak<ThreadPoolShared<WorkerData>>,
+	clock: Arc<dyn Clock>,
+	handler: Arc<BatchHandler<WorkerData, Payload>>,
+	location: &'static Location<'static>,
+	window: Duration,
+	max_len: usize,
+	state: Mutex<BatcherState<Key, Payload>>,
+	/// Notified when a batch is started or the batcher is dropped, for the flusher
+	/// to update its deadline, and when the flusher is done dispatching.
+	condvar: Condvar,
+	/// Whether the flusher thread could be spawned, or else [`KeyedBatcher::push`]
+	/// dispatches the batches whose window is over.
+	flusher: AtomicBool,
+}
+
+struct BatcherState<Key, Payload> {
+	batches: HashMap<Key, PendingBatch<Payload>>,
+	closed: bool,
+	/// Whether the flusher is dispatching batches it took out of `batches`.
+	dispatching: bool,
+}
+
+struct PendingBatch<Payload> {
+	payloads: Vec<Payload>,
+	deadline: Instant,
+}
+
+impl<Key, Payload> BatcherState<Key, Payload> {
+	/// Take out the batches whose window is over.
+	fn take_expired(&mut self, now: Instant) -> Vec<Vec<Payload>> {
+		let mut expired = Vec::new();
+		self.batches.retain(|_, batch| {
+			let is_expired = batch.deadline <= now;
+			if is_expired {
+				expired.push(mem::take(&mut batch.payloads));
+			}
+			!is_expired
+		});
+		expired
+	}
+}
+
+impl<Key, Payload, WorkerData> BatcherShared<Key, Payload, WorkerData>
+where
+	Payload: Send + 'static,
+	WorkerData: Send + 'static,
+{
+	/// Enqueue the handling of the given batches on the pool.
+	fn dispatch(&self, batches: impl IntoIterator<Item = Vec<Payload>>) {
+		let Some(pool) = self.pool.upgrade() else {
+			debug!("pool is gone, discarding batches");
+			return;
+		};
+		let tasks = batches.into_iter().map(|payloads| {
+			let handler = self.handler.clone();
+			Task {
+				location: self.location,
+				..Task::new(move |worker_data: &mut WorkerData| handler(worker_data, payloads))
+			}
+		});
+		if !pool.on_worker_thread() {
+			pool.enqueue_tasks(tasks);
+			return;
+		}
+		// Waiting for room would keep the worker from making any.
+		for task in tasks {
+			if pool.admit(&task) {
+				pool.requeue(pool.propagators.wrap(task));
+			}
+		}
+	}
+
+	/// Dispatch the batches whose window is over, until the batcher is dropped.
+	fn flush_expired(this: &Weak<Self>) {
+		/// Upper bound on each sleep, so that the thread notices the batcher is dropped.
+		const TICK: Duration = Duration::from_millis(10);
+
+		// Only upgraded while awake, so that a sleeping flusher doesn't keep the batcher alive.
+		while let Some(shared) = this.upgrade() {
+			let mut state = shared.state.lock().unwrap();
+			if state.closed {
+				return;
+			}
+			let now = shared.clock.now();
+			let ready = state.take_expired(now);
+			if !ready.is_empty() {
+				state.dispatching = true;
+				drop(state);
+				shared.dispatch(ready);
+				shared.state.lock().unwrap().dispatching = false;
+				shared.condvar.notify_all();
+				continue;
+			}
+			let Some(deadline) = state.batches.values().map(|batch| batch.deadline).min() else {
+				drop(shared.condvar.wait(state).unwrap());
+				continue;
+			};
+			drop(state);
+			let clock = shared.clock.clone();
+			drop(shared);
+			clock.sleep(deadline.saturating_duration_since(now).min(TICK));
+		}
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Construct a [`KeyedBatcher`] whose batches are handled by `handler` on this pool.
+	///
+	/// # Panics
+	/// - if `max_len` is 0
+	/// - if the background thread couldn't be spawned and the
+	///   [`SpawnFailurePolicy`](crate::SpawnFailurePolicy) is
+	///   [`Panic`](crate::SpawnFailurePolicy::Panic)
+	#[track_caller]
+	pub fn keyed_batcher<Key, Payload>(
+		&self,
+		window: Duration,
+		max_len: usize,
+		handler: impl Fn(&mut WorkerData, Vec<Payload>) + Send + Sync + 'static,
+	) -> KeyedBatcher<Key, Payload, WorkerData>
+	where
+		Key: Eq + Hash + Send + 'static,
+		Payload: Send + 'static,
+	{
+		assert_ne!(max_len, 0, "max_len must be greater than 0");
+		let shared = Arc::new(BatcherShared {
+			pool: Arc::downgrade(&self.inner),
+			clock: self.inner.clock.clone(),
+			handler: Arc::new(handler),
+			location: Location::caller(),
+			window,
+			max_len,
+			state: Mutex::new(BatcherState {
+				batches: HashMap::new(),
+				closed: false,
+				dispatching: false,
+			}),
+			condvar: Condvar::new(),
+			flusher: AtomicBool::new(true),
+		});
+		let flusher = Arc::downgrade(&shared);
+		let spawned = self.inner.spawn_thread("batcher".to_string(), move || {
+			BatcherShared::flush_expired(&flusher);
+		});
+		if let Err(error) = spawned {
+			self.inner
+				.helper_spawn_failed("batcher", "flushing expired batches on push", &error);
+			shared.flusher.store(false, Ordering::Relaxed);
+		}
+		KeyedBatcher { shared }
+	}
+}
+
+impl<Key, Payload, WorkerData> KeyedBatcher<Key, Payload, WorkerData>
+where
+	Key: Eq + Hash + Send + 'static,
+	Payload: Send + 'static,
+	WorkerData: Send + 'static,
+{
+	/// Add a payload to the batch of the given key, starting a new batch if there's none.
+	/// Also hands the batches whose window is over to the pool, if the background thread
+	/// of the batcher couldn't be spawned.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking if the batch is full, in which case it waits for the task queue
+	/// to have at least one empty slot for it before returning. When called from a task of
+	/// the pool, the batch is queued regardless of the capacity of the queue instead, as
+	/// a worker waiting for room could keep the pool from making any.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn push(&self, key: Key, payload: Payload) {
+		let shared = &*self.shared;
+		let mut state = shared.state.lock().unwrap();
+		let full = match state.batches.entry(key) {
+			Entry::Occupied(mut entry) => {
+				entry.get_mut().payloads.push(payload);
+				(entry.get().payloads.len() >= shared.max_len).then(|| entry.remove().payloads)
+			}
+			Entry::Vacant(_) if shared.max_len == 1 => Some(vec![payload]),
+			Entry::Vacant(entry) => {
+				entry.insert(PendingBatch {
+					payloads: vec![payload],
+					deadline: shared.clock.now() + shared.window,
+				});
+				shared.condvar.notify_all();
+				None
+			}
+		};
+		let expired = if shared.flusher.load(Ordering::Relaxed) {
+			Vec::new()
+		} else {
+			state.take_expired(shared.clock.now())
+		};
+		drop(state);
+		if full.is_some() || !expired.is_empty() {
+			shared.dispatch(full.into_iter().chain(expired));
+		}
+	}
+
+	/// Hand all the pending batches to the pool without waiting for their window to be over.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have room for the batches.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn flush(&self) {
+		let batches = mem::take(&mut self.shared.state.lock().unwrap().batches);
+		self.shared
+			.dispatch(batches.into_values().map(|batch| batch.payloads));
+	}
+}
+
+impl<Key, Payload, WorkerData> Drop for KeyedBatcher<Key, Payload, WorkerData>
+where
+	Payload: Send + 'static,
+	WorkerData: Send + 'static,
+{
+	fn drop(&mut self) {
+		let mut state = self.shared.state.lock().unwrap();
+		state.closed = true;
+		let batches = mem::take(&mut state.batches);
+		self.shared.condvar.notify_all();
+		// Once dropped, every batch has been handed to the pool.
+		drop(
+			self.shared
+				.condvar
+				.wait_while(state, |state| state.dispatching)
+				.unwrap(),
+		);
+		self.shared
+			.dispatch(batches.into_values().map(|batch| batch.payloads));
+	}
+}
+
+impl<Key, Payload: Send + 'static, WorkerData: Send + 'static> fmt::Debug
+	for KeyedBatcher<Key, Payload, WorkerData>
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("KeyedBatcher")
+			.field("window", &self.shared.window)
+			.field("max_len", &self.shared.max_len)
+			.field("pending", &self.shared.state.lock().unwrap().batches.len())
+			.finish_non_exhaustive()
+	}
+}
@@ -0,0 +1,120 @@
+use super::{PoolEvent, PoolQueue, TaskMeta, ThreadPoolBuilder, ThreadPoolShared};
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Move the task with the given handle to the front of the queue, if it's still queued.
+	pub(crate) fn boost(&self, handle: u64) {
+		let mut guard = self.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			return;
+		};
+		let Some(task) = tasks.boost_handle(handle) else {
+			return;
+		};
+		debug!("task is being waited on, moving it to the front of the queue");
+		if self.listeners.is_empty() {
+			return;
+		}
+		let (name, meta) = (task.name.clone(), task.meta.clone());
+		drop(guard);
+		self.listeners.emit(PoolEvent::TaskBoosted {
+			name: name.as_deref(),
+			meta: meta.as_ref().map(TaskMeta::new),
+		});
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// When a thread starts waiting on a [`TaskHandle`](crate::TaskHandle) whose task is
+	/// still queued, move the task to the front of the queue, so that synchronous callers
+	/// aren't stuck behind a backlog of fire-and-forget tasks.
+	///
+	/// The task stays in its class, or in the inbox of its worker, and it only overtakes
+	/// the tasks of its class: classes are still served round-robin, and with
+	/// [`fair_producers`](ThreadPoolBuilder::fair_producers) it only overtakes the tasks of
+	/// its own producer. If the pool lends data to the callers, the waiting thread runs the
+	/// task itself instead whenever it can, see [`TaskHandle::wait`](crate::TaskHandle::wait).
+	/// Boosted tasks are reported to the [event listeners](Self::event_listener) as
+	/// [`PoolEvent::TaskBoosted`](crate::PoolEvent::TaskBoosted).
+	///
+	/// Ignored by scoped pools, and when combined with
+	/// [`strict_start_order`](ThreadPoolBuilder::strict_start_order).
+	///
+	/// Defaults to leaving waited-on tasks where they are.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{sync::mpsc, thread};
+	/// use lending_thread_pool::{PoolEvent, ThreadPool};
+	///
+	/// let (boosted, was_boosted) = mpsc::channel();
+	/// let pool = ThreadPool::builder(vec![(); 1])
+	/// 	.max_pending_tasks(64)
+	/// 	.boost_waited_tasks()
+	/// 	.event_listener(move |event: PoolEvent<'_>| {
+	/// 		if let PoolEvent::TaskBoosted { .. } = event {
+	/// 			boosted.send(()).unwrap();
+	/// 		}
+	/// 	})
+	/// 	.build();
+	///
+	/// // The only worker is busy until the urgent task is waited on.
+	/// let (unblock, blocked) = mpsc::channel::<()>();
+	/// pool.enqueue(move |()| blocked.recv().unwrap());
+	/// let (started, start_order) = mpsc::channel();
+	/// for _ in 0..32 {
+	/// 	let started = started.clone();
+	/// 	pool.enqueue(move |()| started.send("backlog").unwrap());
+	/// }
+	/// let urgent = pool.enqueue_with_handle(move |()| started.send("urgent").unwrap());
+	///
+	/// thread::scope(|scope| {
+	/// 	scope.spawn(|| urgent.wait());
+	/// 	was_boosted.recv().unwrap();
+	/// 	unblock.send(()).unwrap();
+	/// });
+	/// assert_eq!(start_order.recv().unwrap(), "urgent");
+	/// pool.join();
+	/// ```
+	///
+	/// Boosting a task doesn't let [`ThreadPool::flush`](crate::ThreadPool::flush) overlook
+	/// the tasks it overtook:
+	///
+	/// ```
+	/// use std::{
+	/// 	sync::{
+	/// 		atomic::{AtomicUsize, Ordering},
+	/// 		mpsc,
+	/// 	},
+	/// 	thread,
+	/// };
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 1])
+	/// 	.max_pending_tasks(8)
+	/// 	.boost_waited_tasks()
+	/// 	.build();
+	///
+	/// static DONE: AtomicUsize = AtomicUsize::new(0);
+	/// let (unblock, blocked) = mpsc::channel::<()>();
+	/// pool.enqueue(move |()| blocked.recv().unwrap());
+	/// for _ in 0..4 {
+	/// 	pool.enqueue(|()| drop(DONE.fetch_add(1, Ordering::Relaxed)));
+	/// }
+	///
+	/// thread::scope(|scope| {
+	/// 	let flusher = scope.spawn(|| {
+	/// 		pool.flush();
+	/// 		DONE.load(Ordering::Relaxed)
+	/// 	});
+	/// 	let urgent = pool.enqueue_with_handle(|()| {});
+	/// 	unblock.send(()).unwrap();
+	/// 	urgent.wait();
+	/// 	assert_eq!(flusher.join().unwrap(), 4);
+	/// });
+	/// ```
+	pub fn boost_waited_tasks(mut self) -> Self {
+		self.boost_waited = true;
+		self
+	}
+}
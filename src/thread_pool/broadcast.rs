@@ -0,0 +1,138 @@
+use std::{
+	panic::{self, AssertUnwindSafe},
+	sync::{mpsc, Arc, Barrier},
+};
+
+use super::{PoolQueue, Task, ThreadPool, WorkerState};
+
+/// Tells a closure run by [`ThreadPool::spawn_broadcast`] which worker it's running on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BroadcastContext {
+	index: usize,
+	num_workers: usize,
+}
+
+impl BroadcastContext {
+	/// The index of the worker running the closure.
+	#[must_use]
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	/// The number of workers running the closure, i.e. the workers of the pool that weren't
+	/// brought down by a panic.
+	#[must_use]
+	pub fn num_workers(&self) -> usize {
+		self.num_workers
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Run `f` on every worker at the same time, with its data, and return the results
+	/// in order of worker index once all of them have finished, e.g. to initialize some
+	/// per-worker state or to flush operations that must overlap in time.
+	///
+	/// Rather than going through the queue, `f` is handed to each worker directly, ahead of
+	/// the queued tasks. Each worker waits for the others to be ready before calling `f`,
+	/// so the calls overlap: `f` can synchronize the workers, e.g. with a [`Barrier`].
+	///
+	/// Workers brought down by a panic (see [`ThreadPool::dead_workers`]) are skipped, and so
+	/// are [dedicated workers](crate::ThreadPoolBuilder::dedicated_worker).
+	/// The calls don't count against the [`ThreadBudget`](crate::ThreadBudget) of the pool,
+	/// if any. An [inline](ThreadPool::is_inline) pool calls `f` once, on the calling thread.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for every worker to finish its current task, then
+	/// for `f` to complete on all of them. Calling it from a task running on the same pool
+	/// results in a deadlock.
+	///
+	/// # Panics
+	/// - if `f` panics on any worker, once all of them have finished
+	/// - if the pool is shut down while `f` is pending
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::Barrier;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![Vec::<u32>::new(); 4]);
+	///
+	/// let barrier = Barrier::new(4);
+	/// let indices = pool.spawn_broadcast(move |ctx, buffer| {
+	/// 	buffer.push(ctx.index() as u32);
+	/// 	// Would deadlock if the calls didn't overlap.
+	/// 	barrier.wait();
+	/// 	ctx.index()
+	/// });
+	///
+	/// assert_eq!(indices, [0, 1, 2, 3]);
+	/// ```
+	pub fn spawn_broadcast<R: Send + 'static>(
+		&self,
+		f: impl Fn(BroadcastContext, &mut WorkerData) -> R + Send + Sync + 'static,
+	) -> Vec<R> {
+		if self.is_inline() {
+			let ctx = BroadcastContext {
+				index: 0,
+				num_workers: 1,
+			};
+			return vec![self.install(move |worker_data| f(ctx, worker_data))];
+		}
+
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			debug!("pool is shut down, skipping broadcast");
+			return Vec::new();
+		};
+		let alive = self
+			.inner
+			.workers()
+			.iter()
+			.enumerate()
+			.filter(|&(i, worker)| {
+				!self.inner.is_dedicated(i)
+					&& !matches!(*worker.state.lock().unwrap(), WorkerState::Panicked { .. })
+			})
+			.map(|(i, _)| i)
+			.collect::<Vec<_>>();
+		let f = Arc::new(f);
+		let ready = Arc::new(Barrier::new(alive.len()));
+		let (sender, receiver) = mpsc::channel();
+		for &worker in &alive {
+			let ctx = BroadcastContext {
+				index: worker,
+				num_workers: alive.len(),
+			};
+			let (f, ready, sender) = (f.clone(), ready.clone(), sender.clone());
+			tasks.push_to_worker(
+				worker,
+				Task {
+					budgeted: false,
+					..Task::new(move |worker_data: &mut WorkerData| {
+						ready.wait();
+						let result = panic::catch_unwind(AssertUnwindSafe(|| f(ctx, worker_data)));
+						let _ = sender.send((worker, result));
+					})
+				},
+			);
+		}
+		drop(guard);
+		drop(sender);
+		self.inner.wake_all_workers();
+		debug!("broadcasting to the workers", workers = alive.len());
+
+		let mut results = receiver.iter().collect::<Vec<_>>();
+		assert_eq!(
+			results.len(),
+			alive.len(),
+			"pool to run the broadcast on every worker"
+		);
+		results.sort_by_key(|(worker, _)| *worker);
+		results
+			.into_iter()
+			.map(|(_, result)| result.unwrap_or_else(|payload| panic::resume_unwind(payload)))
+			.collect()
+	}
+}
@@ -0,0 +1,97 @@
+use std::sync::{Arc, Condvar, Mutex};
+
+/// A cap on the number of workers that can run tasks at the same time, shared
+/// across multiple pools.
+///
+/// Each pool configured with a budget (see [`ThreadPoolBuilder::thread_budget`](crate::ThreadPoolBuilder::thread_budget))
+/// acquires a slot before running a task and releases it right after, so that the total number of
+/// simultaneously active workers never exceeds the budget, no matter how many workers each pool owns.
+/// Idle workers don't consume any slot.
+///
+/// Cloning a `ThreadBudget` yields a handle to the same budget.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::{ThreadBudget, ThreadPool};
+///
+/// let budget = ThreadBudget::new(4);
+///
+/// let io_pool = ThreadPool::builder(vec![(); 4])
+/// 	.thread_budget(&budget)
+/// 	.build();
+/// let cpu_pool = ThreadPool::builder(vec![(); 4])
+/// 	.thread_budget(&budget)
+/// 	.build();
+///
+/// for _ in 0..16 {
+/// 	io_pool.enqueue(|_| {});
+/// 	cpu_pool.enqueue(|_| {});
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThreadBudget {
+	inner: Arc<ThreadBudgetShared>,
+}
+
+#[derive(Debug)]
+struct ThreadBudgetShared {
+	max_active: usize,
+	active: Mutex<usize>,
+	condvar: Condvar,
+}
+
+impl ThreadBudget {
+	/// Construct a budget allowing at most `max_active` workers to run tasks at the same time.
+	///
+	/// # Panics
+	/// - if `max_active` is 0.
+	#[must_use]
+	pub fn new(max_active: usize) -> Self {
+		assert_ne!(max_active, 0, "max_active must be greater than 0");
+		Self {
+			inner: Arc::new(ThreadBudgetShared {
+				max_active,
+				active: Mutex::new(0),
+				condvar: Condvar::default(),
+			}),
+		}
+	}
+
+	/// The maximum number of workers that can be active at the same time.
+	#[must_use]
+	pub fn max_active(&self) -> usize {
+		self.inner.max_active
+	}
+
+	/// The number of workers currently running a task under this budget.
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn active(&self) -> usize {
+		*self.inner.active.lock().unwrap()
+	}
+
+	/// Block until a slot is available, then take it. The slot is released when the returned
+	/// permit is dropped.
+	pub(crate) fn acquire(&self) -> BudgetPermit<'_> {
+		let mut active = self.inner.active.lock().unwrap();
+		while *active >= self.inner.max_active {
+			active = self.inner.condvar.wait(active).unwrap();
+		}
+		*active += 1;
+		BudgetPermit { budget: self }
+	}
+}
+
+pub(crate) struct BudgetPermit<'a> {
+	budget: &'a ThreadBudget,
+}
+
+impl Drop for BudgetPermit<'_> {
+	fn drop(&mut self) {
+		let mut active = self.budget.inner.active.lock().unwrap();
+		*active -= 1;
+		drop(active);
+		self.budget.inner.condvar.notify_one();
+	}
+}
@@ -0,0 +1,653 @@
+use std::{
+	borrow::Cow,
+	sync::{mpsc::Receiver, Arc},
+};
+
+use super::{
+	admission::Admission,
+	capability::WorkerCapabilities,
+	dedicated::DedicatedWorker,
+	events::Listeners,
+	hung::HungWorkers,
+	label::WorkerLabel,
+	panic_hook::PanicHook,
+	propagate::Propagators,
+	spawner::Unscoped,
+	warmup::Warmup,
+	worker::{Respawn, WorkerRecycling},
+	BoxedTask, Clock, DeadWorkerPolicy, IdleWait, IdleWorkerPolicy, Park, PoolEventListener,
+	QosClass, SpawnFailurePolicy, SystemClock, TaskClass, ThreadBudget, ThreadPool, ThreadSpawner,
+};
+
+/// A builder for [`ThreadPool`], useful when the defaults provided by
+/// [`ThreadPool::new`] don't fit your use case. This can be constructed using
+/// the [`ThreadPool::builder`] method.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::ThreadPool;
+///
+/// let pool = ThreadPool::builder(
+/// 	(0..4)
+/// 		.map(|i| format!("Hello from worker {i}"))
+/// 		.collect::<Vec<_>>(),
+/// )
+/// .max_pending_tasks(16)
+/// .build();
+///
+/// for _ in 0..16 {
+/// 	pool.enqueue(|greeting| { println!("{greeting}"); });
+/// }
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct ThreadPoolBuilder<WorkerData: Send> {
+	pub(crate) workers_data: Vec<WorkerData>,
+	pub(crate) max_pending_tasks: usize,
+	pub(crate) max_pending_bytes: Option<usize>,
+	pub(crate) task_classes: Vec<TaskClass>,
+	pub(crate) worker_groups: Vec<WorkerGroup>,
+	pub(crate) dedicated_workers: Vec<DedicatedWorker<WorkerData>>,
+	pub(crate) fair_producers: bool,
+	pub(crate) caller_runs: Option<WorkerData>,
+	pub(crate) segment_capacity: Option<usize>,
+	pub(crate) boost_waited: bool,
+	pub(crate) thread_budget: Option<ThreadBudget>,
+	pub(crate) listeners: Listeners,
+	pub(crate) propagators: Propagators,
+	pub(crate) task_source: Option<Receiver<BoxedTask<WorkerData>>>,
+	pub(crate) recycling: Option<WorkerRecycling<WorkerData>>,
+	pub(crate) strict_start_order: bool,
+	pub(crate) task_history: usize,
+	pub(crate) worker_label: Option<WorkerLabel<WorkerData>>,
+	pub(crate) worker_capabilities: Option<WorkerCapabilities<WorkerData>>,
+	pub(crate) admission: Option<Admission>,
+	pub(crate) idle_worker_policy: IdleWorkerPolicy,
+	pub(crate) idle_wait: Box<dyn IdleWait>,
+	pub(crate) warmup: Option<Warmup<WorkerData>>,
+	pub(crate) dead_worker_policy: DeadWorkerPolicy,
+	pub(crate) spawn_failure_policy: SpawnFailurePolicy,
+	pub(crate) respawn: Option<Respawn<WorkerData>>,
+	pub(crate) panic_hook: Option<PanicHook>,
+	pub(crate) hung_workers: Option<HungWorkers<WorkerData>>,
+	pub(crate) qos_class: Option<QosClass>,
+	pub(crate) thread_spawner: Option<Box<dyn ThreadSpawner>>,
+	pub(crate) clock: Arc<dyn Clock>,
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	pub(crate) fn new(workers_data: Vec<WorkerData>) -> Self {
+		let max_pending_tasks = workers_data.len();
+		Self {
+			workers_data,
+			max_pending_tasks,
+			max_pending_bytes: None,
+			task_classes: Vec::new(),
+			worker_groups: Vec::new(),
+			dedicated_workers: Vec::new(),
+			fair_producers: false,
+			caller_runs: None,
+			segment_capacity: None,
+			boost_waited: false,
+			thread_budget: None,
+			listeners: Listeners::default(),
+			propagators: Propagators::default(),
+			task_source: None,
+			recycling: None,
+			strict_start_order: false,
+			task_history: 0,
+			worker_label: None,
+			worker_capabilities: None,
+			admission: None,
+			idle_worker_policy: IdleWorkerPolicy::Any,
+			idle_wait: Box::new(Park),
+			warmup: None,
+			dead_worker_policy: DeadWorkerPolicy::Ignore,
+			spawn_failure_policy: SpawnFailurePolicy::Panic,
+			respawn: None,
+			panic_hook: None,
+			hung_workers: None,
+			qos_class: None,
+			thread_spawner: None,
+			clock: Arc::new(SystemClock),
+		}
+	}
+
+	/// Set the maximum number of pending tasks, i.e. the queue size.
+	/// Defaults to the number of workers.
+	///
+	/// When task classes are registered, this only applies to the tasks
+	/// that are not enqueued in any class.
+	pub fn max_pending_tasks(mut self, max_pending_tasks: usize) -> Self {
+		self.max_pending_tasks = max_pending_tasks;
+		self
+	}
+
+	/// Additionally bound the queue by the approximate memory held by the pending tasks.
+	/// Task sizes are estimated at enqueue time, see [`ThreadPool::enqueue_sized`].
+	/// A single task exceeding the budget is still accepted when the queue is empty.
+	///
+	/// Defaults to no limit.
+	pub fn max_pending_bytes(mut self, max_pending_bytes: usize) -> Self {
+		self.max_pending_bytes = Some(max_pending_bytes);
+		self
+	}
+
+	/// Register a [`TaskClass`], with its own sub-queue and worker share.
+	pub fn task_class(mut self, class: TaskClass) -> Self {
+		self.task_classes.push(class);
+		self
+	}
+
+	/// Dedicate the next `workers` workers (in the order of the `WorkerData` Vec) to a named group,
+	/// whose tasks are enqueued with [`ThreadPool::enqueue_to`]. Workers in a group only run the
+	/// tasks of their group, isolating it from the rest of the pool, while tasks enqueued
+	/// otherwise are run by the workers outside of any group (or by all of them, if there are none).
+	///
+	/// Each group has its own queue, sized after the number of its workers.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder((0..12).collect::<Vec<_>>())
+	/// 	.worker_group("io", 4)
+	/// 	.worker_group("cpu", 8)
+	/// 	.build();
+	///
+	/// pool.enqueue_to("io", |&mut i| assert!(i < 4));
+	/// pool.enqueue_to("cpu", |&mut i| assert!(i >= 4));
+	/// ```
+	pub fn worker_group(mut self, name: impl Into<Cow<'static, str>>, workers: usize) -> Self {
+		self.worker_groups.push(WorkerGroup {
+			name: name.into(),
+			workers,
+		});
+		self
+	}
+
+	/// Interleave the tasks of different producers, i.e. [`TaskSender`](crate::TaskSender)s,
+	/// rather than dispatching them in strict FIFO order, so that a chatty producer can't
+	/// monopolize the workers. Producers are served round-robin, each dispatching up to
+	/// as many tasks as its weight per turn. Tasks enqueued directly on the pool count
+	/// as a producer of weight 1.
+	///
+	/// Defaults to FIFO.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 4])
+	/// 	.max_pending_tasks(64)
+	/// 	.fair_producers()
+	/// 	.build();
+	///
+	/// let bulk = pool.sender();
+	/// let interactive = pool.sender_with_weight(4);
+	/// for i in 0..32 {
+	/// 	bulk.enqueue(move |()| println!("bulk task {i}"));
+	/// }
+	/// interactive.enqueue(|()| println!("not stuck behind the bulk tasks"));
+	/// ```
+	pub fn fair_producers(mut self) -> Self {
+		self.fair_producers = true;
+		self
+	}
+
+	/// Share a [`ThreadBudget`] with other pools, capping the number of workers
+	/// that can run tasks at the same time across all of them.
+	pub fn thread_budget(mut self, budget: &ThreadBudget) -> Self {
+		self.thread_budget = Some(budget.clone());
+		self
+	}
+
+	/// Register a [`PoolEventListener`], notified of worker and task lifecycle events.
+	/// Can be called multiple times to register multiple listeners.
+	pub fn event_listener(mut self, listener: impl PoolEventListener + 'static) -> Self {
+		self.listeners.push(Box::new(listener));
+		self
+	}
+
+	/// Also run the tasks received from an existing channel, for when the queue is shared
+	/// with other components. Tasks are moved from the channel to the queue of the pool
+	/// as room becomes available, so the channel keeps buffering the backlog.
+	///
+	/// Joining the pool waits for all the senders to be dropped. Tasks still in the channel
+	/// when the pool is shut down are left there.
+	///
+	/// If the thread moving the tasks over can't be spawned and the pool
+	/// [degrades](SpawnFailurePolicy::Degrade), the tasks stay in the channel until the pool
+	/// is joined, which then moves them over on the joining thread. They're left there by
+	/// [`ThreadPool::join_async`](crate::ThreadPool::join_async), which doesn't block.
+	///
+	/// Defaults to no external source.
+	pub fn task_source(mut self, receiver: Receiver<BoxedTask<WorkerData>>) -> Self {
+		self.task_source = Some(receiver);
+		self
+	}
+
+	/// Rebuild the data of each worker with `factory` after it has run `max_tasks` tasks,
+	/// guarding against slow leaks in long-lived worker state. The factory is called
+	/// on the worker thread with the worker index, and the old data is dropped there.
+	///
+	/// Defaults to never rebuilding worker data.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![Vec::new(); 2])
+	/// 	.max_tasks_per_worker(100, |_| Vec::new())
+	/// 	.build();
+	///
+	/// for i in 0..1000 {
+	/// 	pool.enqueue(move |cache: &mut Vec<i32>| {
+	/// 		cache.push(i);
+	/// 		assert!(cache.len() <= 100);
+	/// 	});
+	/// }
+	/// ```
+	pub fn max_tasks_per_worker(
+		mut self,
+		max_tasks: usize,
+		factory: impl Fn(usize) -> WorkerData + Send + Sync + 'static,
+	) -> Self {
+		self.recycling = Some(WorkerRecycling {
+			max_tasks,
+			factory: Box::new(factory),
+		});
+		self
+	}
+
+	/// Guarantee that tasks start in the order they were enqueued: a worker that picked up
+	/// a task waits for the workers that picked up earlier tasks to start running them, instead
+	/// of possibly overtaking them when woken up first. The tasks targeting a specific worker,
+	/// e.g. the ones of [`ThreadPool::replace_worker_data`], also take their turn.
+	///
+	/// Once started, tasks run concurrently as usual, so this doesn't order their completion,
+	/// nor what they do from the start.
+	///
+	/// Defaults to FIFO dequeueing, without ordering guarantees between workers.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 4])
+	/// 	.max_pending_tasks(64)
+	/// 	.strict_start_order()
+	/// 	.build();
+	///
+	/// for i in 0..64 {
+	/// 	pool.enqueue(move |()| println!("task {i} started after tasks 0..{i}"));
+	/// }
+	/// ```
+	pub fn strict_start_order(mut self) -> Self {
+		self.strict_start_order = true;
+		self
+	}
+
+	/// Keep track of the last `len` tasks run by each worker, with their name, duration and
+	/// outcome, as reported in [`PoolStatus::recent_tasks`](crate::PoolStatus::recent_tasks),
+	/// e.g. to see what a misbehaving worker recently executed.
+	///
+	/// Defaults to 0, i.e. no history.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::{TaskOutcome, ThreadPool};
+	///
+	/// let pool = ThreadPool::builder(vec![(); 1])
+	/// 	.task_history(2)
+	/// 	.build();
+	///
+	/// for name in ["first", "second", "third"] {
+	/// 	pool.enqueue_named(name, |()| {});
+	/// }
+	/// pool.flush();
+	///
+	/// let recent = &pool.status().recent_tasks[0];
+	/// assert_eq!(recent.len(), 2);
+	/// assert_eq!(recent[1].name.as_deref(), Some("third"));
+	/// assert_eq!(recent[1].outcome, TaskOutcome::Completed);
+	/// ```
+	pub fn task_history(mut self, len: usize) -> Self {
+		self.task_history = len;
+		self
+	}
+
+	/// Choose which worker runs a newly enqueued task when several are idle: the most recently
+	/// idle one to benefit from warm caches, the one that ran the fewest tasks, or each in turn
+	/// to even out the wear of the resources they own. The task is handed to the chosen worker
+	/// as long as no task of the same class is queued ahead of it, otherwise workers pick up
+	/// tasks as they become available.
+	///
+	/// Any policy other than [`IdleWorkerPolicy::Any`] wakes up all idle workers for each task,
+	/// for the chosen one to notice, which adds overhead to pools with many workers.
+	///
+	/// Defaults to [`IdleWorkerPolicy::Any`], i.e. whichever worker wakes up first.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::{IdleWorkerPolicy, ThreadPool};
+	///
+	/// let pool = ThreadPool::builder((0..4).collect::<Vec<_>>())
+	/// 	.idle_worker_policy(IdleWorkerPolicy::RoundRobin)
+	/// 	.build();
+	///
+	/// for _ in 0..16 {
+	/// 	pool.enqueue(|i| println!("Hello from worker {i}"));
+	/// }
+	/// ```
+	pub fn idle_worker_policy(mut self, policy: IdleWorkerPolicy) -> Self {
+		self.idle_worker_policy = policy;
+		self
+	}
+
+	/// Set how workers wait for tasks once they've found the queue empty, see [`IdleWait`]
+	/// for the available strategies.
+	///
+	/// Defaults to [`Park`], i.e. blocking until a task is enqueued.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::time::Duration;
+	/// use lending_thread_pool::{SleepBackoff, ThreadPool};
+	///
+	/// let pool = ThreadPool::builder(vec![(); 2])
+	/// 	.idle_wait(SleepBackoff::new(Duration::from_micros(50), Duration::from_millis(5)))
+	/// 	.build();
+	///
+	/// pool.enqueue(|()| println!("picked up within 5ms"));
+	/// ```
+	pub fn idle_wait(mut self, idle_wait: impl IdleWait + 'static) -> Self {
+		self.idle_wait = Box::new(idle_wait);
+		self
+	}
+
+	/// Run `warmup` on each worker, with its index and data, right after it's spawned:
+	/// building the pool blocks until every worker is done, so that lazy initialization
+	/// (e.g. connection handshakes or cache priming) doesn't add to the latency
+	/// of the first tasks.
+	///
+	/// A panicking warmup brings its worker down, just like a panicking task.
+	///
+	/// Defaults to no warmup.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![Vec::new(); 4])
+	/// 	.warmup(|_, cache: &mut Vec<u64>| cache.extend((0..1024).map(|i| i * i)))
+	/// 	.build();
+	///
+	/// pool.enqueue(|cache| assert_eq!(cache.len(), 1024));
+	/// ```
+	pub fn warmup(
+		mut self,
+		warmup: impl Fn(usize, &mut WorkerData) + Send + Sync + 'static,
+	) -> Self {
+		self.warmup = Some(Warmup::new(warmup));
+		self
+	}
+
+	/// Choose what happens to the tasks enqueued once a worker has been brought down
+	/// by a panic, rather than silently queueing tasks that may never run
+	/// if no worker is left. Doesn't apply to the tasks already queued.
+	///
+	/// Defaults to [`DeadWorkerPolicy::Ignore`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{panic, thread::sleep, time::Duration};
+	/// use lending_thread_pool::{DeadWorkerPolicy, ThreadPool};
+	///
+	/// let pool = ThreadPool::builder(vec![(); 1])
+	/// 	.dead_worker_policy(DeadWorkerPolicy::Panic)
+	/// 	.build();
+	///
+	/// pool.enqueue(|()| panic!("bringing the only worker down"));
+	/// sleep(Duration::from_millis(100));
+	///
+	/// assert!(panic::catch_unwind(panic::AssertUnwindSafe(|| pool.enqueue(|()| {}))).is_err());
+	/// # let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || pool.join()));
+	/// ```
+	pub fn dead_worker_policy(mut self, policy: DeadWorkerPolicy) -> Self {
+		self.dead_worker_policy = policy;
+		self
+	}
+
+	/// Replace the workers brought down by a panic, running the following tasks on the same
+	/// thread with data rebuilt by `factory`, which is called with the worker index. The tasks
+	/// that targeted the dead worker are discarded, and the warmup isn't run again.
+	///
+	/// Respawned workers are not reported as dead nor by [`ThreadPool::join_report`],
+	/// the panics are only logged and reported to the
+	/// [`PoolEventListener`](crate::PoolEventListener)s.
+	///
+	/// Defaults to leaving dead workers down.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{thread::sleep, time::Duration};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![0; 1])
+	/// 	.respawn_dead_workers(|_| 0)
+	/// 	.build();
+	///
+	/// pool.enqueue(|_| panic!("bringing the only worker down"));
+	/// sleep(Duration::from_millis(100));
+	///
+	/// assert!(pool.healthy());
+	/// pool.enqueue(|count| *count += 1);
+	/// pool.join();
+	/// ```
+	pub fn respawn_dead_workers(
+		mut self,
+		factory: impl Fn(usize) -> WorkerData + Send + Sync + 'static,
+	) -> Self {
+		self.respawn = Some(Respawn {
+			factory: Box::new(factory),
+		});
+		self
+	}
+
+	/// Run the workers with the given quality of service class on macOS and iOS, where the scheduler relies
+	/// on it rather than on thread priorities. Only has an effect with the `qos` feature
+	/// enabled, on Apple platforms.
+	///
+	/// Defaults to the quality of service class inherited from the thread building the pool.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::{QosClass, ThreadPool};
+	///
+	/// let pool = ThreadPool::builder(vec![(); 2])
+	/// 	.qos_class(QosClass::Background)
+	/// 	.build();
+	///
+	/// pool.enqueue(|()| println!("running in the background"));
+	/// ```
+	pub fn qos_class(mut self, class: QosClass) -> Self {
+		self.qos_class = Some(class);
+		self
+	}
+
+	/// Create the threads of the pool, i.e. the workers, including the ones added by
+	/// [`ThreadPool::grow`], and the feeder of a
+	/// [`task_source`](Self::task_source), with the given [`ThreadSpawner`] instead of
+	/// [`std::thread`], e.g. on embedded targets. Not used by [`build_scoped`](Self::build_scoped),
+	/// which spawns the threads in its scope.
+	pub fn thread_spawner(mut self, spawner: impl ThreadSpawner + 'static) -> Self {
+		self.thread_spawner = Some(Box::new(spawner));
+		self
+	}
+
+	/// Use the given [`Clock`] to schedule delayed work and measure the uptime of the pool,
+	/// e.g. a [`ManualClock`](crate::ManualClock) in tests. Defaults to [`SystemClock`].
+	pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+		self.clock = Arc::new(clock);
+		self
+	}
+
+	/// Spawn the worker threads and construct the pool.
+	///
+	/// # Panics
+	/// - if the Vec of `WorkerData` is empty
+	/// - if `max_pending_tasks` is 0
+	/// - if a task class has `max_pending_tasks` or `max_workers` set to 0, or is registered twice.
+	/// - if `max_tasks_per_worker` was set to 0
+	/// - if the threshold of `replace_hung_workers` is 0
+	/// - if a worker group is empty or registered twice, or worker groups add up to more
+	///   workers than there are
+	/// - if a dedicated worker is out of bounds, registered twice or in a worker group,
+	///   or if every worker is dedicated
+	/// - if `strict_start_order` is combined with task classes, worker groups,
+	///   `fair_producers` or `worker_capabilities`, which dispatch tasks out of their
+	///   enqueue order
+	/// - if the workers have more than 64 distinct capabilities overall
+	/// - if a thread couldn't be spawned
+	#[must_use]
+	pub fn build(mut self) -> ThreadPool<WorkerData>
+	where
+		WorkerData: 'static,
+	{
+		self.validate();
+		let pool = match self.thread_spawner.take() {
+			Some(spawner) => {
+				let spawner = Arc::<dyn ThreadSpawner>::from(spawner);
+				let pool = ThreadPool::spawn(self, &spawner);
+				let _ = pool.inner.thread_spawner.set(spawner);
+				pool
+			}
+			None => ThreadPool::spawn(self, &Unscoped),
+		};
+		pool.inner.enable_on_wait();
+		pool
+	}
+
+	pub(crate) fn validate(&self) {
+		assert_ne!(
+			self.workers_data.len(),
+			0,
+			"workers_data must contain at least one item"
+		);
+		assert_ne!(
+			self.max_pending_tasks, 0,
+			"max_pending_tasks must be greater than 0"
+		);
+		assert_ne!(
+			self.segment_capacity,
+			Some(0),
+			"the capacity of sender segments must be greater than 0"
+		);
+		for (i, class) in self.task_classes.iter().enumerate() {
+			assert_ne!(
+				class.max_pending_tasks, 0,
+				"max_pending_tasks of task class {:?} must be greater than 0",
+				class.name
+			);
+			assert_ne!(
+				class.max_workers,
+				Some(0),
+				"max_workers of task class {:?} must be greater than 0",
+				class.name
+			);
+			assert!(
+				self.task_classes[..i]
+					.iter()
+					.all(|other| other.name != class.name),
+				"task class {:?} registered more than once",
+				class.name
+			);
+		}
+
+		for (i, group) in self.worker_groups.iter().enumerate() {
+			assert_ne!(
+				group.workers, 0,
+				"worker group {:?} must have at least one worker",
+				group.name
+			);
+			assert!(
+				self.worker_groups[..i]
+					.iter()
+					.all(|other| other.name != group.name),
+				"worker group {:?} registered more than once",
+				group.name
+			);
+		}
+		assert!(
+			self.worker_groups
+				.iter()
+				.map(|group| group.workers)
+				.sum::<usize>()
+				<= self.workers_data.len(),
+			"worker groups must not add up to more workers than there are"
+		);
+		let grouped = self
+			.worker_groups
+			.iter()
+			.map(|group| group.workers)
+			.sum::<usize>();
+		for (i, dedicated) in self.dedicated_workers.iter().enumerate() {
+			assert!(
+				(grouped..self.workers_data.len()).contains(&dedicated.index),
+				"dedicated worker {} must exist and not belong to a worker group",
+				dedicated.index
+			);
+			assert!(
+				self.dedicated_workers[..i]
+					.iter()
+					.all(|other| other.index != dedicated.index),
+				"worker {} dedicated more than once",
+				dedicated.index
+			);
+		}
+		assert!(
+			self.dedicated_workers.len() < self.workers_data.len(),
+			"at least one worker must not be dedicated"
+		);
+		assert!(
+			self.recycling
+				.as_ref()
+				.is_none_or(|recycling| recycling.max_tasks != 0),
+			"max_tasks_per_worker must be greater than 0"
+		);
+		assert!(
+			self.hung_workers
+				.as_ref()
+				.is_none_or(|hung_workers| !hung_workers.threshold.is_zero()),
+			"the hung worker threshold must be greater than 0"
+		);
+		assert!(
+			!self.strict_start_order
+				|| (self.task_classes.is_empty()
+					&& self.worker_groups.is_empty()
+					&& !self.fair_producers
+					&& self.worker_capabilities.is_none()),
+			"strict_start_order can't be combined with task classes, worker groups, fair_producers or worker_capabilities"
+		);
+	}
+}
+
+/// A named set of workers, see [`ThreadPoolBuilder::worker_group`].
+#[derive(Debug, Clone)]
+pub(crate) struct WorkerGroup {
+	pub(crate) name: Cow<'static, str>,
+	pub(crate) workers: usize,
+}
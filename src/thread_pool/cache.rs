@@ -0,0 +1,245 @@
+use std::{
+	borrow::Borrow,
+	collections::HashMap,
+	fmt,
+	hash::Hash,
+	sync::{Arc, Condvar, Mutex},
+	time::{Duration, Instant},
+};
+
+use super::ThreadPool;
+
+/// Remembers the results of keyed tasks run with [`ThreadPool::install_cached`], so that
+/// running the task of a key again returns the remembered value instead of recomputing it.
+///
+/// Results can expire after a time to live, as measured by the [`Clock`](crate::Clock) of the
+/// pool, and can be invalidated explicitly.
+///
+/// Cloning a `ResultCache` yields a handle to the same cache.
+///
+/// # Examples
+///
+/// ```
+/// use std::{
+/// 	sync::atomic::{AtomicUsize, Ordering},
+/// 	time::Duration,
+/// };
+/// use lending_thread_pool::{ResultCache, ThreadPool};
+///
+/// static RUNS: AtomicUsize = AtomicUsize::new(0);
+///
+/// let pool = ThreadPool::new(vec![(); 2]);
+/// let cache = ResultCache::new().time_to_live(Duration::from_secs(60));
+///
+/// let square = |n: u64| move |_: &mut ()| {
+/// 	RUNS.fetch_add(1, Ordering::Relaxed);
+/// 	n * n
+/// };
+/// assert_eq!(pool.install_cached(&cache, 12, square(12)), 144);
+/// assert_eq!(pool.install_cached(&cache, 12, square(12)), 144);
+/// assert_eq!(RUNS.load(Ordering::Relaxed), 1);
+///
+/// cache.invalidate(&12);
+/// assert_eq!(pool.install_cached(&cache, 12, square(12)), 144);
+/// assert_eq!(RUNS.load(Ordering::Relaxed), 2);
+/// ```
+pub struct ResultCache<Key, Value> {
+	shared: Arc<CacheShared<Key, Value>>,
+	time_to_live: Option<Duration>,
+}
+
+struct CacheShared<Key, Value> {
+	entries: Mutex<CacheEntries<Key, Value>>,
+	/// Notified when a value stops being computed.
+	condvar: Condvar,
+}
+
+struct CacheEntries<Key, Value> {
+	entries: HashMap<Key, CacheEntry<Value>>,
+	/// Identifies each computation, so that an invalidated one doesn't store its result.
+	next_computation: u64,
+}
+
+enum CacheEntry<Value> {
+	Computing(u64),
+	Ready { value: Value, computed_at: Instant },
+}
+
+impl<Key: Eq + Hash, Value> ResultCache<Key, Value> {
+	/// Construct an empty cache whose results never expire.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			shared: Arc::new(CacheShared {
+				entries: Mutex::new(CacheEntries {
+					entries: HashMap::new(),
+					next_computation: 0,
+				}),
+				condvar: Condvar::new(),
+			}),
+			time_to_live: None,
+		}
+	}
+
+	/// Make results expire once they're older than `time_to_live`, to be recomputed
+	/// the next time they're requested.
+	#[must_use]
+	pub fn time_to_live(mut self, time_to_live: Duration) -> Self {
+		self.time_to_live = Some(time_to_live);
+		self
+	}
+
+	fn is_expired(&self, computed_at: Instant, now: Instant) -> bool {
+		matches!(
+			self.time_to_live,
+			Some(time_to_live) if now.saturating_duration_since(computed_at) >= time_to_live
+		)
+	}
+
+	/// Forget the result of the given key, if any. A computation of the key that's
+	/// in progress still returns its result to the callers waiting for it, without caching it.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn invalidate<Q>(&self, key: &Q)
+	where
+		Key: Borrow<Q>,
+		Q: Eq + Hash + ?Sized,
+	{
+		self.shared.entries.lock().unwrap().entries.remove(key);
+		self.shared.condvar.notify_all();
+	}
+
+	/// Forget all the results.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn clear(&self) {
+		self.shared.entries.lock().unwrap().entries.clear();
+		self.shared.condvar.notify_all();
+	}
+
+	/// The number of keys with a result, or whose result is being computed.
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.shared.entries.lock().unwrap().entries.len()
+	}
+
+	/// Whether the cache holds no result.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl<Key: Eq + Hash, Value> Default for ResultCache<Key, Value> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Key, Value> Clone for ResultCache<Key, Value> {
+	fn clone(&self) -> Self {
+		Self {
+			shared: self.shared.clone(),
+			time_to_live: self.time_to_live,
+		}
+	}
+}
+
+impl<Key, Value> fmt::Debug for ResultCache<Key, Value> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ResultCache")
+			.field("len", &self.shared.entries.lock().unwrap().entries.len())
+			.field("time_to_live", &self.time_to_live)
+			.finish()
+	}
+}
+
+/// Removes the entry of a computation when dropped, e.g. because the task panicked,
+/// so that the callers waiting for it compute the value themselves.
+struct Computation<'cache, Key: Eq + Hash, Value> {
+	shared: &'cache CacheShared<Key, Value>,
+	key: Key,
+	id: u64,
+}
+
+impl<Key: Eq + Hash, Value> Computation<'_, Key, Value> {
+	fn is_current(entries: &CacheEntries<Key, Value>, key: &Key, id: u64) -> bool {
+		matches!(entries.entries.get(key), Some(CacheEntry::Computing(current)) if *current == id)
+	}
+}
+
+impl<Key: Eq + Hash, Value> Drop for Computation<'_, Key, Value> {
+	fn drop(&mut self) {
+		let mut entries = self.shared.entries.lock().unwrap();
+		if Self::is_current(&entries, &self.key, self.id) {
+			entries.entries.remove(&self.key);
+		}
+		drop(entries);
+		self.shared.condvar.notify_all();
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Like [`ThreadPool::install`], but returns the result cached for `key` when there is one,
+	/// only running `f` when there isn't or it has expired. Callers requesting a key that's
+	/// being computed wait for its result rather than computing it again.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have room, then for `f`
+	/// to complete, or for the result of the key to be computed by another caller.
+	/// Calling it from a task running on the same pool may result in a deadlock.
+	///
+	/// # Panics
+	/// - if `f` panics, in which case nothing is cached
+	/// - if the pool has been shut down, as `f` is discarded without running
+	#[track_caller]
+	pub fn install_cached<Key, Value>(
+		&self,
+		cache: &ResultCache<Key, Value>,
+		key: Key,
+		f: impl FnOnce(&mut WorkerData) -> Value + Send + 'static,
+	) -> Value
+	where
+		Key: Eq + Hash + Clone,
+		Value: Clone + Send + 'static,
+	{
+		let shared = &*cache.shared;
+		let mut entries = shared.entries.lock().unwrap();
+		loop {
+			match entries.entries.get(&key) {
+				Some(CacheEntry::Ready { value, computed_at })
+					if !cache.is_expired(*computed_at, self.inner.clock.now()) =>
+				{
+					return value.clone();
+				}
+				Some(CacheEntry::Computing(_)) => entries = shared.condvar.wait(entries).unwrap(),
+				_ => break,
+			}
+		}
+		let id = entries.next_computation;
+		entries.next_computation += 1;
+		entries
+			.entries
+			.insert(key.clone(), CacheEntry::Computing(id));
+		drop(entries);
+
+		let computation = Computation { shared, key, id };
+		let value = self.install(f);
+		let mut entries = shared.entries.lock().unwrap();
+		if Computation::is_current(&entries, &computation.key, id) {
+			let computed_at = self.inner.clock.now();
+			entries.entries.insert(
+				computation.key.clone(),
+				CacheEntry::Ready {
+					value: value.clone(),
+					computed_at,
+				},
+			);
+		} else {
+			debug!("cached result invalidated while being computed, not caching it");
+		}
+		drop(entries);
+		drop(computation);
+		value
+	}
+}
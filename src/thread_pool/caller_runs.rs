@@ -0,0 +1,177 @@
+use std::{
+	fmt,
+	sync::{atomic::Ordering, Mutex, MutexGuard, PoisonError, TryLockError},
+};
+
+use super::{
+	worker::{self, WorkerShared, NOT_RUNNING},
+	Task, ThreadPoolBuilder, ThreadPoolShared,
+};
+
+#[cfg(doc)]
+use super::{PoolStatus, ThreadPool};
+
+/// The worker index the tasks run by their caller are reported with, e.g. in
+/// [`PoolEvent`](crate::PoolEvent)s and [`TaskPanic`](crate::TaskPanic)s, see
+/// [`ThreadPoolBuilder::caller_runs`].
+pub const CALLER_WORKER: usize = usize::MAX;
+
+/// The data lent to the tasks run by their caller, see [`ThreadPoolBuilder::caller_runs`].
+pub(crate) struct CallerRuns<WorkerData> {
+	worker_data: Mutex<WorkerData>,
+	/// Keeps track of the task run by a caller, one at a time, the way a worker does.
+	pub(crate) worker: WorkerShared,
+}
+
+impl<WorkerData> CallerRuns<WorkerData> {
+	pub(crate) fn new(worker_data: WorkerData) -> Self {
+		Self {
+			worker: WorkerShared::new(CALLER_WORKER, &worker_data, None),
+			worker_data: Mutex::new(worker_data),
+		}
+	}
+}
+
+impl<WorkerData> fmt::Debug for CallerRuns<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("CallerRuns")
+			.field("worker", &self.worker)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// The data lent to tasks run by their caller, unless there's none or it's already in use,
+	/// see [`ThreadPoolBuilder::caller_runs`].
+	pub(crate) fn caller_data(&self) -> Option<MutexGuard<'_, WorkerData>> {
+		match self.caller_runs.as_ref()?.worker_data.try_lock() {
+			Ok(worker_data) => Some(worker_data),
+			// A task run by a caller panicked, the data is still usable.
+			Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+			Err(TryLockError::WouldBlock) => None,
+		}
+	}
+
+	/// Whether a caller is running a task, or is about to, which the pool isn't drained
+	/// until it's done with.
+	pub(crate) fn caller_running(&self) -> bool {
+		self.caller_running_before(NOT_RUNNING)
+	}
+
+	/// Whether a caller is running a task enqueued before the one with sequence number
+	/// `target`, or is about to, see [`ThreadPool::flush`].
+	pub(crate) fn caller_running_before(&self, target: u64) -> bool {
+		self.caller_runs.as_ref().is_some_and(|caller_runs| {
+			caller_runs.worker.running_seq.load(Ordering::SeqCst) < target
+		})
+	}
+
+	/// Mark the callers as running `task`, to be called before releasing the lock it was
+	/// taken under and running it with [`ThreadPoolShared::run_with_caller_data`], so that
+	/// the threads flushing or joining the pool never miss it.
+	pub(crate) fn start_on_caller(&self, task: &Task<WorkerData>) {
+		if let Some(caller_runs) = &self.caller_runs {
+			caller_runs
+				.worker
+				.running_seq
+				.store(task.seq, Ordering::SeqCst);
+		}
+	}
+
+	/// Run a task that didn't fit in the queue on the calling thread.
+	pub(crate) fn run_on_caller(
+		&self,
+		task: Task<WorkerData>,
+		worker_data: MutexGuard<'_, WorkerData>,
+	) {
+		trace!(
+			"queue full, running task on the caller",
+			location = task.location
+		);
+		self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+		self.run_with_caller_data(task, worker_data);
+	}
+
+	/// Run `task` with the data lent to the callers, reporting it like the workers do under
+	/// [`CALLER_WORKER`], and waking up the threads joining the pool once done.
+	pub(crate) fn run_with_caller_data(
+		&self,
+		task: Task<WorkerData>,
+		mut worker_data: MutexGuard<'_, WorkerData>,
+	) {
+		let caller_runs = self.caller_runs.as_ref().unwrap();
+		let _done = CallerDone(self);
+		worker::run_inline(
+			self,
+			CALLER_WORKER,
+			&caller_runs.worker,
+			task,
+			&mut worker_data,
+		);
+	}
+}
+
+/// Wakes up the threads joining the pool when a caller is done with its task, even if
+/// it panicked.
+struct CallerDone<'a, WorkerData: Send>(&'a ThreadPoolShared<WorkerData>);
+
+impl<WorkerData: Send> Drop for CallerDone<'_, WorkerData> {
+	fn drop(&mut self) {
+		// Taking the lock guarantees joiners are either waiting or yet to check the callers.
+		drop(
+			self.0
+				.pending_tasks
+				.lock()
+				.unwrap_or_else(PoisonError::into_inner),
+		);
+		self.0.notify_room();
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// When a task is enqueued while the queue is full, run it on the calling thread with
+	/// the given data instead of blocking until there's room, so that producers are slowed
+	/// down by the work they can't hand over rather than by waiting.
+	///
+	/// The data is shared by all the callers: while a caller is running a task, the others
+	/// block as usual. If the task panics, the panic is propagated to the caller.
+	/// Otherwise, tasks run by their caller are treated like the ones run by the workers:
+	/// they're reported to the [event listeners](Self::event_listener) and the
+	/// [panic hook](Self::panic_hook) under the [`CALLER_WORKER`] index, take a permit of the
+	/// [thread budget](Self::thread_budget), are waited for by [`ThreadPool::flush`] and
+	/// [`ThreadPool::join`], and show up in [`PoolStatus::caller`].
+	/// Methods that don't block, such as [`ThreadPool::try_enqueue_many`](crate::ThreadPool::try_enqueue_many),
+	/// still reject the tasks that don't fit.
+	///
+	/// Defaults to blocking.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{sync::mpsc, thread};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 1])
+	/// 	.max_pending_tasks(1)
+	/// 	.caller_runs(())
+	/// 	.build();
+	///
+	/// let (sender, receiver) = mpsc::channel();
+	/// for _ in 0..8 {
+	/// 	let sender = sender.clone();
+	/// 	pool.enqueue(move |()| sender.send(thread::current().id()).unwrap());
+	/// }
+	/// drop(sender);
+	/// pool.join();
+	///
+	/// let on_caller = receiver
+	/// 	.iter()
+	/// 	.filter(|id| *id == thread::current().id())
+	/// 	.count();
+	/// println!("{on_caller} task(s) run by the caller");
+	/// ```
+	pub fn caller_runs(mut self, worker_data: WorkerData) -> Self {
+		self.caller_runs = Some(worker_data);
+		self
+	}
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+
+use super::{PoolQueue, Task, ThreadPool, ThreadPoolBuilder, ThreadPoolShared};
+
+type CapabilitiesFn<WorkerData> = dyn Fn(usize, &WorkerData) -> Vec<&'static str> + Send + Sync;
+
+/// Tells what each worker can do, see [`ThreadPoolBuilder::worker_capabilities`].
+pub(crate) struct WorkerCapabilities<WorkerData>(Box<CapabilitiesFn<WorkerData>>);
+
+impl<WorkerData> WorkerCapabilities<WorkerData> {
+	pub(crate) fn of(&self, index: usize, worker_data: &WorkerData) -> Vec<&'static str> {
+		(self.0)(index, worker_data)
+	}
+}
+
+impl<WorkerData> fmt::Debug for WorkerCapabilities<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("WorkerCapabilities").finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Record the capabilities of the workers about to be spawned with the given data.
+	pub(crate) fn assign_capabilities(&self, workers_data: &[WorkerData]) {
+		let Some(capabilities) = &self.worker_capabilities else {
+			return;
+		};
+		if let PoolQueue::Todo(tasks) = &mut *self.pending_tasks.lock().unwrap() {
+			for (index, worker_data) in workers_data.iter().enumerate() {
+				tasks.set_capabilities(index, &capabilities.of(index, worker_data));
+			}
+		}
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a task that only the workers with all the given capabilities can run,
+	/// see [`ThreadPoolBuilder::worker_capabilities`]. The other workers skip it and run
+	/// the tasks queued behind it instead.
+	///
+	/// A task that requires capabilities is never run by the caller, e.g. with
+	/// [`ThreadPoolBuilder::caller_runs`], as the data of the caller might lack them.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Panics
+	/// - if no worker has all the given capabilities
+	#[track_caller]
+	pub fn enqueue_requiring<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		requirements: &[&str],
+		task: Task,
+	) {
+		let requires = match &*self.inner.pending_tasks.lock().unwrap() {
+			PoolQueue::Todo(tasks) => tasks.requirements(requirements),
+			// The task is discarded anyway.
+			PoolQueue::Done => Some(0),
+		};
+		let Some(requires) = requires else {
+			panic!("no worker has all the capabilities required by the task: {requirements:?}");
+		};
+		self.enqueue_task(self::Task {
+			requires,
+			..self::Task::new(task)
+		});
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Tell what each worker can do, given its index and its data, e.g. whether it's backed
+	/// by a GPU, so that tasks enqueued with [`ThreadPool::enqueue_requiring`] only run on the
+	/// workers that have all the capabilities they require. Tasks that don't require any
+	/// capability run on any worker.
+	///
+	/// Workers added by [`ThreadPool::grow`](crate::ThreadPool::grow) are asked too.
+	/// Capabilities are matched by name, and the workers can't have more than 64 distinct
+	/// capabilities overall.
+	///
+	/// Defaults to workers being able to run any task.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// enum Device {
+	/// 	Gpu(usize),
+	/// 	Cpu,
+	/// }
+	///
+	/// let pool = ThreadPool::builder(vec![Device::Gpu(0), Device::Cpu, Device::Cpu])
+	/// 	.worker_capabilities(|_, device| match device {
+	/// 		Device::Gpu(_) => vec!["gpu"],
+	/// 		Device::Cpu => vec![],
+	/// 	})
+	/// 	.build();
+	///
+	/// for _ in 0..4 {
+	/// 	pool.enqueue_requiring(&["gpu"], |device| assert!(matches!(device, Device::Gpu(0))));
+	/// 	pool.enqueue(|_| println!("running anywhere"));
+	/// }
+	/// ```
+	pub fn worker_capabilities(
+		mut self,
+		capabilities: impl Fn(usize, &WorkerData) -> Vec<&'static str> + Send + Sync + 'static,
+	) -> Self {
+		self.worker_capabilities = Some(WorkerCapabilities(Box::new(capabilities)));
+		self
+	}
+}
@@ -0,0 +1,50 @@
+use std::borrow::Cow;
+
+/// A class of tasks with its own sub-queue and, optionally, a cap on the number
+/// of workers its tasks can occupy at the same time. Classes are registered through
+/// [`ThreadPoolBuilder::task_class`](crate::ThreadPoolBuilder::task_class) and
+/// targeted with [`ThreadPool::enqueue_in_class`](crate::ThreadPool::enqueue_in_class).
+///
+/// Workers serve the classes round-robin, so a class full of pending tasks can't
+/// prevent the others from being dispatched.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::{TaskClass, ThreadPool};
+///
+/// let pool = ThreadPool::builder(vec![(); 4])
+/// 	.task_class(TaskClass::new("interactive", 16))
+/// 	.task_class(TaskClass::new("batch", 256).max_workers(2))
+/// 	.build();
+///
+/// for i in 0..64 {
+/// 	pool.enqueue_in_class("batch", move |()| println!("batch task {i}"));
+/// }
+/// pool.enqueue_in_class("interactive", |()| println!("served right away"));
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct TaskClass {
+	pub(crate) name: Cow<'static, str>,
+	pub(crate) max_pending_tasks: usize,
+	pub(crate) max_workers: Option<usize>,
+}
+
+impl TaskClass {
+	/// Construct a task class with a sub-queue of size `max_pending_tasks`,
+	/// whose tasks can run on any number of workers.
+	pub fn new(name: impl Into<Cow<'static, str>>, max_pending_tasks: usize) -> Self {
+		Self {
+			name: name.into(),
+			max_pending_tasks,
+			max_workers: None,
+		}
+	}
+
+	/// Limit the number of workers that can run tasks of this class at the same time.
+	pub fn max_workers(mut self, max_workers: usize) -> Self {
+		self.max_workers = Some(max_workers);
+		self
+	}
+}
@@ -0,0 +1,144 @@
+use std::{
+	fmt,
+	sync::{Arc, Condvar, Mutex},
+	thread,
+	time::{Duration, Instant},
+};
+
+/// The source of time of a pool, used to schedule delayed work, e.g. the backoff of
+/// [`ThreadPool::enqueue_with_retry`](crate::ThreadPool::enqueue_with_retry), and to measure
+/// its uptime. Registered with [`ThreadPoolBuilder::clock`](crate::ThreadPoolBuilder::clock),
+/// defaults to [`SystemClock`].
+///
+/// Replacing it with a [`ManualClock`] lets tests advance time deterministically instead of sleeping.
+pub trait Clock: Send + Sync {
+	/// The current time.
+	fn now(&self) -> Instant;
+
+	/// Block the current thread for `duration`, as measured by this clock.
+	fn sleep(&self, duration: Duration);
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+	fn now(&self) -> Instant {
+		(**self).now()
+	}
+
+	fn sleep(&self, duration: Duration) {
+		(**self).sleep(duration);
+	}
+}
+
+impl fmt::Debug for dyn Clock {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Clock").finish_non_exhaustive()
+	}
+}
+
+/// The monotonic clock of the system, i.e. [`Instant::now`] and [`thread::sleep`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+	fn now(&self) -> Instant {
+		Instant::now()
+	}
+
+	fn sleep(&self, duration: Duration) {
+		thread::sleep(duration);
+	}
+}
+
+/// A clock that only moves forward when told to, see [`ManualClock::advance`].
+/// Threads sleeping on it wake up once it has been advanced past their deadline.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::{mpsc, Arc}, time::Duration};
+/// use lending_thread_pool::{ManualClock, RetryPolicy, ThreadPool};
+///
+/// let clock = Arc::new(ManualClock::new());
+/// let pool = ThreadPool::builder(vec![(); 1]).clock(clock.clone()).build();
+///
+/// let (tx, rx) = mpsc::channel();
+/// let mut attempts = 0;
+/// pool.enqueue_with_retry(RetryPolicy::new(2).backoff(Duration::from_secs(3600)), move |()| {
+/// 	attempts += 1;
+/// 	if attempts == 1 {
+/// 		return Err("not yet");
+/// 	}
+/// 	tx.send(attempts).unwrap();
+/// 	Ok(())
+/// });
+///
+/// // The retry waits for an hour, which passes instantly.
+/// clock.wait_for_sleepers(1);
+/// clock.advance(Duration::from_secs(3600));
+/// assert_eq!(rx.recv().unwrap(), 2);
+/// ```
+#[derive(Debug)]
+pub struct ManualClock {
+	origin: Instant,
+	state: Mutex<ManualState>,
+	condvar: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct ManualState {
+	elapsed: Duration,
+	sleepers: usize,
+}
+
+impl ManualClock {
+	/// Construct a clock that starts at the current time of the system.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			origin: Instant::now(),
+			state: Mutex::default(),
+			condvar: Condvar::new(),
+		}
+	}
+
+	/// Move the clock forward, waking up the threads whose sleep is over.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn advance(&self, by: Duration) {
+		self.state.lock().unwrap().elapsed += by;
+		self.condvar.notify_all();
+	}
+
+	/// Block until at least `count` threads are sleeping on this clock, e.g. to advance it only
+	/// once a delayed task has been scheduled.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn wait_for_sleepers(&self, count: usize) {
+		let _guard = self
+			.condvar
+			.wait_while(self.state.lock().unwrap(), |state| state.sleepers < count)
+			.unwrap();
+	}
+}
+
+impl Default for ManualClock {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Clock for ManualClock {
+	fn now(&self) -> Instant {
+		self.origin + self.state.lock().unwrap().elapsed
+	}
+
+	fn sleep(&self, duration: Duration) {
+		let mut state = self.state.lock().unwrap();
+		let deadline = state.elapsed + duration;
+		state.sleepers += 1;
+		self.condvar.notify_all();
+		let mut state = self
+			.condvar
+			.wait_while(state, |state| state.elapsed < deadline)
+			.unwrap();
+		state.sleepers -= 1;
+	}
+}
@@ -0,0 +1,134 @@
+use std::{
+	collections::HashMap,
+	fmt,
+	hash::Hash,
+	sync::{Arc, Mutex},
+};
+
+use super::{Task, TaskHandle, ThreadPool};
+
+/// Tracks the keyed tasks waiting in the queue, so that a task enqueued with
+/// [`ThreadPool::enqueue_coalesced`] is dropped when one with the same key is already
+/// waiting to run, e.g. redundant refresh requests.
+///
+/// Cloning a `TaskCoalescer` yields a handle to the same set of keys.
+#[derive(Clone)]
+pub struct TaskCoalescer<Key> {
+	pending: Arc<Mutex<HashMap<Key, TaskHandle>>>,
+}
+
+impl<Key> TaskCoalescer<Key> {
+	/// Construct a coalescer with no pending keys.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			pending: Arc::default(),
+		}
+	}
+
+	/// The number of keys whose task is waiting to run.
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.pending.lock().unwrap().len()
+	}
+
+	/// Whether no keyed task is waiting to run.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl<Key> Default for TaskCoalescer<Key> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Key> fmt::Debug for TaskCoalescer<Key> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TaskCoalescer")
+			.field("pending", &self.len())
+			.finish()
+	}
+}
+
+/// Removes the key of a task from the pending ones when dropped, i.e. when the task starts
+/// or is discarded without running.
+struct PendingKey<Key: Eq + Hash> {
+	pending: Arc<Mutex<HashMap<Key, TaskHandle>>>,
+	key: Key,
+}
+
+impl<Key: Eq + Hash> Drop for PendingKey<Key> {
+	fn drop(&mut self) {
+		self.pending.lock().unwrap().remove(&self.key);
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a task identified by `key`, unless a task with the same key enqueued through
+	/// `coalescer` is still waiting to run, in which case `task` is dropped and the
+	/// [`TaskHandle`] of the pending one is returned. Once a task has started, enqueueing its
+	/// key again enqueues a new task.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning, unless the task is coalesced.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	/// use lending_thread_pool::{TaskCoalescer, ThreadPool};
+	///
+	/// let pool = ThreadPool::new(vec![(); 1]);
+	/// let coalescer = TaskCoalescer::new();
+	///
+	/// // Keep the only worker busy until the refreshes are enqueued.
+	/// let (unblock, blocked) = mpsc::channel::<()>();
+	/// pool.enqueue(move |()| {
+	/// 	let _ = blocked.recv();
+	/// });
+	///
+	/// let first = pool.enqueue_coalesced(&coalescer, "dashboard", |()| println!("refreshing"));
+	/// let second = pool.enqueue_coalesced(&coalescer, "dashboard", |()| unreachable!());
+	/// drop(unblock);
+	///
+	/// second.wait();
+	/// assert!(first.is_completed());
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	#[track_caller]
+	pub fn enqueue_coalesced<Key, Task>(
+		&self,
+		coalescer: &TaskCoalescer<Key>,
+		key: Key,
+		task: Task,
+	) -> TaskHandle
+	where
+		Key: Eq + Hash + Clone + Send + 'static,
+		Task: FnOnce(&mut WorkerData) + Send + 'static,
+	{
+		let mut pending = coalescer.pending.lock().unwrap();
+		if let Some(handle) = pending.get(&key) {
+			debug!("a task with the same key is pending, coalescing");
+			return handle.clone();
+		}
+		let handle = TaskHandle::default();
+		pending.insert(key.clone(), handle.clone());
+		drop(pending);
+		let pending_key = PendingKey {
+			pending: coalescer.pending.clone(),
+			key,
+		};
+		let task = handle.track(move |worker_data| {
+			drop(pending_key);
+			task(worker_data);
+		});
+		self.enqueue_task(self.inner.waitable(&handle, self::Task::new(task)));
+		handle
+	}
+}
@@ -0,0 +1,93 @@
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use super::{DeadWorkerPolicy, IdleWorkerPolicy, ThreadPool, ThreadPoolBuilder};
+
+/// The settings of a pool that can be read from a configuration file, turned into a
+/// [`ThreadPoolBuilder`] with [`PoolConfig::builder`]. Every field is optional,
+/// missing ones keeping the defaults of the builder.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::{IdleWorkerPolicy, PoolConfig};
+///
+/// let config: PoolConfig = serde_json::from_str(
+/// 	r#"{
+/// 		"workers": 4,
+/// 		"max_pending_tasks": 64,
+/// 		"idle_worker_policy": "least_loaded",
+/// 		"worker_groups": [{ "name": "io", "workers": 1 }]
+/// 	}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(config.idle_worker_policy, IdleWorkerPolicy::LeastLoaded);
+///
+/// let pool = config.builder(|i| i).build();
+/// pool.enqueue_to("io", |&mut i| assert_eq!(i, 0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct PoolConfig {
+	/// The number of workers, including the ones in worker groups.
+	/// Defaults to one per core, as reported by [`std::thread::available_parallelism`].
+	pub workers: Option<usize>,
+	/// See [`ThreadPoolBuilder::max_pending_tasks`].
+	pub max_pending_tasks: Option<usize>,
+	/// See [`ThreadPoolBuilder::max_pending_bytes`].
+	pub max_pending_bytes: Option<usize>,
+	/// See [`ThreadPoolBuilder::worker_group`], groups taking the workers in order.
+	pub worker_groups: Vec<WorkerGroupConfig>,
+	/// See [`ThreadPoolBuilder::fair_producers`].
+	pub fair_producers: bool,
+	/// See [`ThreadPoolBuilder::strict_start_order`].
+	pub strict_start_order: bool,
+	/// See [`ThreadPoolBuilder::idle_worker_policy`].
+	pub idle_worker_policy: IdleWorkerPolicy,
+	/// See [`ThreadPoolBuilder::dead_worker_policy`].
+	pub dead_worker_policy: DeadWorkerPolicy,
+}
+
+/// A worker group of a [`PoolConfig`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkerGroupConfig {
+	/// The name of the group, as passed to [`ThreadPool::enqueue_to`].
+	pub name: String,
+	/// The number of workers in the group.
+	pub workers: usize,
+}
+
+impl PoolConfig {
+	/// Construct a [`ThreadPoolBuilder`] with these settings, calling `factory` with each
+	/// worker index to build its `WorkerData`.
+	pub fn builder<WorkerData: Send>(
+		&self,
+		factory: impl FnMut(usize) -> WorkerData,
+	) -> ThreadPoolBuilder<WorkerData> {
+		let workers = self
+			.workers
+			.unwrap_or_else(|| thread::available_parallelism().map_or(1, usize::from));
+		let mut builder = ThreadPool::builder((0..workers).map(factory).collect())
+			.idle_worker_policy(self.idle_worker_policy)
+			.dead_worker_policy(self.dead_worker_policy);
+		if let Some(max_pending_tasks) = self.max_pending_tasks {
+			builder = builder.max_pending_tasks(max_pending_tasks);
+		}
+		if let Some(max_pending_bytes) = self.max_pending_bytes {
+			builder = builder.max_pending_bytes(max_pending_bytes);
+		}
+		for group in &self.worker_groups {
+			builder = builder.worker_group(group.name.clone(), group.workers);
+		}
+		if self.fair_producers {
+			builder = builder.fair_producers();
+		}
+		if self.strict_start_order {
+			builder = builder.strict_start_order();
+		}
+		builder
+	}
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+/// CPU time consumed by the current thread so far, if supported on this platform
+/// and the `cpu-time` feature is enabled.
+#[cfg(all(feature = "cpu-time", unix))]
+pub(crate) fn thread_cpu_time() -> Option<Duration> {
+	let mut time = libc::timespec {
+		tv_sec: 0,
+		tv_nsec: 0,
+	};
+	// SAFETY: `time` is a valid, writable timespec.
+	let result = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &raw mut time) };
+	(result == 0).then(|| {
+		Duration::new(
+			u64::try_from(time.tv_sec).unwrap_or(0),
+			u32::try_from(time.tv_nsec).unwrap_or(0),
+		)
+	})
+}
+
+/// CPU time consumed by the current thread so far, if supported on this platform
+/// and the `cpu-time` feature is enabled.
+#[cfg(not(all(feature = "cpu-time", unix)))]
+pub(crate) fn thread_cpu_time() -> Option<Duration> {
+	None
+}
@@ -0,0 +1,20 @@
+/// What to do with newly enqueued tasks once workers have been brought down by a panic, see
+/// [`ThreadPoolBuilder::dead_worker_policy`](crate::ThreadPoolBuilder::dead_worker_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum DeadWorkerPolicy {
+	/// Keep accepting tasks, which are run by the remaining workers, if any.
+	#[default]
+	Ignore,
+	/// Discard the tasks without running them, as if the pool had been shut down. Discarded
+	/// tasks are logged and reported to the [`PoolEventListener`](crate::PoolEventListener)s,
+	/// and count as rejected by [`TaskSender`](crate::TaskSender)s.
+	Reject,
+	/// Panic in the thread enqueueing the task.
+	Panic,
+}
@@ -0,0 +1,82 @@
+use std::time::{Duration, Instant};
+
+use super::ThreadPool;
+
+/// What to do with a task that hasn't started by its deadline,
+/// see [`ThreadPool::enqueue_with_deadline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum DeadlineMissPolicy {
+	/// Run the task anyway.
+	#[default]
+	Run,
+	/// Skip the task, e.g. because its result would already be obsolete.
+	Skip,
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a task that should start by `deadline`, as measured by the
+	/// [`Clock`](crate::Clock) of the pool. If it's picked up later than that, `on_miss` is called
+	/// by the worker with how late the task is, then the task is run or skipped according to
+	/// `policy`, so that stale work, like rendering a frame that's already obsolete, can be skipped.
+	///
+	/// The deadline is soft: it's only checked when the task is about to start, and a task
+	/// that has started is never interrupted.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{
+	/// 	sync::mpsc,
+	/// 	thread::sleep,
+	/// 	time::{Duration, Instant},
+	/// };
+	/// use lending_thread_pool::{DeadlineMissPolicy, ThreadPool};
+	///
+	/// let pool = ThreadPool::new(vec![(); 1]);
+	/// let (missed, misses) = mpsc::channel();
+	///
+	/// // Keep the only worker busy past the deadline of the frame.
+	/// pool.enqueue(|()| sleep(Duration::from_millis(50)));
+	/// pool.enqueue_with_deadline(
+	/// 	Instant::now() + Duration::from_millis(10),
+	/// 	DeadlineMissPolicy::Skip,
+	/// 	move |late| missed.send(late).unwrap(),
+	/// 	|()| unreachable!("the frame is obsolete"),
+	/// );
+	///
+	/// assert!(misses.recv().unwrap() > Duration::ZERO);
+	/// ```
+	#[track_caller]
+	pub fn enqueue_with_deadline<Task>(
+		&self,
+		deadline: Instant,
+		policy: DeadlineMissPolicy,
+		on_miss: impl FnOnce(Duration) + Send + 'static,
+		task: Task,
+	) where
+		Task: FnOnce(&mut WorkerData) + Send + 'static,
+	{
+		let clock = self.inner.clock.clone();
+		self.enqueue(move |worker_data| {
+			let now = clock.now();
+			if now > deadline {
+				trace!(
+					"task missed its deadline",
+					late = now - deadline,
+					policy = policy,
+				);
+				on_miss(now - deadline);
+				if policy == DeadlineMissPolicy::Skip {
+					return;
+				}
+			}
+			task(worker_data);
+		});
+	}
+}
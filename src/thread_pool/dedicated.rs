@@ -0,0 +1,149 @@
+use std::{
+	fmt,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex,
+	},
+};
+
+use super::{PoolQueue, ThreadPoolBuilder, ThreadPoolShared};
+
+/// Tells the services run by dedicated workers to return, see
+/// [`ThreadPoolBuilder::dedicated_worker`].
+///
+/// Cloning a `StopSignal` yields a handle to the same flag.
+#[derive(Debug, Clone)]
+pub struct StopSignal {
+	stopping: Arc<AtomicBool>,
+}
+
+impl StopSignal {
+	/// Whether the pool is being joined, detached or aborted.
+	#[must_use]
+	pub fn is_stopping(&self) -> bool {
+		self.stopping.load(Ordering::Relaxed)
+	}
+}
+
+type Service<WorkerData> = Box<dyn FnOnce(&mut WorkerData, &StopSignal) + Send>;
+
+/// A worker running a service instead of the tasks of the queue, see
+/// [`ThreadPoolBuilder::dedicated_worker`].
+pub(crate) struct DedicatedWorker<WorkerData> {
+	pub(crate) index: usize,
+	/// Taken by the worker when it starts.
+	service: Mutex<Option<Service<WorkerData>>>,
+}
+
+impl<WorkerData> fmt::Debug for DedicatedWorker<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DedicatedWorker")
+			.field("index", &self.index)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Whether the given worker runs a service, see [`ThreadPoolBuilder::dedicated_worker`].
+	pub(crate) fn is_dedicated(&self, worker: usize) -> bool {
+		self.dedicated_workers
+			.iter()
+			.any(|dedicated| dedicated.index == worker)
+	}
+
+	/// Keep the tasks of the queue away from the dedicated workers.
+	pub(crate) fn reserve_dedicated_workers(&self) {
+		if let PoolQueue::Todo(tasks) = &mut *self.pending_tasks.lock().unwrap() {
+			for dedicated in &self.dedicated_workers {
+				tasks.set_reserved(dedicated.index, true);
+			}
+		}
+	}
+
+	/// Run the service of the given worker, if it's dedicated to one that hasn't run yet.
+	pub(crate) fn run_service(&self, worker: usize, worker_data: &mut WorkerData) {
+		let Some(service) = self
+			.dedicated_workers
+			.iter()
+			.find(|dedicated| dedicated.index == worker)
+			.and_then(|dedicated| dedicated.service.lock().unwrap().take())
+		else {
+			return;
+		};
+		debug!("running service", worker = worker);
+		service(
+			worker_data,
+			&StopSignal {
+				stopping: self.stopping.clone(),
+			},
+		);
+		debug!("service returned", worker = worker);
+	}
+
+	/// Raise the [`StopSignal`] of the services.
+	pub(crate) fn stop_services(&self) {
+		self.stopping.store(true, Ordering::Relaxed);
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Dedicate the worker at `index` (in the order of the `WorkerData` Vec) to a long-lived
+	/// service, e.g. a consumer on a socket owned by its `WorkerData`, while the other workers
+	/// run the tasks of the queue as usual.
+	///
+	/// The worker calls `service` once, as soon as it starts, and never runs the tasks of the
+	/// queue. The service is expected to return once the [`StopSignal`] it's passed is raised,
+	/// which happens when the pool is joined (after the queue is drained), detached or
+	/// aborted, so that it shares the lifecycle of the pool: joining the pool waits for it to
+	/// return, and [`ThreadPool::stop`](crate::ThreadPool::stop) gives its data back with
+	/// the others. Tasks the service enqueues on the pool after that are discarded.
+	///
+	/// A dedicated worker can't be [checked out](crate::ThreadPool::checkout) or
+	/// [retired](crate::ThreadPool::shrink), and is skipped by
+	/// [`ThreadPool::spawn_broadcast`](crate::ThreadPool::spawn_broadcast). Running its service
+	/// doesn't count as running a task, e.g. for [`ThreadPool::flush`](crate::ThreadPool::flush)
+	/// and [`ThreadPool::status`](crate::ThreadPool::status). If the service panics, the worker
+	/// is brought down as it would be by a task.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{sync::mpsc, time::Duration};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let (sender, receiver) = mpsc::channel::<u32>();
+	/// let mut receiver = Some(receiver);
+	///
+	/// let pool = ThreadPool::builder(vec![0; 3])
+	/// 	.dedicated_worker(0, move |received: &mut u32, signal| {
+	/// 		let receiver = receiver.take().unwrap();
+	/// 		while !signal.is_stopping() {
+	/// 			if let Ok(n) = receiver.recv_timeout(Duration::from_millis(10)) {
+	/// 				*received += n;
+	/// 			}
+	/// 		}
+	/// 		*received += receiver.try_iter().sum::<u32>();
+	/// 	})
+	/// 	.build();
+	///
+	/// sender.send(42).unwrap();
+	/// for _ in 0..8 {
+	/// 	pool.enqueue(|count| *count += 1);
+	/// }
+	///
+	/// let data = pool.stop().into_workers_data();
+	/// assert_eq!(data[0], 42);
+	/// assert_eq!(data[1] + data[2], 8);
+	/// ```
+	pub fn dedicated_worker(
+		mut self,
+		index: usize,
+		service: impl FnOnce(&mut WorkerData, &StopSignal) + Send + 'static,
+	) -> Self {
+		self.dedicated_workers.push(DedicatedWorker {
+			index,
+			service: Mutex::new(Some(Box::new(service))),
+		});
+		self
+	}
+}
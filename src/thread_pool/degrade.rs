@@ -0,0 +1,297 @@
+use std::{
+	collections::VecDeque,
+	fmt, io,
+	sync::{atomic::Ordering, mpsc::Sender, Arc, Mutex, PoisonError},
+	thread::{self, ThreadId},
+};
+
+use super::{
+	label,
+	spawner::{PoolThread, Spawner},
+	warmup::Warmup,
+	worker::{self, WorkerState},
+	PoolEvent, PoolQueue, Task, ThreadPool, ThreadPoolBuilder, ThreadPoolShared,
+};
+
+/// What to do when some worker threads can't be spawned, e.g. because of resource limits
+/// or sandboxes, see [`ThreadPoolBuilder::spawn_failure_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum SpawnFailurePolicy {
+	/// Panic while building the pool.
+	#[default]
+	Panic,
+	/// Run with the workers spawned before the first failure, dropping the data of the others.
+	/// If none could be spawned, or if a [worker group](ThreadPoolBuilder::worker_group) would
+	/// be left without workers, run the tasks inline instead, see [`ThreadPool::is_inline`].
+	///
+	/// Either way, the failure is logged and reported to the
+	/// [`PoolEventListener`](crate::PoolEventListener)s.
+	Degrade,
+}
+
+/// Runs the tasks of a pool without worker threads, see [`SpawnFailurePolicy::Degrade`].
+pub(crate) struct Inline<WorkerData> {
+	/// Taken when the pool is joined.
+	worker_data: Mutex<Option<WorkerData>>,
+	/// The thread running a task, if any.
+	running_on: Mutex<Option<ThreadId>>,
+	/// Tasks enqueued by the running task, run once it completes.
+	deferred: Mutex<VecDeque<Task<WorkerData>>>,
+}
+
+impl<WorkerData: Send> Inline<WorkerData> {
+	/// Run the given tasks on the calling thread, one at a time, returning how many were
+	/// accepted. Tasks enqueued from within a task are deferred until it completes.
+	pub(crate) fn run(
+		&self,
+		inner: &ThreadPoolShared<WorkerData>,
+		tasks: impl IntoIterator<Item = Task<WorkerData>>,
+	) -> usize {
+		let mut accepted = 0;
+		for task in tasks {
+			if inner.shut_down.load(Ordering::Relaxed) {
+				debug!("pool is shut down, discarding task");
+				inner.counters.discarded.fetch_add(1, Ordering::Relaxed);
+				continue;
+			}
+			inner.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+			accepted += 1;
+			let current = thread::current().id();
+			if *self.running_on.lock().unwrap() == Some(current) {
+				self.deferred.lock().unwrap().push_back(task);
+				continue;
+			}
+			let mut guard = self
+				.worker_data
+				.lock()
+				.unwrap_or_else(PoisonError::into_inner);
+			let Some(worker_data) = guard.as_mut() else {
+				debug!("pool is joined, discarding task");
+				inner.counters.discarded.fetch_add(1, Ordering::Relaxed);
+				continue;
+			};
+			*self.running_on.lock().unwrap() = Some(current);
+			let _running = Running(self);
+			let worker = inner.worker(0);
+			worker::run_inline(inner, 0, &worker, task, worker_data);
+			while let Some(task) = self.deferred.lock().unwrap().pop_front() {
+				worker::run_inline(inner, 0, &worker, task, worker_data);
+			}
+		}
+		accepted
+	}
+
+	/// Take the worker data back, after which tasks are discarded.
+	pub(crate) fn take(&self) -> Option<WorkerData> {
+		self.worker_data
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.take()
+	}
+}
+
+impl<WorkerData> fmt::Debug for Inline<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Inline").finish_non_exhaustive()
+	}
+}
+
+/// Clears the thread running a task once done, even if the task panicked.
+struct Running<'a, WorkerData>(&'a Inline<WorkerData>);
+
+impl<WorkerData> Drop for Running<'_, WorkerData> {
+	fn drop(&mut self) {
+		*self
+			.0
+			.running_on
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner) = None;
+	}
+}
+
+/// Spawn a thread for each worker, applying the [`SpawnFailurePolicy`] on the first failure.
+pub(crate) fn spawn_workers<'a, WorkerData: Send + 'a>(
+	inner: &Arc<ThreadPoolShared<WorkerData>>,
+	workers_data: Vec<WorkerData>,
+	warmup: Option<&(Warmup<WorkerData>, Sender<()>)>,
+	spawner: &impl Spawner<'a>,
+) -> Vec<PoolThread<WorkerData>> {
+	let requested = workers_data.len();
+	let mut workers = Vec::with_capacity(requested);
+	for (i, worker_data) in workers_data.into_iter().enumerate() {
+		let warmup = warmup.map(|(warmup, warmed_up)| warmup.for_worker(warmed_up.clone()));
+		let name = label::thread_name(i, inner.worker(i).label.as_deref());
+		// Lets the data be recovered if the thread can't be spawned.
+		let slot = Arc::new(Mutex::new(Some(worker_data)));
+		let thread = spawner.spawn(name.clone(), {
+			let inner = inner.clone();
+			let slot = slot.clone();
+			move || {
+				let data = slot.lock().unwrap().take().unwrap();
+				worker::run(i, &inner, warmup, data)
+			}
+		});
+		match thread {
+			Ok(thread) => workers.push(thread),
+			Err(error) => {
+				let unspawned = slot.lock().unwrap().take();
+				inner.spawn_failed(&mut workers, requested, &name, &error, unspawned);
+				break;
+			}
+		}
+	}
+	workers
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Whether the tasks run inline, see [`ThreadPool::is_inline`].
+	pub(crate) fn is_inline(&self) -> bool {
+		self.inline.get().is_some()
+	}
+
+	/// Apply the [`SpawnFailurePolicy`] after the worker following `workers`, named `thread`,
+	/// failed to spawn, given its data if it could be recovered.
+	fn spawn_failed(
+		&self,
+		workers: &mut Vec<PoolThread<WorkerData>>,
+		requested: usize,
+		thread: &str,
+		error: &io::Error,
+		unspawned: Option<WorkerData>,
+	) {
+		assert!(
+			self.spawn_failure_policy != SpawnFailurePolicy::Panic,
+			"thread to be spawned: {error:?}"
+		);
+		let spawned = workers.len();
+		let mut guard = self.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			unreachable!("the pool is being built");
+		};
+		if spawned > 0 && !tasks.leaves_group_empty(spawned) {
+			warn!(
+				"failed to spawn all the workers, running with fewer",
+				requested = requested,
+				spawned = spawned,
+				error = error,
+			);
+			tasks.retire_from(spawned);
+			tasks.remove_retired();
+			self.workers.write().unwrap().truncate(spawned);
+			drop(guard);
+			self.listeners.emit(PoolEvent::SpawnFailed {
+				thread,
+				requested,
+				spawned,
+				inline: false,
+			});
+			return;
+		}
+
+		warn!(
+			"failed to spawn the workers, running tasks inline",
+			requested = requested,
+			spawned = spawned,
+			error = error,
+		);
+		// The queue is left to the threads already spawned, which exit right away.
+		*guard = PoolQueue::Done;
+		drop(guard);
+		self.wake_all_workers();
+		let joined = workers.drain(..).map(PoolThread::join).collect::<Vec<_>>();
+		let worker_data = match joined.into_iter().next() {
+			Some(joined) => joined.ok(),
+			None => unspawned,
+		};
+		let Some(worker_data) = worker_data else {
+			panic!("thread to be spawned: {error:?}");
+		};
+		self.workers.write().unwrap().truncate(1);
+		*self.worker(0).state.lock().unwrap() = WorkerState::Idle;
+		let _ = self.inline.set(Inline {
+			worker_data: Mutex::new(Some(worker_data)),
+			running_on: Mutex::new(None),
+			deferred: Mutex::default(),
+		});
+		self.listeners.emit(PoolEvent::SpawnFailed {
+			thread,
+			requested,
+			spawned,
+			inline: true,
+		});
+	}
+
+	/// Apply the [`SpawnFailurePolicy`] after a thread of the pool other than a worker, named
+	/// `thread`, failed to spawn. When degrading, the pool does without it as described by
+	/// `fallback`.
+	///
+	/// # Panics
+	/// - if the policy is [`SpawnFailurePolicy::Panic`]
+	#[track_caller]
+	pub(crate) fn helper_spawn_failed(&self, thread: &str, fallback: &str, error: &io::Error) {
+		assert!(
+			self.spawn_failure_policy != SpawnFailurePolicy::Panic,
+			"thread to be spawned: {error:?}"
+		);
+		warn!(
+			"failed to spawn a thread of the pool, doing without",
+			thread = thread,
+			fallback = fallback,
+			error = error,
+		);
+		let workers = self.workers().len();
+		self.listeners.emit(PoolEvent::SpawnFailed {
+			thread,
+			requested: workers,
+			spawned: workers,
+			inline: self.is_inline(),
+		});
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Whether the tasks run inline, i.e. on the threads enqueueing them, one at a time,
+	/// because no worker thread could be spawned, see [`SpawnFailurePolicy::Degrade`].
+	///
+	/// Inline pools have a single worker, and behave as if they were shut down for anything
+	/// that targets workers rather than enqueueing tasks, e.g. [`ThreadPool::checkout`].
+	/// A task panicking is propagated to the thread that enqueued it.
+	#[must_use]
+	pub fn is_inline(&self) -> bool {
+		self.inner.is_inline()
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Set what to do when some worker threads can't be spawned.
+	/// Defaults to [`SpawnFailurePolicy::Panic`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::io;
+	/// use lending_thread_pool::{SpawnFailurePolicy, ThreadPool};
+	///
+	/// let pool = ThreadPool::builder(vec![(); 4])
+	/// 	.spawn_failure_policy(SpawnFailurePolicy::Degrade)
+	/// 	// Stand-in for a sandbox denying thread creation.
+	/// 	.thread_spawner(|_: String, _: Box<dyn FnOnce() + Send>| -> io::Result<()> {
+	/// 		Err(io::Error::from(io::ErrorKind::PermissionDenied))
+	/// 	})
+	/// 	.build();
+	///
+	/// assert!(pool.is_inline());
+	/// pool.enqueue(|()| println!("running on the main thread"));
+	/// pool.join();
+	/// ```
+	pub fn spawn_failure_policy(mut self, policy: SpawnFailurePolicy) -> Self {
+		self.spawn_failure_policy = policy;
+		self
+	}
+}
@@ -0,0 +1,163 @@
+use std::{
+	fmt,
+	sync::{mpsc, Arc, Mutex},
+};
+
+use super::ThreadPool;
+
+/// A destination for the errors returned by fallible tasks, see [`ThreadPool::enqueue_fallible`].
+///
+/// An `ErrorSink` either collects errors in memory, to be retrieved later
+/// via [`ErrorSink::take`], or forwards them into a channel.
+/// Cloning an `ErrorSink` yields a handle to the same destination.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::{ErrorSink, ThreadPool};
+///
+/// let pool = ThreadPool::new(vec![(); 4]);
+/// let errors = ErrorSink::new();
+///
+/// for i in 0..16 {
+/// 	pool.enqueue_fallible(&errors, move |()| {
+/// 		if i % 4 == 0 {
+/// 			Err(format!("task {i} failed"))
+/// 		} else {
+/// 			Ok(())
+/// 		}
+/// 	});
+/// }
+/// pool.join();
+///
+/// assert_eq!(errors.take().len(), 4);
+/// ```
+pub struct ErrorSink<Error> {
+	inner: ErrorSinkInner<Error>,
+}
+
+enum ErrorSinkInner<Error> {
+	Collect(Arc<Mutex<Vec<Error>>>),
+	Channel(mpsc::Sender<Error>),
+}
+
+impl<Error> ErrorSink<Error> {
+	/// Construct a sink that collects errors in memory.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			inner: ErrorSinkInner::Collect(Arc::default()),
+		}
+	}
+
+	/// Construct a sink that forwards errors into the given channel. Errors are
+	/// dropped if the receiving end has been disconnected.
+	#[must_use]
+	pub fn from_sender(sender: mpsc::Sender<Error>) -> Self {
+		Self {
+			inner: ErrorSinkInner::Channel(sender),
+		}
+	}
+
+	/// Take the errors collected so far, leaving the sink empty. Always empty for sinks
+	/// constructed with [`ErrorSink::from_sender`].
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn take(&self) -> Vec<Error> {
+		match &self.inner {
+			ErrorSinkInner::Collect(errors) => std::mem::take(&mut *errors.lock().unwrap()),
+			ErrorSinkInner::Channel(_) => Vec::new(),
+		}
+	}
+
+	/// The number of errors collected so far. Always 0 for sinks constructed with
+	/// [`ErrorSink::from_sender`].
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn len(&self) -> usize {
+		match &self.inner {
+			ErrorSinkInner::Collect(errors) => errors.lock().unwrap().len(),
+			ErrorSinkInner::Channel(_) => 0,
+		}
+	}
+
+	/// Whether no error has been collected so far.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub(crate) fn push(&self, error: Error) {
+		match &self.inner {
+			ErrorSinkInner::Collect(errors) => errors.lock().unwrap().push(error),
+			ErrorSinkInner::Channel(sender) => {
+				let _ = sender.send(error);
+			}
+		}
+	}
+}
+
+impl<Error> Default for ErrorSink<Error> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Error> Clone for ErrorSink<Error> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: match &self.inner {
+				ErrorSinkInner::Collect(errors) => ErrorSinkInner::Collect(errors.clone()),
+				ErrorSinkInner::Channel(sender) => ErrorSinkInner::Channel(sender.clone()),
+			},
+		}
+	}
+}
+
+impl<Error> fmt::Debug for ErrorSink<Error> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.inner {
+			ErrorSinkInner::Collect(_) => write!(f, "ErrorSink::Collect({})", self.len()),
+			ErrorSinkInner::Channel(_) => write!(f, "ErrorSink::Channel"),
+		}
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a fallible task in the pool. If the task returns an error, the error
+	/// is delivered to the given [`ErrorSink`].
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	/// use lending_thread_pool::{ErrorSink, ThreadPool};
+	///
+	/// let pool = ThreadPool::new(vec![(); 4]);
+	/// let (tx, rx) = mpsc::channel();
+	/// let errors = ErrorSink::from_sender(tx);
+	///
+	/// pool.enqueue_fallible(&errors, |()| "not a number".parse::<u32>().map(|_| ()));
+	///
+	/// assert!(rx.recv().is_ok());
+	/// ```
+	#[track_caller]
+	pub fn enqueue_fallible<Error, Task>(&self, sink: &ErrorSink<Error>, task: Task)
+	where
+		Error: Send + 'static,
+		Task: FnOnce(&mut WorkerData) -> Result<(), Error> + Send + 'static,
+	{
+		let sink = sink.clone();
+		self.enqueue(move |worker_data| {
+			if let Err(error) = task(worker_data) {
+				debug!("task failed");
+				sink.push(error);
+			}
+		});
+	}
+}
@@ -0,0 +1,147 @@
+use std::{fmt, time::Duration};
+
+use super::TaskMeta;
+
+/// Something that happened in a pool, reported to [`PoolEventListener`]s. The tasks run by
+/// their caller are reported with the [`CALLER_WORKER`](crate::CALLER_WORKER) index, see
+/// [`ThreadPoolBuilder::caller_runs`](crate::ThreadPoolBuilder::caller_runs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PoolEvent<'a> {
+	/// A worker thread has started.
+	WorkerSpawned { worker: usize },
+	/// A worker has taken a task off the queue and is about to run it.
+	TaskDequeued {
+		worker: usize,
+		name: Option<&'a str>,
+		/// The metadata the task was enqueued with, see
+		/// [`ThreadPool::enqueue_with_meta`](crate::ThreadPool::enqueue_with_meta).
+		meta: Option<TaskMeta<'a>>,
+	},
+	/// A task has run to completion.
+	TaskCompleted {
+		worker: usize,
+		name: Option<&'a str>,
+		meta: Option<TaskMeta<'a>>,
+		/// Wall time spent running the task.
+		elapsed: Duration,
+		/// CPU time spent running the task, which is much lower than `elapsed` for tasks
+		/// that mostly wait on IO. Only available with the `cpu-time` feature, on unix.
+		cpu_time: Option<Duration>,
+	},
+	/// A task has panicked, bringing its worker down.
+	TaskPanicked {
+		worker: usize,
+		name: Option<&'a str>,
+		meta: Option<TaskMeta<'a>>,
+	},
+	/// A task being waited on was moved to the front of the queue, see
+	/// [`ThreadPoolBuilder::boost_waited_tasks`](crate::ThreadPoolBuilder::boost_waited_tasks).
+	TaskBoosted {
+		name: Option<&'a str>,
+		meta: Option<TaskMeta<'a>>,
+	},
+	/// A task was turned down by the admission predicate of the pool, see
+	/// [`ThreadPoolBuilder::admission`](crate::ThreadPoolBuilder::admission).
+	TaskRejected {
+		name: Option<&'a str>,
+		meta: Option<TaskMeta<'a>>,
+	},
+	/// Pending tasks were dropped without being run, e.g. because the pool was aborted
+	/// or the worker they targeted was brought down by a panic.
+	TasksDiscarded {
+		count: usize,
+		/// The names of the discarded tasks that were enqueued with one.
+		names: &'a [&'a str],
+	},
+	/// A worker thread is about to exit, either because the pool was stopped
+	/// or because of a panic.
+	WorkerExited { worker: usize, panicked: bool },
+	/// A thread of the pool couldn't be spawned, see
+	/// [`SpawnFailurePolicy::Degrade`](crate::SpawnFailurePolicy::Degrade). Either fewer
+	/// worker threads than requested could be spawned while building or
+	/// [growing](crate::ThreadPool::grow) the pool, or the pool does without one of its
+	/// other threads, e.g. the feeder of the
+	/// [task source](crate::ThreadPoolBuilder::task_source), in which case `requested` and
+	/// `spawned` are both the current number of workers.
+	SpawnFailed {
+		/// The name of the first thread that couldn't be spawned, e.g. `w(3)` for a worker,
+		/// or `feeder`.
+		thread: &'a str,
+		requested: usize,
+		spawned: usize,
+		/// Whether the pool runs its tasks inline, see
+		/// [`ThreadPool::is_inline`](crate::ThreadPool::is_inline).
+		inline: bool,
+	},
+}
+
+/// An observer of the lifecycle of a pool, registered with
+/// [`ThreadPoolBuilder::event_listener`](crate::ThreadPoolBuilder::event_listener).
+///
+/// Events are reported synchronously from the worker threads, so listeners should be quick
+/// and must not panic. Any `Fn(PoolEvent)` closure is a listener.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+/// use lending_thread_pool::{PoolEvent, ThreadPool};
+///
+/// let completed = Arc::new(AtomicUsize::new(0));
+///
+/// let pool = ThreadPool::builder(vec![(); 2])
+/// 	.event_listener({
+/// 		let completed = completed.clone();
+/// 		move |event: PoolEvent<'_>| {
+/// 			if let PoolEvent::TaskCompleted { .. } = event {
+/// 				completed.fetch_add(1, Ordering::Relaxed);
+/// 			}
+/// 		}
+/// 	})
+/// 	.build();
+///
+/// for _ in 0..8 {
+/// 	pool.enqueue(|()| {});
+/// }
+/// pool.join();
+///
+/// assert_eq!(completed.load(Ordering::Relaxed), 8);
+/// ```
+pub trait PoolEventListener: Send + Sync {
+	/// Called for every event in the pool.
+	fn on_event(&self, event: PoolEvent<'_>);
+}
+
+impl<F: Fn(PoolEvent<'_>) + Send + Sync> PoolEventListener for F {
+	fn on_event(&self, event: PoolEvent<'_>) {
+		self(event);
+	}
+}
+
+/// The listeners registered on a pool.
+#[derive(Default)]
+pub(crate) struct Listeners(Vec<Box<dyn PoolEventListener>>);
+
+impl Listeners {
+	pub(crate) fn push(&mut self, listener: Box<dyn PoolEventListener>) {
+		self.0.push(listener);
+	}
+
+	pub(crate) fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub(crate) fn emit(&self, event: PoolEvent<'_>) {
+		for listener in &self.0 {
+			listener.on_event(event);
+		}
+	}
+}
+
+impl fmt::Debug for Listeners {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} listener(s)", self.0.len())
+	}
+}
@@ -0,0 +1,77 @@
+use std::{fmt, sync::Mutex};
+
+use super::{BoxedTask, ThreadPool};
+
+/// An object-safe abstraction over something that can run tasks borrowing a `WorkerData`.
+///
+/// Application code can depend on `dyn TaskExecutor<WorkerData>` instead of [`ThreadPool`],
+/// so that tests can substitute a synchronous implementation such as [`InlineExecutor`]
+/// without spawning threads.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::{InlineExecutor, TaskExecutor, ThreadPool};
+///
+/// fn greet_all(executor: &dyn TaskExecutor<String>, names: &[&str]) {
+/// 	for name in names {
+/// 		let name = name.to_string();
+/// 		executor.enqueue_boxed(Box::new(move |greeting| println!("{greeting}, {name}")));
+/// 	}
+/// }
+///
+/// // In production
+/// let pool = ThreadPool::new(vec!["Hello".to_string(); 4]);
+/// greet_all(&pool, &["Alice", "Bob"]);
+///
+/// // In tests
+/// let executor = InlineExecutor::new("Hi".to_string());
+/// greet_all(&executor, &["Alice", "Bob"]);
+/// ```
+pub trait TaskExecutor<WorkerData> {
+	/// Enqueue an already boxed task.
+	fn enqueue_boxed(&self, task: BoxedTask<WorkerData>);
+}
+
+impl<WorkerData: Send> TaskExecutor<WorkerData> for ThreadPool<WorkerData> {
+	#[track_caller]
+	fn enqueue_boxed(&self, task: BoxedTask<WorkerData>) {
+		ThreadPool::enqueue_boxed(self, task);
+	}
+}
+
+/// A [`TaskExecutor`] that runs tasks synchronously on the calling thread, lending them
+/// its only `WorkerData`.
+pub struct InlineExecutor<WorkerData> {
+	worker_data: Mutex<WorkerData>,
+}
+
+impl<WorkerData> InlineExecutor<WorkerData> {
+	/// Construct an executor owning the given `WorkerData`.
+	#[must_use]
+	pub fn new(worker_data: WorkerData) -> Self {
+		Self {
+			worker_data: Mutex::new(worker_data),
+		}
+	}
+
+	/// Consume the executor, returning its `WorkerData`.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn into_inner(self) -> WorkerData {
+		self.worker_data.into_inner().unwrap()
+	}
+}
+
+impl<WorkerData> TaskExecutor<WorkerData> for InlineExecutor<WorkerData> {
+	fn enqueue_boxed(&self, task: BoxedTask<WorkerData>) {
+		task(&mut self.worker_data.lock().unwrap());
+	}
+}
+
+impl<WorkerData: fmt::Debug> fmt::Debug for InlineExecutor<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("InlineExecutor")
+			.field("worker_data", &self.worker_data)
+			.finish()
+	}
+}
@@ -0,0 +1,123 @@
+use std::{error, fmt, thread};
+
+use super::{ThreadPool, ThreadPoolBuilder};
+
+/// The workers whose data couldn't be constructed by a fallible factory, e.g. the one passed
+/// to [`ThreadPool::try_builder`].
+#[derive(Debug)]
+pub struct WorkerDataError<Error> {
+	/// The index of each worker whose data couldn't be constructed, with the error returned
+	/// by the factory, in order of index.
+	pub failures: Vec<(usize, Error)>,
+}
+
+impl<Error: fmt::Display> fmt::Display for WorkerDataError<Error> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"failed to construct the data of {} worker(s)",
+			self.failures.len()
+		)?;
+		for (i, (index, error)) in self.failures.iter().enumerate() {
+			let separator = if i == 0 { ": " } else { ", " };
+			write!(f, "{separator}worker {index} ({error})")?;
+		}
+		Ok(())
+	}
+}
+
+impl<Error: error::Error + 'static> error::Error for WorkerDataError<Error> {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		self.failures
+			.first()
+			.map(|(_, error)| error as &(dyn error::Error + 'static))
+	}
+}
+
+/// Call `factory` with each worker index, up to `workers`. If any call fails, the data
+/// constructed by the others is dropped, in order of index, and all the failures are returned.
+fn try_workers_data<WorkerData, Error>(
+	workers: usize,
+	factory: impl FnMut(usize) -> Result<WorkerData, Error>,
+) -> Result<Vec<WorkerData>, WorkerDataError<Error>> {
+	let mut workers_data = Vec::with_capacity(workers);
+	let mut failures = Vec::new();
+	for (index, built) in (0..workers).map(factory).enumerate() {
+		match built {
+			Ok(data) => workers_data.push(data),
+			Err(error) => failures.push((index, error)),
+		}
+	}
+	if failures.is_empty() {
+		Ok(workers_data)
+	} else {
+		debug!(
+			"failed to construct worker data, dropping the rest",
+			failed = failures.len(),
+			constructed = workers_data.len(),
+		);
+		Err(WorkerDataError { failures })
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Construct a [`ThreadPoolBuilder`] for `workers` workers, calling `factory` with each
+	/// worker index to build its `WorkerData`, e.g. to open a connection per worker.
+	///
+	/// Every worker index is tried, even after a failure, so that the error lists all the
+	/// workers whose data couldn't be constructed. The data constructed for the other
+	/// workers is then dropped, in order of index, before returning.
+	///
+	/// # Errors
+	/// - if `factory` failed for at least one worker.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let error = ThreadPool::try_builder(4, |i| {
+	/// 	if i % 2 == 0 {
+	/// 		Ok(format!("connection {i}"))
+	/// 	} else {
+	/// 		Err(format!("host {i} unreachable"))
+	/// 	}
+	/// })
+	/// .unwrap_err();
+	///
+	/// assert_eq!(
+	/// 	error.to_string(),
+	/// 	"failed to construct the data of 2 worker(s): \
+	/// 	 worker 1 (host 1 unreachable), worker 3 (host 3 unreachable)",
+	/// );
+	///
+	/// let pool = ThreadPool::try_builder(4, |i| Ok::<_, String>(format!("connection {i}")))
+	/// 	.unwrap()
+	/// 	.build();
+	/// pool.enqueue(|connection| println!("using {connection}"));
+	/// ```
+	pub fn try_builder<Error>(
+		workers: usize,
+		factory: impl FnMut(usize) -> Result<WorkerData, Error>,
+	) -> Result<ThreadPoolBuilder<WorkerData>, WorkerDataError<Error>> {
+		try_workers_data(workers, factory).map(Self::builder)
+	}
+
+	/// Like [`ThreadPool::for_blocking_io`], with a fallible `factory`, see
+	/// [`ThreadPool::try_builder`].
+	///
+	/// # Errors
+	/// - if `factory` failed for at least one worker.
+	pub fn try_for_blocking_io<Error>(
+		factory: impl FnMut(usize) -> Result<WorkerData, Error>,
+	) -> Result<Self, WorkerDataError<Error>>
+	where
+		WorkerData: 'static,
+	{
+		let workers = thread::available_parallelism().map_or(1, usize::from)
+			* Self::BLOCKING_IO_WORKERS_PER_CORE;
+		Ok(Self::try_builder(workers, factory)?
+			.max_pending_tasks(workers * Self::BLOCKING_IO_TASKS_PER_WORKER)
+			.build())
+	}
+}
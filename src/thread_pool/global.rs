@@ -0,0 +1,49 @@
+use std::{sync::OnceLock, thread};
+
+use super::ThreadPool;
+
+static GLOBAL: OnceLock<ThreadPool> = OnceLock::new();
+
+/// Get the process-wide thread pool, initializing it on first access.
+///
+/// Unless configured beforehand via [`init_global`], the pool is created with one worker
+/// per available core (as reported by [`std::thread::available_parallelism`]).
+///
+/// Note: being stored in a static, the global pool is never joined. Tasks that are still
+/// pending or running when the main thread returns are abandoned.
+///
+/// # Examples
+///
+/// ```
+/// lending_thread_pool::global().enqueue(|_| {
+/// 	println!("Hello from the global pool");
+/// });
+/// ```
+pub fn global() -> &'static ThreadPool {
+	GLOBAL.get_or_init(|| {
+		let workers = thread::available_parallelism().map_or(1, usize::from);
+		ThreadPool::new(vec![(); workers])
+	})
+}
+
+/// Configure the process-wide thread pool. This should be called once at startup,
+/// before any call to [`global`].
+///
+/// # Errors
+/// - if the global pool has already been initialized, in which case the provided
+///   pool is handed back.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::{global, init_global, ThreadPool};
+///
+/// init_global(ThreadPool::new_with_queue_size(vec![(); 2], 64)).unwrap();
+///
+/// global().enqueue(|_| {
+/// 	println!("Hello from the global pool");
+/// });
+/// ```
+pub fn init_global(pool: ThreadPool) -> Result<(), ThreadPool> {
+	GLOBAL.set(pool)
+}
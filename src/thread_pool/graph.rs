@@ -0,0 +1,312 @@
+use std::{
+	any::Any,
+	fmt,
+	panic::{self, AssertUnwindSafe, Location},
+	sync::{Arc, Condvar, Mutex, Weak},
+	thread,
+};
+
+use super::{BoxedTask, Task, ThreadPool, ThreadPoolShared};
+
+/// A set of tasks with dependencies between them, run with [`ThreadPool::run_graph`].
+/// Each task starts as soon as all of its dependencies have completed, so independent
+/// tasks run in parallel, e.g. the units of a build sharing the compilers owned by the workers.
+///
+/// A task can only depend on tasks added before it, so the graph never has cycles.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use lending_thread_pool::{TaskGraph, ThreadPool};
+///
+/// let pool = ThreadPool::new(vec![(); 4]);
+/// let log = Arc::new(Mutex::new(Vec::new()));
+/// let step = |name: &'static str| {
+/// 	let log = log.clone();
+/// 	move |_: &mut ()| log.lock().unwrap().push(name)
+/// };
+///
+/// let mut graph = TaskGraph::new();
+/// let parse = graph.add(step("parse")).id();
+/// let check = graph.add(step("check")).depends_on(parse).id();
+/// let docs = graph.add(step("docs")).depends_on(parse).id();
+/// graph.add(step("link")).depends_on(check).depends_on(docs);
+///
+/// pool.run_graph(graph).unwrap();
+///
+/// let log = log.lock().unwrap();
+/// assert_eq!(log.first(), Some(&"parse"));
+/// assert_eq!(log.last(), Some(&"link"));
+/// ```
+pub struct TaskGraph<WorkerData> {
+	nodes: Vec<Node<WorkerData>>,
+}
+
+struct Node<WorkerData> {
+	task: BoxedTask<WorkerData>,
+	dependencies: Vec<usize>,
+}
+
+/// Identifies a task of a [`TaskGraph`], to declare that other tasks depend on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// A task just added to a [`TaskGraph`], see [`TaskGraph::add`].
+pub struct GraphNode<'graph, WorkerData> {
+	graph: &'graph mut TaskGraph<WorkerData>,
+	index: usize,
+}
+
+impl<WorkerData> TaskGraph<WorkerData> {
+	/// Construct an empty graph.
+	#[must_use]
+	pub fn new() -> Self {
+		Self { nodes: Vec::new() }
+	}
+
+	/// Add a task to the graph, returning a [`GraphNode`] to declare its dependencies.
+	pub fn add(
+		&mut self,
+		task: impl FnOnce(&mut WorkerData) + Send + 'static,
+	) -> GraphNode<'_, WorkerData> {
+		self.nodes.push(Node {
+			task: Box::new(task),
+			dependencies: Vec::new(),
+		});
+		GraphNode {
+			index: self.nodes.len() - 1,
+			graph: self,
+		}
+	}
+
+	/// The number of tasks in the graph.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// Whether the graph has no tasks.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+}
+
+impl<WorkerData> Default for TaskGraph<WorkerData> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<WorkerData> GraphNode<'_, WorkerData> {
+	/// Only start this task once the given one has completed.
+	///
+	/// # Panics
+	/// - if `other` doesn't belong to this graph.
+	#[must_use = "call id() to depend on this task"]
+	pub fn depends_on(self, other: NodeId) -> Self {
+		assert!(
+			other.0 < self.index,
+			"the dependency must be a task of the same graph"
+		);
+		self.graph.nodes[self.index].dependencies.push(other.0);
+		self
+	}
+
+	/// The identifier of this task, to make other tasks depend on it.
+	#[must_use]
+	pub fn id(&self) -> NodeId {
+		NodeId(self.index)
+	}
+}
+
+impl<WorkerData> fmt::Debug for TaskGraph<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TaskGraph")
+			.field("len", &self.nodes.len())
+			.finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData> fmt::Debug for GraphNode<'_, WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("GraphNode")
+			.field("id", &self.index)
+			.field("dependencies", &self.graph.nodes[self.index].dependencies)
+			.finish()
+	}
+}
+
+struct GraphShared<WorkerData> {
+	state: Mutex<GraphState<WorkerData>>,
+	condvar: Condvar,
+}
+
+struct GraphState<WorkerData> {
+	/// Taken when the task is enqueued.
+	tasks: Vec<Option<BoxedTask<WorkerData>>>,
+	dependents: Vec<Vec<usize>>,
+	/// Dependencies that haven't completed yet, for each task.
+	waiting_on: Vec<usize>,
+	/// Tasks that haven't run nor been skipped yet.
+	unfinished: usize,
+	/// The payload of the first task that panicked.
+	panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<WorkerData> GraphState<WorkerData> {
+	/// Record the outcome of a task, returning the tasks that became ready to run
+	/// along with the ones skipped because they depend on a failed one.
+	fn finish(&mut self, node: usize, completed: bool) -> (Vec<usize>, Vec<BoxedTask<WorkerData>>) {
+		self.unfinished -= 1;
+		let mut ready = Vec::new();
+		let mut skipped = Vec::new();
+		if completed {
+			for &dependent in &self.dependents[node] {
+				self.waiting_on[dependent] -= 1;
+				if self.waiting_on[dependent] == 0 {
+					ready.push(dependent);
+				}
+			}
+		} else {
+			let mut stack = self.dependents[node].clone();
+			while let Some(dependent) = stack.pop() {
+				if let Some(task) = self.tasks[dependent].take() {
+					self.unfinished -= 1;
+					skipped.push(task);
+					stack.extend_from_slice(&self.dependents[dependent]);
+				}
+			}
+		}
+		(ready, skipped)
+	}
+}
+
+/// A task of a running graph, reporting its outcome when dropped, even if it was discarded
+/// by the pool without running.
+struct GraphTask<WorkerData: Send + 'static> {
+	shared: Arc<GraphShared<WorkerData>>,
+	pool: Weak<ThreadPoolShared<WorkerData>>,
+	location: &'static Location<'static>,
+	node: usize,
+	completed: bool,
+}
+
+impl<WorkerData: Send + 'static> GraphTask<WorkerData> {
+	fn into_task(self) -> Task<WorkerData> {
+		let location = self.location;
+		let mut graph_task = self;
+		Task {
+			location,
+			..Task::new(move |worker_data: &mut WorkerData| graph_task.run(worker_data))
+		}
+	}
+
+	fn run(&mut self, worker_data: &mut WorkerData) {
+		let task = self.shared.state.lock().unwrap().tasks[self.node].take();
+		let Some(task) = task else {
+			return;
+		};
+		match panic::catch_unwind(AssertUnwindSafe(|| task(worker_data))) {
+			Ok(()) => self.completed = true,
+			Err(payload) => {
+				debug!("graph task panicked, skipping the tasks depending on it");
+				self.shared
+					.state
+					.lock()
+					.unwrap()
+					.panic
+					.get_or_insert(payload);
+			}
+		}
+	}
+}
+
+impl<WorkerData: Send + 'static> Drop for GraphTask<WorkerData> {
+	fn drop(&mut self) {
+		let mut state = self.shared.state.lock().unwrap();
+		// Still there if discarded by the pool rather than run.
+		let discarded = state.tasks[self.node].take();
+		let (ready, skipped) = state.finish(self.node, self.completed);
+		drop(state);
+		drop((discarded, skipped));
+		self.shared.condvar.notify_all();
+		let Some(pool) = self.pool.upgrade() else {
+			return;
+		};
+		for node in ready {
+			pool.requeue(
+				GraphTask {
+					shared: self.shared.clone(),
+					pool: self.pool.clone(),
+					location: self.location,
+					node,
+					completed: false,
+				}
+				.into_task(),
+			);
+		}
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Run the tasks of a [`TaskGraph`], each one as soon as its dependencies have completed,
+	/// and block until all of them have finished.
+	///
+	/// Tasks whose dependencies have completed are put at the end of the queue by the worker
+	/// that completed the last one, regardless of the capacity of the queue, so that workers
+	/// are never blocked on a full queue.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the tasks of the graph to complete, and for the
+	/// task queue to have room for the ones without dependencies.
+	///
+	/// # Errors
+	/// - the payload of the first task that panicked. The tasks depending on it, directly
+	///   or not, are skipped, while the other ones still run.
+	#[allow(clippy::missing_panics_doc)]
+	#[track_caller]
+	pub fn run_graph(&self, graph: TaskGraph<WorkerData>) -> thread::Result<()> {
+		let location = Location::caller();
+		let mut dependents = vec![Vec::new(); graph.nodes.len()];
+		let mut waiting_on = Vec::with_capacity(graph.nodes.len());
+		let mut tasks = Vec::with_capacity(graph.nodes.len());
+		for (node, Node { task, dependencies }) in graph.nodes.into_iter().enumerate() {
+			for &dependency in &dependencies {
+				dependents[dependency].push(node);
+			}
+			waiting_on.push(dependencies.len());
+			tasks.push(Some(task));
+		}
+		let roots: Vec<usize> = (0..tasks.len()).filter(|&i| waiting_on[i] == 0).collect();
+		let shared = Arc::new(GraphShared {
+			state: Mutex::new(GraphState {
+				unfinished: tasks.len(),
+				tasks,
+				dependents,
+				waiting_on,
+				panic: None,
+			}),
+			condvar: Condvar::default(),
+		});
+		for node in roots {
+			self.enqueue_task(
+				GraphTask {
+					shared: shared.clone(),
+					pool: Arc::downgrade(&self.inner),
+					location,
+					node,
+					completed: false,
+				}
+				.into_task(),
+			);
+		}
+		let mut state = shared
+			.condvar
+			.wait_while(shared.state.lock().unwrap(), |state| state.unfinished > 0)
+			.unwrap();
+		state.panic.take().map_or(Ok(()), Err)
+	}
+}
@@ -0,0 +1,174 @@
+use std::sync::{mpsc, Arc};
+
+use super::{
+	label, padded::CachePadded, worker, PoolEvent, PoolQueue, SpawnFailurePolicy, Task, ThreadPool,
+	WorkerShared,
+};
+
+impl<WorkerData: Send + Clone + 'static> ThreadPool<WorkerData> {
+	/// Spawn `count` more workers, each with a clone of the data of an existing worker,
+	/// e.g. to scale up when the load picks up. Use [`ThreadPool::grow_from`] to clone
+	/// a template instead.
+	///
+	/// Does nothing if the pool has been shut down, e.g. by [`ThreadPool::abort`].
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for a worker to clone its data, which happens
+	/// once the tasks enqueued before have been picked up.
+	///
+	/// # Panics
+	///
+	/// - if all workers were brought down by a panic (see [`ThreadPool::dead_workers`])
+	/// - if a thread couldn't be spawned, unless the pool
+	///   [degrades](crate::SpawnFailurePolicy::Degrade)
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let mut pool = ThreadPool::new(vec![String::from("config"); 2]);
+	/// pool.grow(2);
+	///
+	/// assert_eq!(pool.status().workers.len(), 4);
+	/// assert_eq!(pool.stop().into_workers_data(), vec!["config"; 4]);
+	/// ```
+	pub fn grow(&mut self, count: usize) {
+		assert!(
+			self.dead_workers().len() < self.inner.workers().len(),
+			"all workers are dead"
+		);
+		let (sender, receiver) = mpsc::sync_channel(1);
+		self.inner.enqueue_task(self::Task {
+			budgeted: false,
+			admitted: true,
+			..self::Task::new(move |worker_data: &mut WorkerData| {
+				let _ = sender.send(worker_data.clone());
+			})
+		});
+		// The task is dropped without running if the pool is shut down.
+		let Ok(template) = receiver.recv() else {
+			debug!("pool is shut down, not growing");
+			return;
+		};
+		self.grow_from(&template, count);
+	}
+
+	/// Spawn `count` more workers, each with a clone of `template`, see [`ThreadPool::grow`].
+	///
+	/// The new workers are spawned like the others, through the
+	/// [`thread_spawner`](crate::ThreadPoolBuilder::thread_spawner) if one is set. They
+	/// don't belong to any [`worker_group`](crate::ThreadPoolBuilder::worker_group) and
+	/// they skip the [`warmup`](crate::ThreadPoolBuilder::warmup). The queue size is
+	/// unchanged.
+	///
+	/// If a thread can't be spawned, the pool keeps the workers spawned before the failure
+	/// when it [degrades](crate::SpawnFailurePolicy::Degrade), and the ones it had before
+	/// growing otherwise.
+	///
+	/// Does nothing if the pool has been shut down, e.g. by [`ThreadPool::abort`].
+	///
+	/// # Panics
+	///
+	/// - if a thread couldn't be spawned, unless the pool
+	///   [degrades](crate::SpawnFailurePolicy::Degrade)
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let mut pool = ThreadPool::new(vec![0_u64; 1]);
+	/// pool.grow_from(&0, 3);
+	///
+	/// for i in 0..16 {
+	/// 	pool.enqueue(move |sum| *sum += i);
+	/// }
+	///
+	/// let sums = pool.stop().into_workers_data();
+	/// assert_eq!(sums.len(), 4);
+	/// assert_eq!(sums.iter().sum::<u64>(), 120);
+	/// ```
+	///
+	/// ```
+	/// use std::{
+	/// 	io,
+	/// 	sync::atomic::{AtomicUsize, Ordering},
+	/// 	thread,
+	/// };
+	///
+	/// use lending_thread_pool::{SpawnFailurePolicy, ThreadPool};
+	///
+	/// static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+	///
+	/// let mut pool = ThreadPool::builder(vec![(); 2])
+	/// 	.spawn_failure_policy(SpawnFailurePolicy::Degrade)
+	/// 	.thread_spawner(|name: String, f: Box<dyn FnOnce() + Send>| -> io::Result<()> {
+	/// 		if SPAWNED.fetch_add(1, Ordering::Relaxed) == 3 {
+	/// 			return Err(io::Error::other("thread limit reached"));
+	/// 		}
+	/// 		thread::Builder::new().name(name).spawn(f).map(drop)
+	/// 	})
+	/// 	.build();
+	/// pool.grow_from(&(), 4);
+	///
+	/// // The 4th thread couldn't be spawned.
+	/// assert_eq!(pool.status().workers.len(), 3);
+	/// pool.enqueue(|()| println!("still running"));
+	/// ```
+	pub fn grow_from(&mut self, template: &WorkerData, count: usize) {
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			debug!("pool is shut down, not growing");
+			return;
+		};
+		let first = self.inner.workers().len();
+		tasks.add_workers(count);
+		if let Some(capabilities) = &self.inner.worker_capabilities {
+			for index in first..first + count {
+				tasks.set_capabilities(index, &capabilities.of(index, template));
+			}
+		}
+		let mut workers = self.inner.workers.write().unwrap();
+		workers.extend((first..first + count).map(|index| {
+			let label = self.inner.worker_label.as_ref();
+			Arc::new(CachePadded::new(WorkerShared::new(index, template, label)))
+		}));
+		drop(workers);
+		drop(guard);
+
+		debug!("growing pool", from = first, to = first + count);
+		for index in first..first + count {
+			let name = label::thread_name(index, self.inner.worker(index).label.as_deref());
+			let inner = self.inner.clone();
+			let worker_data = template.clone();
+			let thread = self.inner.spawn_thread(name.clone(), move || {
+				worker::run(index, &inner, None, worker_data)
+			});
+			match thread {
+				Ok(thread) => self.workers.push(thread),
+				Err(error) => {
+					let degrade = self.inner.spawn_failure_policy == SpawnFailurePolicy::Degrade;
+					// Only the workers spawned before the failure may be kept.
+					let kept = if degrade { index } else { first };
+					drop(self.shrink(self.workers.len() - kept));
+					assert!(degrade, "thread to be spawned: {error:?}");
+					warn!(
+						"failed to spawn all the new workers, running with fewer",
+						requested = first + count,
+						spawned = index,
+						error = error,
+					);
+					self.inner.listeners.emit(PoolEvent::SpawnFailed {
+						thread: &name,
+						requested: first + count,
+						spawned: index,
+						inline: false,
+					});
+					return;
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,283 @@
+use std::{
+	fmt, mem,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, Condvar, Mutex,
+	},
+};
+
+use super::{steal::OnWait, Task, ThreadPool};
+
+/// Handle of the tasks enqueued without one.
+pub(crate) const NO_HANDLE: u64 = 0;
+
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(NO_HANDLE + 1);
+
+/// Tracks the completion of a task, as returned by [`ThreadPool::enqueue_with_handle`] and
+/// [`ThreadPool::enqueue_after`].
+///
+/// Cloning a `TaskHandle` yields a handle to the same task.
+#[derive(Clone, Default)]
+pub struct TaskHandle {
+	shared: Arc<HandleShared>,
+}
+
+struct HandleShared {
+	/// Identifies the task in the queue, see [`Task::handle`].
+	id: u64,
+	state: Mutex<HandleState>,
+	condvar: Condvar,
+	/// Runs or boosts the task if it's still queued, see [`TaskHandle::wait`].
+	on_wait: Mutex<Option<OnWait>>,
+}
+
+impl Default for HandleShared {
+	fn default() -> Self {
+		Self {
+			id: NEXT_HANDLE.fetch_add(1, Ordering::Relaxed),
+			state: Mutex::default(),
+			condvar: Condvar::new(),
+			on_wait: Mutex::new(None),
+		}
+	}
+}
+
+enum HandleState {
+	/// Holds what to do once the task completes, i.e. enqueue the tasks depending on it.
+	Pending(Vec<Box<dyn FnOnce() + Send>>),
+	Completed,
+	/// The task panicked, or was discarded without running.
+	Failed,
+}
+
+impl TaskHandle {
+	/// Whether the task has finished, either by completing or by failing to, i.e. panicking
+	/// or being discarded without running.
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn is_finished(&self) -> bool {
+		matches!(
+			*self.shared.state.lock().unwrap(),
+			HandleState::Completed | HandleState::Failed
+		)
+	}
+
+	/// Whether the task has completed, as opposed to still pending or failed.
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn is_completed(&self) -> bool {
+		matches!(*self.shared.state.lock().unwrap(), HandleState::Completed)
+	}
+
+	/// Block until the task has finished.
+	///
+	/// If the task is still queued and its pool lends data to the callers, see
+	/// [`ThreadPoolBuilder::caller_runs`](crate::ThreadPoolBuilder::caller_runs), and isn't
+	/// [scoped](crate::ThreadPool::new_scoped), it's taken
+	/// out of the queue and run on the calling thread instead, saving the round-trip through
+	/// a worker. Tasks that must run on a given worker or group, that belong to a class
+	/// limiting its running tasks, or whose pool enforces a
+	/// [`strict_start_order`](crate::ThreadPoolBuilder::strict_start_order) are left queued.
+	/// The stolen task is still waited for by [`ThreadPool::flush`](crate::ThreadPool::flush)
+	/// and [`ThreadPool::join`](crate::ThreadPool::join), and if it panics, the panic is
+	/// propagated to the caller. Otherwise, the task
+	/// is moved to the front of the queue if the pool boosts waited-on tasks, see
+	/// [`ThreadPoolBuilder::boost_waited_tasks`](crate::ThreadPoolBuilder::boost_waited_tasks).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{sync::mpsc, thread};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 1]).caller_runs(()).build();
+	///
+	/// let (unblock, blocked) = mpsc::channel::<()>();
+	/// pool.enqueue(move |()| blocked.recv().unwrap());
+	///
+	/// let (sender, receiver) = mpsc::channel();
+	/// let handle = pool.enqueue_with_handle(move |()| sender.send(thread::current().id()).unwrap());
+	/// // The only worker is busy, so the task is run by the waiting thread.
+	/// handle.wait();
+	/// assert_eq!(receiver.recv().unwrap(), thread::current().id());
+	/// unblock.send(()).unwrap();
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	pub fn wait(&self) {
+		let on_wait = self.shared.on_wait.lock().unwrap().take();
+		if let Some(on_wait) = on_wait {
+			on_wait.run(self.shared.id);
+		}
+		let _state = self
+			.shared
+			.condvar
+			.wait_while(self.shared.state.lock().unwrap(), |state| {
+				matches!(state, HandleState::Pending(_))
+			})
+			.unwrap();
+	}
+
+	/// Let [`TaskHandle::wait`] run or boost the task if it's still queued.
+	pub(crate) fn set_on_wait(&self, on_wait: OnWait) {
+		*self.shared.on_wait.lock().unwrap() = Some(on_wait);
+	}
+
+	pub(crate) fn id(&self) -> u64 {
+		self.shared.id
+	}
+
+	/// Wrap `task` so that it updates this handle when it completes, or when it's dropped
+	/// without completing.
+	pub(crate) fn track<WorkerData>(
+		&self,
+		task: impl FnOnce(&mut WorkerData) + Send + 'static,
+	) -> impl FnOnce(&mut WorkerData) + Send + 'static {
+		let completion = Completion(Some(self.shared.clone()));
+		move |worker_data| {
+			let mut completion = completion;
+			task(worker_data);
+			if let Some(shared) = completion.0.take() {
+				shared.finish(HandleState::Completed);
+			}
+		}
+	}
+}
+
+impl HandleShared {
+	fn finish(&self, outcome: HandleState) {
+		let completed = matches!(outcome, HandleState::Completed);
+		let previous = mem::replace(&mut *self.state.lock().unwrap(), outcome);
+		self.condvar.notify_all();
+		if let HandleState::Pending(dependents) = previous {
+			if completed {
+				for dependent in dependents {
+					dependent();
+				}
+			} else if !dependents.is_empty() {
+				debug!("task failed, discarding the tasks depending on it");
+			}
+		}
+	}
+}
+
+impl Default for HandleState {
+	fn default() -> Self {
+		Self::Pending(Vec::new())
+	}
+}
+
+/// Marks the task as failed if dropped before completing, e.g. while unwinding.
+struct Completion(Option<Arc<HandleShared>>);
+
+impl Drop for Completion {
+	fn drop(&mut self) {
+		if let Some(shared) = self.0.take() {
+			shared.finish(HandleState::Failed);
+		}
+	}
+}
+
+impl fmt::Debug for TaskHandle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let state = match *self.shared.state.lock().unwrap() {
+			HandleState::Pending(_) => "Pending",
+			HandleState::Completed => "Completed",
+			HandleState::Failed => "Failed",
+		};
+		f.debug_struct("TaskHandle").field("state", &state).finish()
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a task, returning a [`TaskHandle`] to wait for it or to enqueue
+	/// other tasks after it with [`ThreadPool::enqueue_after`].
+	///
+	/// Tracking costs an allocation per task and a lock when it completes, which
+	/// [`ThreadPool::enqueue`] doesn't pay for, so prefer the latter when the handle
+	/// isn't needed.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	#[track_caller]
+	pub fn enqueue_with_handle<Task>(&self, task: Task) -> TaskHandle
+	where
+		Task: FnOnce(&mut WorkerData) + Send + 'static,
+	{
+		let handle = TaskHandle::default();
+		let task = self
+			.inner
+			.waitable(&handle, self::Task::new(handle.track(task)));
+		self.enqueue_task(task);
+		handle
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Enqueue a task that only becomes runnable once the task tracked by `after` has completed,
+	/// so that dependency chains can be expressed without blocking in between. The task is
+	/// discarded if `after` panics or is discarded itself.
+	///
+	/// Once `after` completes, the task is put at the end of the queue by the worker that ran it,
+	/// regardless of the capacity of the queue, so that workers are never blocked on a full queue.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking only if `after` has already completed, in which case it waits
+	/// for the task queue to have at least one empty slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 4]);
+	/// let (tx, rx) = mpsc::channel();
+	///
+	/// let download = pool.enqueue_with_handle({
+	/// 	let tx = tx.clone();
+	/// 	move |()| tx.send("download").unwrap()
+	/// });
+	/// let unpack = pool.enqueue_after(&download, {
+	/// 	let tx = tx.clone();
+	/// 	move |()| tx.send("unpack").unwrap()
+	/// });
+	/// let install = pool.enqueue_after(&unpack, move |()| tx.send("install").unwrap());
+	///
+	/// install.wait();
+	/// assert!(install.is_completed());
+	/// assert_eq!(rx.iter().collect::<Vec<_>>(), ["download", "unpack", "install"]);
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	#[track_caller]
+	pub fn enqueue_after<Task>(&self, after: &TaskHandle, task: Task) -> TaskHandle
+	where
+		Task: FnOnce(&mut WorkerData) + Send + 'static,
+	{
+		let handle = TaskHandle::default();
+		let task = self
+			.inner
+			.waitable(&handle, self::Task::new(handle.track(task)));
+		let mut state = after.shared.state.lock().unwrap();
+		match &mut *state {
+			HandleState::Pending(dependents) => {
+				let inner = Arc::downgrade(&self.inner);
+				dependents.push(Box::new(move || {
+					if let Some(inner) = inner.upgrade() {
+						inner.requeue(task);
+					}
+				}));
+			}
+			HandleState::Completed => {
+				drop(state);
+				self.enqueue_task(task);
+			}
+			HandleState::Failed => {
+				debug!("task failed, discarding the tasks depending on it");
+			}
+		}
+		handle
+	}
+}
@@ -0,0 +1,207 @@
+use std::{
+	collections::HashMap,
+	fmt, io,
+	panic::Location,
+	sync::{Arc, Mutex},
+	thread::Thread,
+	time::Duration,
+};
+
+use super::{
+	label,
+	spawner::{PoolThread, Spawner},
+	worker, PoolQueue, ThreadPoolBuilder, ThreadPoolShared,
+};
+
+/// How often the watchdog checks the workers, at most.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A worker that was replaced because it kept running the same task for too long,
+/// see [`ThreadPoolBuilder::replace_hung_workers`].
+#[derive(Debug, Clone)]
+pub struct StrandedWorker {
+	/// The index of the worker.
+	pub worker: usize,
+	/// The thread left running the task, which exits as soon as the task returns.
+	pub thread: Thread,
+	/// The name of the task, if it was enqueued with one.
+	pub name: Option<String>,
+	/// Where the task was enqueued from.
+	pub location: &'static Location<'static>,
+	/// How long the task had been running for when the worker was replaced.
+	pub elapsed: Duration,
+}
+
+type SpawnReplacement<WorkerData> =
+	fn(&Arc<ThreadPoolShared<WorkerData>>, usize) -> io::Result<PoolThread<WorkerData>>;
+
+/// Replaces the workers stuck on a task,
+/// see [`ThreadPoolBuilder::replace_hung_workers`].
+pub(crate) struct HungWorkers<WorkerData: Send> {
+	pub(crate) threshold: Duration,
+	factory: Box<dyn Fn(usize) -> WorkerData + Send + Sync>,
+	on_stranded: Box<dyn Fn(StrandedWorker) + Send + Sync>,
+	/// Replacements outlive the builder's spawner, so this can only be set where
+	/// `WorkerData: 'static`.
+	spawn_replacement: SpawnReplacement<WorkerData>,
+	/// The threads replacing hung workers, indexed by worker.
+	replacements: Mutex<HashMap<usize, PoolThread<WorkerData>>>,
+	/// Held by the watchdog while it looks for hung workers, see
+	/// [`ThreadPoolShared::stop_watchdog`].
+	scanning: Mutex<()>,
+}
+
+impl<WorkerData: Send> fmt::Debug for HungWorkers<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("HungWorkers")
+			.field("threshold", &self.threshold)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPoolBuilder<WorkerData> {
+	/// Replace the workers that have been running the same task for at least `threshold`,
+	/// e.g. stuck on a call that never times out, so that the throughput of the pool recovers.
+	/// The replacement runs on a new thread with data built by `factory`, which is called with
+	/// the worker index, while the stranded thread is reported to `on_stranded` and exits
+	/// as soon as its task returns, dropping its data.
+	///
+	/// Workers are checked at least every 50 milliseconds by a background thread, and how
+	/// long they've been running their task is measured with the [`clock`](Self::clock)
+	/// of the pool. Replacements are spawned through the
+	/// [`thread_spawner`](Self::thread_spawner), if any, and are joined in place of the
+	/// stranded threads.
+	///
+	/// Defaults to leaving hung workers alone.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{sync::mpsc, time::Duration};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 1])
+	/// 	.replace_hung_workers(Duration::from_millis(100), |_| (), |stranded| {
+	/// 		println!("worker {} is stuck on a task from {}", stranded.worker, stranded.location);
+	/// 	})
+	/// 	.build();
+	///
+	/// let (unblock, blocked) = mpsc::channel::<()>();
+	/// pool.enqueue(move |()| {
+	/// 	let _ = blocked.recv();
+	/// });
+	///
+	/// assert_eq!(pool.install(|()| "served by the replacement"), "served by the replacement");
+	/// drop(unblock);
+	/// ```
+	pub fn replace_hung_workers(
+		mut self,
+		threshold: Duration,
+		factory: impl Fn(usize) -> WorkerData + Send + Sync + 'static,
+		on_stranded: impl Fn(StrandedWorker) + Send + Sync + 'static,
+	) -> Self {
+		self.hung_workers = Some(HungWorkers {
+			threshold,
+			factory: Box::new(factory),
+			on_stranded: Box::new(on_stranded),
+			spawn_replacement,
+			replacements: Mutex::default(),
+			scanning: Mutex::default(),
+		});
+		self
+	}
+}
+
+fn spawn_replacement<WorkerData: Send + 'static>(
+	inner: &Arc<ThreadPoolShared<WorkerData>>,
+	index: usize,
+) -> io::Result<PoolThread<WorkerData>> {
+	let name = label::thread_name(index, inner.worker(index).label.as_deref());
+	let pool = inner.clone();
+	inner.spawn_thread(name, move || {
+		let hung_workers = pool.hung_workers.as_ref().unwrap();
+		let worker_data = (hung_workers.factory)(index);
+		worker::run(index, &pool, None, worker_data)
+	})
+}
+
+/// Spawn a thread replacing the workers that have been running the same task for longer
+/// than the threshold, until the pool is shut down. The thread isn't joined, as it sleeps
+/// on the clock of the pool, which may never wake it up if it's a [`ManualClock`](super::ManualClock).
+/// Hung workers are left alone if the thread can't be spawned.
+///
+/// # Panics
+/// - if the thread can't be spawned and the
+///   [`SpawnFailurePolicy`](crate::SpawnFailurePolicy) is
+///   [`Panic`](crate::SpawnFailurePolicy::Panic)
+#[track_caller]
+pub(crate) fn spawn_watchdog<'a, WorkerData: Send + 'a>(
+	spawner: &impl Spawner<'a>,
+	inner: &Arc<ThreadPoolShared<WorkerData>>,
+) -> Option<PoolThread<()>> {
+	let pool = inner.clone();
+	spawner
+		.spawn("watchdog".to_string(), move || {
+			let inner = pool;
+			let hung_workers = inner.hung_workers.as_ref().unwrap();
+			let interval = (hung_workers.threshold / 4).min(POLL_INTERVAL);
+			loop {
+				inner.clock.sleep(interval);
+				let _scanning = hung_workers.scanning.lock().unwrap();
+				if matches!(*inner.pending_tasks.lock().unwrap(), PoolQueue::Done) {
+					return;
+				}
+				// Stranding a worker may take the queue lock, so the workers aren't kept locked.
+				let workers = inner.workers().clone();
+				for (index, worker) in workers.iter().enumerate() {
+					let replace = || {
+						let replacement = (hung_workers.spawn_replacement)(&inner, index)?;
+						hung_workers
+							.replacements
+							.lock()
+							.unwrap()
+							.insert(index, replacement);
+						Ok(())
+					};
+					match worker.strand(index, &inner, hung_workers.threshold, replace) {
+						None => {}
+						Some(Ok(stranded)) => {
+							warn!(
+								"replacing hung worker",
+								worker = index,
+								location = stranded.location,
+								elapsed = stranded.elapsed,
+							);
+							(hung_workers.on_stranded)(stranded);
+						}
+						// Reported with the worker released, as listeners may look at it.
+						Some(Err(error)) => inner.helper_spawn_failed(
+							&label::thread_name(index, worker.label.as_deref()),
+							"leaving the hung worker alone",
+							&error,
+						),
+					}
+				}
+			}
+		})
+		.inspect_err(|error| {
+			inner.helper_spawn_failed("watchdog", "leaving hung workers alone", error);
+		})
+		.ok()
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Wait for the watchdog to be done looking for hung workers, if it's doing so. Once the
+	/// queue is shut down, it won't look for them again, so no more workers are replaced.
+	pub(crate) fn stop_watchdog(&self) {
+		if let Some(hung_workers) = &self.hung_workers {
+			drop(hung_workers.scanning.lock().unwrap());
+		}
+	}
+
+	/// Take the thread that replaced the given worker, if it was stranded.
+	pub(crate) fn take_replacement(&self, index: usize) -> Option<PoolThread<WorkerData>> {
+		let hung_workers = self.hung_workers.as_ref()?;
+		hung_workers.replacements.lock().unwrap().remove(&index)
+	}
+}
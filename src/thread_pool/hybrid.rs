@@ -0,0 +1,96 @@
+use std::thread;
+
+use super::{ThreadPool, WorkerDataError};
+
+/// The worker group running the tasks enqueued with [`ThreadPool::enqueue_cpu`].
+const CPU_GROUP: &str = "cpu";
+/// The worker group running the tasks enqueued with [`ThreadPool::enqueue_io`].
+const IO_GROUP: &str = "io";
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Construct a thread pool with two sets of workers: one worker per core (as reported by
+	/// [`std::thread::available_parallelism`]) for CPU-bound tasks, enqueued with
+	/// [`ThreadPool::enqueue_cpu`], and as many workers as [`ThreadPool::for_blocking_io`]
+	/// would spawn for tasks that block on IO, enqueued with [`ThreadPool::enqueue_io`].
+	///
+	/// Both sets share the lifecycle and the metrics of the pool, so a single
+	/// [`ThreadPool::join`] waits for all the tasks. Tasks enqueued with [`ThreadPool::enqueue`]
+	/// can run on any worker.
+	///
+	/// `factory` is called with each worker index to build its `WorkerData`, CPU workers first.
+	/// The sets are [worker groups](crate::ThreadPoolBuilder::worker_group) named `"cpu"` and
+	/// `"io"`, which can be registered on a builder to size them differently.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::fs;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::hybrid(|_| ());
+	///
+	/// pool.enqueue_io(|()| {
+	/// 	let manifest = fs::read_to_string("Cargo.toml").unwrap();
+	/// 	assert!(manifest.contains("[package]"));
+	/// });
+	/// pool.enqueue_cpu(|()| {
+	/// 	let sum: u64 = (0..1_000_000).sum();
+	/// 	assert_eq!(sum, 499_999_500_000);
+	/// });
+	/// pool.join();
+	/// ```
+	#[must_use]
+	pub fn hybrid(factory: impl FnMut(usize) -> WorkerData) -> Self {
+		let cpu_workers = thread::available_parallelism().map_or(1, usize::from);
+		let io_workers = cpu_workers * Self::BLOCKING_IO_WORKERS_PER_CORE;
+		Self::builder((0..cpu_workers + io_workers).map(factory).collect())
+			.worker_group(CPU_GROUP, cpu_workers)
+			.worker_group(IO_GROUP, io_workers)
+			.build()
+	}
+
+	/// Like [`ThreadPool::hybrid`], with a fallible `factory`, see [`ThreadPool::try_builder`].
+	///
+	/// # Errors
+	/// - if `factory` failed for at least one worker.
+	pub fn try_hybrid<Error>(
+		factory: impl FnMut(usize) -> Result<WorkerData, Error>,
+	) -> Result<Self, WorkerDataError<Error>> {
+		let cpu_workers = thread::available_parallelism().map_or(1, usize::from);
+		let io_workers = cpu_workers * Self::BLOCKING_IO_WORKERS_PER_CORE;
+		Ok(Self::try_builder(cpu_workers + io_workers, factory)?
+			.worker_group(CPU_GROUP, cpu_workers)
+			.worker_group(IO_GROUP, io_workers)
+			.build())
+	}
+
+	/// Enqueue a CPU-bound task, run by the CPU workers of a pool
+	/// constructed with [`ThreadPool::hybrid`].
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the queue of the CPU workers to have at
+	/// least one empty slot before returning.
+	///
+	/// # Panics
+	/// - if the pool has no `"cpu"` worker group.
+	#[track_caller]
+	pub fn enqueue_cpu<Task: FnOnce(&mut WorkerData) + Send + 'static>(&self, task: Task) {
+		self.enqueue_to(CPU_GROUP, task);
+	}
+
+	/// Enqueue a task that blocks on IO, run by the IO workers of a pool
+	/// constructed with [`ThreadPool::hybrid`].
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the queue of the IO workers to have at
+	/// least one empty slot before returning.
+	///
+	/// # Panics
+	/// - if the pool has no `"io"` worker group.
+	#[track_caller]
+	pub fn enqueue_io<Task: FnOnce(&mut WorkerData) + Send + 'static>(&self, task: Task) {
+		self.enqueue_to(IO_GROUP, task);
+	}
+}
@@ -0,0 +1,99 @@
+/// Which idle worker a newly enqueued task is handed to, see
+/// [`ThreadPoolBuilder::idle_worker_policy`](crate::ThreadPoolBuilder::idle_worker_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum IdleWorkerPolicy {
+	/// Whichever worker wakes up first. This is the cheapest policy, as only
+	/// one worker needs to be woken up.
+	#[default]
+	Any,
+	/// The worker that became idle last, whose caches are the most likely to still be warm.
+	MostRecentlyIdle,
+	/// The worker that has run the fewest tasks so far.
+	LeastLoaded,
+	/// The workers in turn, by index, evening out the wear of the resources they own.
+	RoundRobin,
+}
+
+/// Tracks the idle workers to pick one according to an [`IdleWorkerPolicy`].
+pub(crate) struct IdleWorkers {
+	policy: IdleWorkerPolicy,
+	/// Idle workers, in the order they became idle. Only tracked when the policy isn't `Any`.
+	idle: Vec<usize>,
+	/// Tasks run by each worker.
+	dispatched: Vec<u64>,
+	/// Worker to start looking from, for the round-robin policy.
+	next: usize,
+}
+
+impl IdleWorkers {
+	pub(crate) fn new(policy: IdleWorkerPolicy, workers: usize) -> Self {
+		Self {
+			policy,
+			idle: Vec::new(),
+			dispatched: vec![0; workers],
+			next: 0,
+		}
+	}
+
+	/// Track `count` more workers, see [`ThreadPool::grow`](crate::ThreadPool::grow).
+	pub(crate) fn add_workers(&mut self, count: usize) {
+		self.dispatched.resize(self.dispatched.len() + count, 0);
+	}
+
+	/// Stop tracking the workers from index `first` on, see
+	/// [`ThreadPool::shrink`](crate::ThreadPool::shrink).
+	pub(crate) fn remove_workers(&mut self, first: usize) {
+		self.dispatched.truncate(first);
+		self.idle.retain(|&worker| worker < first);
+		if self.next >= first {
+			self.next = 0;
+		}
+	}
+
+	/// Record that the given worker found nothing to run.
+	pub(crate) fn set_idle(&mut self, worker: usize) {
+		if self.policy != IdleWorkerPolicy::Any && !self.idle.contains(&worker) {
+			self.idle.push(worker);
+		}
+	}
+
+	/// Record that the given worker took a task.
+	pub(crate) fn set_busy(&mut self, worker: usize) {
+		self.dispatched[worker] += 1;
+		self.idle.retain(|&idle| idle != worker);
+	}
+
+	/// Pick an idle worker among the `eligible` ones according to the policy, to be claimed
+	/// with [`IdleWorkers::claim`]. Returns `None` under the `Any` policy.
+	pub(crate) fn pick(&self, eligible: impl Fn(usize) -> bool) -> Option<usize> {
+		let candidates = self.idle.iter().copied().filter(|&worker| eligible(worker));
+		let workers = self.dispatched.len();
+		match self.policy {
+			IdleWorkerPolicy::Any => None,
+			IdleWorkerPolicy::MostRecentlyIdle => candidates.last(),
+			IdleWorkerPolicy::LeastLoaded => {
+				candidates.min_by_key(|&worker| (self.dispatched[worker], worker))
+			}
+			IdleWorkerPolicy::RoundRobin => {
+				candidates.min_by_key(|&worker| (worker + workers - self.next) % workers)
+			}
+		}
+	}
+
+	/// Hand a task to the given idle worker, so that it's no longer picked.
+	pub(crate) fn claim(&mut self, worker: usize) {
+		self.next = (worker + 1) % self.dispatched.len();
+		self.idle.retain(|&idle| idle != worker);
+	}
+
+	/// Whether newly enqueued tasks may be handed to specific workers.
+	pub(crate) fn is_targeted(&self) -> bool {
+		self.policy != IdleWorkerPolicy::Any
+	}
+}
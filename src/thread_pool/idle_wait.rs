@@ -0,0 +1,128 @@
+use std::{fmt, hint, thread, time::Duration};
+
+/// How a worker waits for tasks once it has found the queue empty, registered with
+/// [`ThreadPoolBuilder::idle_wait`](crate::ThreadPoolBuilder::idle_wait). Parking right away
+/// is the cheapest on CPU, while polling for a while cuts the latency of tasks enqueued
+/// in bursts, so the right trade-off depends on the workload.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use lending_thread_pool::{IdleAction, IdleWait, ThreadPool};
+///
+/// /// Yield a few times before parking.
+/// struct YieldThenPark;
+///
+/// impl IdleWait for YieldThenPark {
+/// 	fn wait(&self, attempt: u32) -> IdleAction {
+/// 		if attempt < 3 {
+/// 			thread::yield_now();
+/// 			IdleAction::Recheck
+/// 		} else {
+/// 			IdleAction::Park
+/// 		}
+/// 	}
+/// }
+///
+/// let pool = ThreadPool::builder(vec![(); 2])
+/// 	.idle_wait(YieldThenPark)
+/// 	.build();
+///
+/// pool.enqueue(|()| println!("Hello"));
+/// ```
+pub trait IdleWait: Send + Sync {
+	/// Called without holding any lock each time the worker finds no task to run, `attempt`
+	/// being the number of times in a row it did so before (0 for the first), parking included.
+	/// Returns what the worker should do next.
+	fn wait(&self, attempt: u32) -> IdleAction;
+}
+
+impl fmt::Debug for dyn IdleWait {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("IdleWait").finish_non_exhaustive()
+	}
+}
+
+/// What an idle worker should do next, see [`IdleWait::wait`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleAction {
+	/// Check the queue again.
+	Recheck,
+	/// Block until a task is enqueued or the pool is stopped.
+	Park,
+}
+
+/// Park right away, see [`IdleWait`]. This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Park;
+
+impl IdleWait for Park {
+	fn wait(&self, _attempt: u32) -> IdleAction {
+		IdleAction::Park
+	}
+}
+
+/// Keep yielding to the scheduler and checking the queue, never parking, see [`IdleWait`].
+/// This keeps latency to a minimum, at the cost of a busy core per idle worker.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YieldLoop;
+
+impl IdleWait for YieldLoop {
+	fn wait(&self, _attempt: u32) -> IdleAction {
+		thread::yield_now();
+		IdleAction::Recheck
+	}
+}
+
+/// Check the queue between sleeps, doubling from `initial` up to `max`, never parking,
+/// see [`IdleWait`]. Tasks may wait up to `max` before being picked up.
+#[derive(Debug, Clone, Copy)]
+pub struct SleepBackoff {
+	initial: Duration,
+	max: Duration,
+}
+
+impl SleepBackoff {
+	/// Construct a backoff sleeping `initial`, then twice as long after each check, up to `max`.
+	#[must_use]
+	pub fn new(initial: Duration, max: Duration) -> Self {
+		Self { initial, max }
+	}
+}
+
+impl IdleWait for SleepBackoff {
+	fn wait(&self, attempt: u32) -> IdleAction {
+		let delay = self
+			.initial
+			.saturating_mul(2_u32.saturating_pow(attempt))
+			.min(self.max);
+		thread::sleep(delay);
+		IdleAction::Recheck
+	}
+}
+
+/// Busy-wait for the given number of checks before parking, see [`IdleWait`].
+#[derive(Debug, Clone, Copy)]
+pub struct SpinThenPark {
+	spins: u32,
+}
+
+impl SpinThenPark {
+	/// Construct a strategy checking the queue `spins` times before parking.
+	#[must_use]
+	pub fn new(spins: u32) -> Self {
+		Self { spins }
+	}
+}
+
+impl IdleWait for SpinThenPark {
+	fn wait(&self, attempt: u32) -> IdleAction {
+		if attempt < self.spins {
+			hint::spin_loop();
+			IdleAction::Recheck
+		} else {
+			IdleAction::Park
+		}
+	}
+}
@@ -0,0 +1,85 @@
+use std::{
+	panic::{self, AssertUnwindSafe},
+	sync::mpsc::{self, Sender},
+};
+
+use super::ThreadPool;
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Run `f` on one of the workers and return its result, for when the caller needs
+	/// something computed with worker-owned state.
+	///
+	/// If `f` panics, the panic is propagated to the caller and the worker survives.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have room, then for `f`
+	/// to complete. Calling it from a task running on the same pool may result in a deadlock.
+	///
+	/// # Panics
+	/// - if `f` panics
+	/// - if the pool has been shut down, as `f` is discarded without running
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![vec![1, 2, 3]; 2]);
+	///
+	/// let sum: i32 = pool.install(|numbers| numbers.iter().sum());
+	/// assert_eq!(sum, 6);
+	/// ```
+	#[track_caller]
+	pub fn install<R: Send + 'static>(
+		&self,
+		f: impl FnOnce(&mut WorkerData) -> R + Send + 'static,
+	) -> R {
+		let (sender, receiver) = mpsc::sync_channel(1);
+		self.enqueue(move |worker_data| {
+			let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(|| f(worker_data))));
+		});
+		match receiver.recv().expect("pool to run the task") {
+			Ok(result) => result,
+			Err(payload) => panic::resume_unwind(payload),
+		}
+	}
+
+	/// Enqueue a task and send its result into `sender`, so that results from multiple
+	/// pools or stages can be funneled into a single channel.
+	///
+	/// The result is dropped if the receiver has been dropped in the meantime.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let parsers = ThreadPool::new(vec![(); 2]);
+	/// let checkers = ThreadPool::new(vec![(); 2]);
+	/// let (sender, receiver) = mpsc::channel();
+	///
+	/// parsers.enqueue_into(sender.clone(), |()| "parsed");
+	/// checkers.enqueue_into(sender, |()| "checked");
+	///
+	/// let mut results = receiver.iter().collect::<Vec<_>>();
+	/// results.sort_unstable();
+	/// assert_eq!(results, ["checked", "parsed"]);
+	/// ```
+	#[track_caller]
+	pub fn enqueue_into<R: Send + 'static>(
+		&self,
+		sender: Sender<R>,
+		task: impl FnOnce(&mut WorkerData) -> R + Send + 'static,
+	) {
+		self.enqueue(move |worker_data| {
+			let _ = sender.send(task(worker_data));
+		});
+	}
+}
@@ -0,0 +1,149 @@
+use std::{
+	fmt,
+	future::Future,
+	pin::Pin,
+	sync::atomic::Ordering,
+	task::{Context, Poll, Waker},
+};
+
+use super::{PoolQueue, ThreadPool, WorkerState};
+
+/// Resolves once a pool has been joined, as returned by [`ThreadPool::join_async`].
+#[must_use = "futures do nothing unless polled, though the pool is joined regardless"]
+pub struct JoinFuture {
+	/// Taken once joined.
+	pool: Option<Box<dyn AsyncJoin>>,
+}
+
+/// A pool being joined by a [`JoinFuture`], with its worker data type erased.
+trait AsyncJoin: Send {
+	/// Stop the pool once drained, like [`ThreadPool::join`] but without blocking: register
+	/// `waker` and return whether all the workers have exited.
+	fn poll_stopped(&mut self, waker: &Waker) -> bool;
+
+	/// Join the workers, which have all exited.
+	fn join(self: Box<Self>);
+}
+
+impl fmt::Debug for JoinFuture {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("JoinFuture")
+			.field("joined", &self.pool.is_none())
+			.finish_non_exhaustive()
+	}
+}
+
+impl Future for JoinFuture {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+		let pool = self.pool.as_mut().expect("future polled after completion");
+		if !pool.poll_stopped(cx.waker()) {
+			return Poll::Pending;
+		}
+		self.pool.take().unwrap().join();
+		Poll::Ready(())
+	}
+}
+
+impl<WorkerData: Send + 'static> AsyncJoin for ThreadPool<WorkerData> {
+	fn poll_stopped(&mut self, waker: &Waker) -> bool {
+		let inner = &self.inner;
+		// Registered before looking, so that no progress made in the meantime goes unnoticed.
+		inner.wakers.register(waker);
+		if inner.feeding.load(Ordering::SeqCst) {
+			return false;
+		}
+		let mut guard = inner.pending_tasks.lock().unwrap();
+		if let PoolQueue::Todo(tasks) = &*guard {
+			if !tasks.is_empty() || inner.has_delayed_retries() || inner.caller_running() {
+				return false;
+			}
+			debug!("sending stop request...");
+			*guard = PoolQueue::Done;
+			drop(guard);
+			inner.stop_services();
+			inner.stop_watchdog();
+			inner.wake_all_workers();
+		} else {
+			drop(guard);
+		}
+		inner.is_inline()
+			|| inner.workers().iter().all(|worker| {
+				match *worker.state.lock().unwrap() {
+					WorkerState::Exited => true,
+					// Respawned workers exit later on.
+					WorkerState::Panicked { .. } => inner.respawn.is_none(),
+					WorkerState::Idle | WorkerState::Running { .. } => false,
+				}
+			})
+	}
+
+	fn join(self: Box<Self>) {
+		ThreadPool::join(*self);
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Like [`ThreadPool::join`], but returns a future resolving once the queue is drained
+	/// and all workers have exited, so that async programs can include the pool in their
+	/// graceful shutdown without blocking the executor. No thread is blocked joining the pool:
+	/// the future is woken by the workers as they drain the queue and exit. The pool is
+	/// stopped once the future is polled, while dropping the future before it resolves joins
+	/// the pool in place, like dropping the pool does.
+	///
+	/// Only available with the `async` feature. The future doesn't depend on any runtime.
+	///
+	/// # Panics
+	///
+	/// Polling the future panics if a worker panicked, like [`ThreadPool::join`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{
+	/// 	future::Future,
+	/// 	pin::pin,
+	/// 	sync::Arc,
+	/// 	task::{Context, Poll, Wake},
+	/// 	thread::{self, Thread},
+	/// };
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// // A minimal executor, use your runtime of choice instead.
+	/// struct Unpark(Thread);
+	///
+	/// impl Wake for Unpark {
+	/// 	fn wake(self: Arc<Self>) {
+	/// 		self.0.unpark();
+	/// 	}
+	/// }
+	///
+	/// fn block_on<F: Future>(future: F) -> F::Output {
+	/// 	let mut future = pin!(future);
+	/// 	let waker = Arc::new(Unpark(thread::current())).into();
+	/// 	let mut cx = Context::from_waker(&waker);
+	/// 	loop {
+	/// 		if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+	/// 			return output;
+	/// 		}
+	/// 		thread::park();
+	/// 	}
+	/// }
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	/// for i in 0..8 {
+	/// 	pool.enqueue(move |()| println!("task {i}"));
+	/// }
+	///
+	/// block_on(async {
+	/// 	pool.join_async().await;
+	/// 	println!("pool drained, shutting down");
+	/// });
+	/// ```
+	pub fn join_async(self) -> JoinFuture {
+		JoinFuture {
+			pool: Some(Box::new(self)),
+		}
+	}
+}
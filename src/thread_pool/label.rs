@@ -0,0 +1,90 @@
+use std::fmt;
+
+use super::ThreadPoolBuilder;
+
+type LabelFn<WorkerData> = dyn Fn(usize, &WorkerData) -> String + Send + Sync;
+
+/// Names the workers, see [`ThreadPoolBuilder::worker_label`].
+pub(crate) struct WorkerLabel<WorkerData>(Box<LabelFn<WorkerData>>);
+
+impl<WorkerData> WorkerLabel<WorkerData> {
+	pub(crate) fn label(&self, index: usize, worker_data: &WorkerData) -> String {
+		(self.0)(index, worker_data)
+	}
+}
+
+impl<WorkerData> fmt::Debug for WorkerLabel<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("WorkerLabel").finish_non_exhaustive()
+	}
+}
+
+/// The name of the thread serving the given worker.
+pub(crate) fn thread_name(index: usize, label: Option<&str>) -> String {
+	label.map_or_else(|| format!("w({index})"), str::to_string)
+}
+
+/// Keeps the span carrying the label of a worker entered on its thread, so that the label
+/// is recorded on the events emitted while it runs tasks.
+pub(crate) struct LabelSpan {
+	#[cfg(all(feature = "tracing", not(feature = "log")))]
+	_entered: Option<tracing::span::EnteredSpan>,
+}
+
+impl LabelSpan {
+	pub(crate) fn enter(label: Option<&str>) -> Self {
+		#[cfg(all(feature = "tracing", not(feature = "log")))]
+		return Self {
+			_entered: label.map(|label| tracing::info_span!("worker", label).entered()),
+		};
+		#[cfg(not(all(feature = "tracing", not(feature = "log"))))]
+		{
+			let _ = label;
+			Self {}
+		}
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Label each worker, e.g. after the device or shard its data is bound to, given its
+	/// index and its data. The label is used as the name of the worker thread, instead of
+	/// `w(index)`, and with the `tracing` feature it's recorded as the `label` field of
+	/// a `worker` span entered by the worker thread, so that it's attached to all the events
+	/// emitted while the worker runs tasks, the events of the tasks included.
+	///
+	/// Workers added by [`ThreadPool::grow`](crate::ThreadPool::grow) are labelled too.
+	/// A worker keeps its label when its thread is replaced or respawned.
+	///
+	/// Note that some platforms truncate thread names, e.g. to 15 bytes on Linux.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{sync::mpsc, thread};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// struct Gpu {
+	/// 	id: usize,
+	/// }
+	///
+	/// let pool = ThreadPool::builder((0..2).map(|id| Gpu { id }).collect())
+	/// 	.worker_label(|_, gpu| format!("gpu{}", gpu.id))
+	/// 	.build();
+	///
+	/// let (sender, receiver) = mpsc::channel();
+	/// pool.enqueue(move |gpu| {
+	/// 	let name = thread::current().name().map(str::to_string);
+	/// 	sender.send((gpu.id, name)).unwrap();
+	/// });
+	///
+	/// let (id, name) = receiver.recv().unwrap();
+	/// assert_eq!(name, Some(format!("gpu{id}")));
+	/// ```
+	pub fn worker_label(
+		mut self,
+		label: impl Fn(usize, &WorkerData) -> String + Send + Sync + 'static,
+	) -> Self {
+		self.worker_label = Some(WorkerLabel(Box::new(label)));
+		self
+	}
+}
@@ -0,0 +1,143 @@
+use std::sync::atomic::Ordering;
+
+use super::{PoolQueue, Task, ThreadPool, WorkerState};
+
+/// Exclusive access to one of the workers of a pool, as returned by [`ThreadPool::checkout`].
+///
+/// While the lease is alive, the worker only runs the tasks enqueued through it, in order,
+/// which makes it possible to carry out multi-step interactions with a stateful resource
+/// owned by the worker (e.g. a transaction or a session). Dropping the lease gives the worker
+/// back to the pool, after it's done with the tasks enqueued through the lease.
+#[derive(Debug)]
+pub struct WorkerLease<'pool, WorkerData: Send> {
+	pool: &'pool ThreadPool<WorkerData>,
+	worker: usize,
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Reserve one of the workers, preferring idle ones. The worker finishes its current task,
+	/// if any, and then only serves the returned [`WorkerLease`] until it is dropped.
+	///
+	/// Shared tasks are served by the remaining workers, so checking out every worker
+	/// stalls the queue until a lease is dropped.
+	///
+	/// # Blocking
+	///
+	/// This method blocks while all workers are checked out.
+	///
+	/// # Panics
+	/// - if all workers were brought down by a panic (see [`ThreadPool::dead_workers`])
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![Vec::new(); 4]);
+	///
+	/// let lease = pool.checkout();
+	/// lease.enqueue(|log: &mut Vec<&str>| log.push("begin"));
+	/// lease.enqueue(|log| log.push("update"));
+	/// lease.enqueue(|log| {
+	/// 	log.push("commit");
+	/// 	assert_eq!(log, &["begin", "update", "commit"]);
+	/// });
+	/// drop(lease);
+	/// ```
+	#[must_use]
+	pub fn checkout(&self) -> WorkerLease<'_, WorkerData> {
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		loop {
+			let PoolQueue::Todo(tasks) = &mut *guard else {
+				// tasks enqueued through the lease are discarded anyway
+				return WorkerLease {
+					pool: self,
+					worker: 0,
+				};
+			};
+			let candidates = self
+				.inner
+				.workers()
+				.iter()
+				.enumerate()
+				.filter(|&(i, _)| !tasks.is_reserved(i))
+				.map(|(i, worker)| (i, worker.state.lock().unwrap()))
+				.filter(|(_, state)| !matches!(**state, WorkerState::Panicked { .. }))
+				.map(|(i, state)| (i, matches!(*state, WorkerState::Idle)))
+				.collect::<Vec<_>>();
+			let worker = candidates
+				.iter()
+				.find(|(_, idle)| *idle)
+				.or(candidates.first())
+				.map(|&(i, _)| i);
+			if let Some(worker) = worker {
+				debug!("checking out worker", worker = worker);
+				tasks.set_reserved(worker, true);
+				self.inner.leased.fetch_add(1, Ordering::SeqCst);
+				return WorkerLease { pool: self, worker };
+			}
+			assert!(
+				(0..self.inner.workers().len()).any(|i| tasks.is_reserved(i)),
+				"all workers are dead"
+			);
+			debug!("all workers are checked out, waiting...");
+			guard = self.inner.pool_condvar.wait(guard).unwrap();
+		}
+	}
+}
+
+impl<WorkerData: Send> WorkerLease<'_, WorkerData> {
+	/// The index of the reserved worker.
+	#[must_use]
+	pub fn worker(&self) -> usize {
+		self.worker
+	}
+
+	/// Enqueue a task on the reserved worker. Tasks run in the order they're enqueued.
+	///
+	/// Tasks don't count against the queue limits of the pool, so this method never blocks.
+	/// If the worker was brought down by a panic, or the pool has been shut down,
+	/// the task is discarded.
+	#[allow(clippy::missing_panics_doc)]
+	#[track_caller]
+	pub fn enqueue(&self, task: impl FnOnce(&mut WorkerData) + Send + 'static) {
+		let inner = &*self.pool.inner;
+		let mut guard = inner.pending_tasks.lock().unwrap();
+		let alive = !matches!(
+			*inner.workers()[self.worker].state.lock().unwrap(),
+			WorkerState::Panicked { .. }
+		);
+		match &mut *guard {
+			PoolQueue::Todo(tasks) if alive => {
+				tasks.push_to_worker(self.worker, inner.propagators.wrap(Task::new(task)));
+				inner.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+				drop(guard);
+				inner.wake_all_workers();
+			}
+			_ => {
+				debug!(
+					"worker is unavailable, discarding task",
+					worker = self.worker
+				);
+				inner.counters.discarded.fetch_add(1, Ordering::Relaxed);
+			}
+		}
+	}
+}
+
+impl<WorkerData: Send> Drop for WorkerLease<'_, WorkerData> {
+	fn drop(&mut self) {
+		let inner = &*self.pool.inner;
+		let mut guard = inner.pending_tasks.lock().unwrap();
+		if let PoolQueue::Todo(tasks) = &mut *guard {
+			if tasks.is_reserved(self.worker) {
+				debug!("releasing worker", worker = self.worker);
+				tasks.set_reserved(self.worker, false);
+				inner.leased.fetch_sub(1, Ordering::SeqCst);
+			}
+		}
+		drop(guard);
+		inner.wake_all_workers();
+		inner.notify_room();
+	}
+}
@@ -0,0 +1,164 @@
+use std::{
+	cell::Cell,
+	fmt,
+	future::Future,
+	pin::pin,
+	ptr::NonNull,
+	rc::Rc,
+	sync::Arc,
+	task::{Context, Wake, Waker},
+	thread::{self, Thread},
+};
+
+use super::ThreadPool;
+
+/// Access to the data of the worker driving a future enqueued with
+/// [`ThreadPool::enqueue_future`], lent to the future each time it's polled.
+///
+/// Cloning a `LocalData` yields a handle to the same data.
+pub struct LocalData<WorkerData> {
+	/// Only set while the worker polls the future, and while no call to
+	/// [`LocalData::with`] is in progress.
+	slot: Rc<Cell<Option<NonNull<WorkerData>>>>,
+}
+
+impl<WorkerData> LocalData<WorkerData> {
+	/// Call `f` with the worker data. The borrow can't be held across await points,
+	/// but the data is available again after each of them.
+	///
+	/// # Panics
+	///
+	/// - if called outside of the future, e.g. from a thread-local once the future has completed
+	/// - if called from within another call to `with`
+	pub fn with<R>(&self, f: impl FnOnce(&mut WorkerData) -> R) -> R {
+		let mut data = self
+			.slot
+			.take()
+			.expect("worker data to be lent, i.e. used from within the future and not reentrantly");
+		let _restore = Restore {
+			slot: &self.slot,
+			data,
+		};
+		// SAFETY: the pointer is only set while the worker polls the future, during which
+		// it holds the only other reference to the data without using it, and it's taken
+		// out of the slot for the duration of the call, so no other `&mut` can be created.
+		f(unsafe { data.as_mut() })
+	}
+}
+
+impl<WorkerData> Clone for LocalData<WorkerData> {
+	fn clone(&self) -> Self {
+		Self {
+			slot: self.slot.clone(),
+		}
+	}
+}
+
+impl<WorkerData> fmt::Debug for LocalData<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("LocalData").finish_non_exhaustive()
+	}
+}
+
+/// Puts the data back in the slot when dropped, even if the call panicked.
+struct Restore<'a, WorkerData> {
+	slot: &'a Cell<Option<NonNull<WorkerData>>>,
+	data: NonNull<WorkerData>,
+}
+
+impl<WorkerData> Drop for Restore<'_, WorkerData> {
+	fn drop(&mut self) {
+		self.slot.set(Some(self.data));
+	}
+}
+
+/// Takes the data back from the future once polled, even if polling panicked.
+struct Lend<'a, WorkerData>(&'a Cell<Option<NonNull<WorkerData>>>);
+
+impl<WorkerData> Drop for Lend<'_, WorkerData> {
+	fn drop(&mut self) {
+		self.0.set(None);
+	}
+}
+
+/// Wakes the worker driving the future.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+	fn wake(self: Arc<Self>) {
+		self.0.unpark();
+	}
+
+	fn wake_by_ref(self: &Arc<Self>) {
+		self.0.unpark();
+	}
+}
+
+/// Drive the future built by `task` to completion on the current thread, lending it
+/// `worker_data` each time it's polled.
+fn block_on<WorkerData, Fut: Future<Output = ()>>(
+	worker_data: &mut WorkerData,
+	task: impl FnOnce(LocalData<WorkerData>) -> Fut,
+) {
+	let slot = Rc::new(Cell::new(None));
+	let mut future = pin!(task(LocalData { slot: slot.clone() }));
+	let waker = Waker::from(Arc::new(Unpark(thread::current())));
+	let mut cx = Context::from_waker(&waker);
+	loop {
+		slot.set(Some(NonNull::from(&mut *worker_data)));
+		let lend = Lend(&slot);
+		let poll = future.as_mut().poll(&mut cx);
+		drop(lend);
+		if poll.is_ready() {
+			return;
+		}
+		thread::park();
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Enqueue an async task, which the worker that picks it up drives to completion on
+	/// a minimal local executor, parking in between polls. The future is built on the worker
+	/// by calling `task` with a [`LocalData`], giving it access to the worker data across
+	/// await points, e.g. to use an async client owned by the worker without a runtime per task.
+	///
+	/// The worker is busy until the future completes, so this suits futures waiting on IO
+	/// driven by another thread (e.g. a reactor), rather than many concurrent futures.
+	/// The future doesn't need to be `Send`, as it never leaves the worker.
+	///
+	/// Only available with the `async` feature.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{future, sync::mpsc};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![Vec::<u32>::new(); 1]);
+	/// let (sender, receiver) = mpsc::channel();
+	///
+	/// pool.enqueue_future(|data| async move {
+	/// 	for i in 0..3 {
+	/// 		// Stand-in for a call to an async client.
+	/// 		future::ready(()).await;
+	/// 		data.with(|received| received.push(i));
+	/// 	}
+	/// 	sender.send(data.with(|received| received.clone())).unwrap();
+	/// });
+	///
+	/// assert_eq!(receiver.recv().unwrap(), vec![0, 1, 2]);
+	/// ```
+	#[track_caller]
+	pub fn enqueue_future<Task, Fut>(&self, task: Task)
+	where
+		Task: FnOnce(LocalData<WorkerData>) -> Fut + Send + 'static,
+		Fut: Future<Output = ()> + 'static,
+	{
+		self.enqueue(move |worker_data| block_on(worker_data, task));
+	}
+}
@@ -0,0 +1,103 @@
+use std::{any::Any, fmt, sync::Arc};
+
+use super::{Task, ThreadPool};
+
+/// Type-erased task metadata, see [`ThreadPool::enqueue_with_meta`].
+pub(crate) trait Metadata: Any + fmt::Debug + Send + Sync {
+	fn as_any(&self) -> &dyn Any;
+}
+
+impl<Meta: Any + fmt::Debug + Send + Sync> Metadata for Meta {
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+/// The metadata a task was enqueued with, as reported in [`PoolEvent`](crate::PoolEvent)s.
+/// See [`ThreadPool::enqueue_with_meta`].
+///
+/// Two `TaskMeta`s are equal if they refer to the metadata of the same task.
+#[derive(Clone, Copy)]
+pub struct TaskMeta<'a>(&'a dyn Metadata);
+
+impl<'a> TaskMeta<'a> {
+	pub(crate) fn new(meta: &'a Arc<dyn Metadata>) -> Self {
+		Self(&**meta)
+	}
+
+	/// The metadata, if it's of type `Meta`.
+	#[must_use]
+	pub fn downcast_ref<Meta: Any>(&self) -> Option<&'a Meta> {
+		self.0.as_any().downcast_ref()
+	}
+}
+
+impl PartialEq for TaskMeta<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		std::ptr::addr_eq(self.0, other.0)
+	}
+}
+
+impl Eq for TaskMeta<'_> {}
+
+impl fmt::Debug for TaskMeta<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a task along with some metadata (e.g. a tenant id, a request id or a cost
+	/// estimate), which is reported to the [`PoolEventListener`](crate::PoolEventListener)s
+	/// with the events of the task, for attribution without capturing it in the closure.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{
+	/// 	collections::HashMap,
+	/// 	sync::{Arc, Mutex},
+	/// 	time::Duration,
+	/// };
+	/// use lending_thread_pool::{PoolEvent, ThreadPool};
+	///
+	/// #[derive(Debug)]
+	/// struct Tenant(&'static str);
+	///
+	/// let usage = Arc::new(Mutex::new(HashMap::<&str, Duration>::new()));
+	/// let pool = ThreadPool::builder(vec![(); 2])
+	/// 	.event_listener({
+	/// 		let usage = usage.clone();
+	/// 		move |event: PoolEvent<'_>| {
+	/// 			if let PoolEvent::TaskCompleted { meta: Some(meta), elapsed, .. } = event {
+	/// 				if let Some(Tenant(tenant)) = meta.downcast_ref() {
+	/// 					*usage.lock().unwrap().entry(tenant).or_default() += elapsed;
+	/// 				}
+	/// 			}
+	/// 		}
+	/// 	})
+	/// 	.build();
+	///
+	/// pool.enqueue_with_meta(Tenant("acme"), |()| println!("serving acme"));
+	/// pool.enqueue_with_meta(Tenant("globex"), |()| println!("serving globex"));
+	/// pool.join();
+	///
+	/// assert_eq!(usage.lock().unwrap().len(), 2);
+	/// ```
+	#[track_caller]
+	pub fn enqueue_with_meta<Meta, Task>(&self, meta: Meta, task: Task)
+	where
+		Meta: fmt::Debug + Send + Sync + 'static,
+		Task: FnOnce(&mut WorkerData) + Send + 'static,
+	{
+		self.enqueue_task(self::Task {
+			meta: Some(Arc::new(meta)),
+			..self::Task::new(task)
+		});
+	}
+}
@@ -1,13 +1,155 @@
 #![allow(clippy::tabs_in_doc_comments)]
 
 use std::{
-	collections::VecDeque,
+	borrow::Cow,
 	fmt, mem,
-	sync::{Arc, Condvar, Mutex},
-	thread::{self, JoinHandle},
+	panic::Location,
+	sync::{
+		atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+		mpsc, Arc, Condvar, Mutex, OnceLock, PoisonError, RwLock, RwLockReadGuard,
+	},
+	thread,
+	time::{Duration, Instant},
 };
 
-use tracing::debug;
+mod abort;
+mod admission;
+mod barrier;
+mod batch;
+mod batcher;
+mod boost;
+mod broadcast;
+mod budget;
+mod builder;
+mod cache;
+mod caller_runs;
+mod capability;
+mod class;
+mod clock;
+mod coalesce;
+#[cfg(feature = "serde")]
+mod config;
+mod cpu_time;
+mod dead_workers;
+mod deadline;
+mod dedicated;
+mod degrade;
+mod error_sink;
+mod events;
+mod executor;
+mod factory;
+mod global;
+mod graph;
+mod grow;
+mod handle;
+mod hung;
+mod hybrid;
+mod idle;
+mod idle_wait;
+mod install;
+#[cfg(feature = "async")]
+mod join_async;
+mod label;
+mod lease;
+#[cfg(feature = "async")]
+mod local_executor;
+mod meta;
+mod padded;
+mod panic_hook;
+mod parked;
+mod pool_task;
+mod progress;
+#[cfg(feature = "indicatif")]
+mod progress_bars;
+mod propagate;
+mod qos;
+mod queue;
+mod report;
+mod retry;
+mod router;
+mod scoped;
+mod segment;
+mod sender;
+mod shrink;
+#[cfg(all(feature = "signal", unix))]
+mod signal;
+#[cfg(feature = "async")]
+mod sink;
+mod source;
+mod spawner;
+mod split;
+mod start_order;
+mod status;
+mod steal;
+mod stopped;
+mod stream;
+mod task_group;
+mod timeline;
+mod timeout;
+#[cfg(feature = "async")]
+mod wakers;
+mod warmup;
+mod worker;
+mod worker_data;
+mod worker_thread;
+
+pub use abort::AbortSignal;
+pub use admission::AdmissionRejected;
+pub use batch::{Batch, BatchError};
+pub use batcher::KeyedBatcher;
+pub use broadcast::BroadcastContext;
+pub use budget::ThreadBudget;
+pub use builder::ThreadPoolBuilder;
+pub use cache::ResultCache;
+pub use caller_runs::CALLER_WORKER;
+pub use class::TaskClass;
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use coalesce::TaskCoalescer;
+#[cfg(feature = "serde")]
+pub use config::{PoolConfig, WorkerGroupConfig};
+pub use dead_workers::DeadWorkerPolicy;
+pub use deadline::DeadlineMissPolicy;
+pub use dedicated::StopSignal;
+pub use degrade::SpawnFailurePolicy;
+pub use error_sink::ErrorSink;
+pub use events::{PoolEvent, PoolEventListener};
+pub use executor::{InlineExecutor, TaskExecutor};
+pub use factory::WorkerDataError;
+pub use global::{global, init_global};
+pub use graph::{GraphNode, NodeId, TaskGraph};
+pub use handle::TaskHandle;
+pub use hung::StrandedWorker;
+pub use idle::IdleWorkerPolicy;
+pub use idle_wait::{IdleAction, IdleWait, Park, SleepBackoff, SpinThenPark, YieldLoop};
+#[cfg(feature = "async")]
+pub use join_async::JoinFuture;
+pub use lease::WorkerLease;
+#[cfg(feature = "async")]
+pub use local_executor::LocalData;
+pub use meta::TaskMeta;
+use padded::CachePadded;
+pub use panic_hook::TaskPanic;
+pub use pool_task::PoolTask;
+pub use progress::{ProgressHandle, ProgressReporter};
+#[cfg(feature = "indicatif")]
+pub use progress_bars::{ProgressPool, ProgressWorker};
+pub use qos::QosClass;
+use queue::{TaskQueue, ANONYMOUS_PRODUCER, DEFAULT_CLASS};
+pub use report::{JoinReport, WorkerPanic};
+pub use retry::RetryPolicy;
+pub use router::PoolRouter;
+pub use sender::{SenderStats, TaskSender};
+#[cfg(feature = "async")]
+pub use sink::PoolSink;
+pub use spawner::ThreadSpawner;
+use spawner::{PoolThread, Spawner};
+pub use status::{PoolStatus, TaskInfo, TaskOutcome, TaskRecord, WorkerStatus};
+pub use stopped::StoppedPool;
+pub use task_group::TaskGroup;
+pub use timeline::TimelineRecorder;
+pub use timeout::TimeoutSignal;
+use worker::{WorkerShared, WorkerState};
+pub use worker_thread::WorkerThread;
 
 /// The Thread Pool struct. This can be constructed using the [`ThreadPool::new`] method.
 ///
@@ -16,7 +158,7 @@ use tracing::debug;
 /// ```
 /// use lending_thread_pool::ThreadPool;
 ///
-/// let mut pool = ThreadPool::new(
+/// let pool = ThreadPool::new(
 /// 	(0..4)
 /// 		.map(|i| format!("Hello from worker {i}"))
 /// 		.collect::<Vec<_>>(),
@@ -27,20 +169,97 @@ use tracing::debug;
 /// 	pool.enqueue(|greeting| { println!("{greeting}"); });
 /// }
 /// ```
-#[derive(Debug)]
-pub struct ThreadPool<WorkerData: Send + 'static = ()> {
+pub struct ThreadPool<WorkerData: Send = ()> {
 	inner: Arc<ThreadPoolShared<WorkerData>>,
-	workers: Vec<JoinHandle<()>>,
+	workers: Vec<PoolThread<WorkerData>>,
+	/// Moves tasks from the channel passed to [`ThreadPoolBuilder::task_source`], if any.
+	feeder: Option<source::Feeder<WorkerData>>,
+	/// Replaces hung workers, see [`ThreadPoolBuilder::replace_hung_workers`]. Never joined,
+	/// see [`ThreadPoolShared::stop_watchdog`].
+	watchdog: Option<PoolThread<()>>,
+}
+
+/// A type-erased task, see [`ThreadPool::enqueue_boxed`] and [`TaskExecutor`].
+pub type BoxedTask<WorkerData> = Box<dyn FnOnce(&mut WorkerData) + Send>;
+
+struct Task<WorkerData> {
+	name: Option<Cow<'static, str>>,
+	class: usize,
+	size: usize,
+	/// Assigned when the task is pushed to the queue, see [`ThreadPool::flush`].
+	seq: u64,
+	/// Where the task was enqueued from.
+	location: &'static Location<'static>,
+	/// Who enqueued the task and how many of its tasks can be dispatched in a row,
+	/// see [`TaskSender`].
+	producer: u64,
+	weight: usize,
+	/// Assigned when the task is pushed to the queue, see [`ThreadPool::pending`].
+	enqueued_at: Instant,
+	/// Whether the task must acquire a slot from the [`ThreadBudget`] of the pool, if any.
+	budgeted: bool,
+	/// Reported to the listeners, see [`ThreadPool::enqueue_with_meta`].
+	meta: Option<Arc<dyn meta::Metadata>>,
+	/// The batch the task belongs to, see [`Batch::abort_on_failure`].
+	batch: u64,
+	/// Installed around the execution of the task, see [`ThreadPoolBuilder::propagate`].
+	captured: propagate::Captured,
+	/// The handle tracking the task, see [`TaskHandle::wait`].
+	handle: u64,
+	/// The capabilities a worker must have to run the task, as a mask,
+	/// see [`ThreadPool::enqueue_requiring`].
+	requires: u64,
+	/// Whether the task was already accepted by the admission predicate, or doesn't need to
+	/// be, see [`ThreadPoolBuilder::admission`].
+	admitted: bool,
+	run: BoxedTask<WorkerData>,
 }
 
-type Task<WorkerData> = Box<dyn FnOnce(&mut WorkerData) + Send>;
+impl<WorkerData> Task<WorkerData> {
+	#[track_caller]
+	fn new(task: impl FnOnce(&mut WorkerData) + Send + 'static) -> Self {
+		Self {
+			size: mem::size_of_val(&task),
+			..Self::from_boxed(Box::new(task))
+		}
+	}
+
+	#[track_caller]
+	fn from_boxed(run: BoxedTask<WorkerData>) -> Self {
+		Self {
+			name: None,
+			class: DEFAULT_CLASS,
+			size: mem::size_of_val(&*run),
+			seq: 0,
+			location: Location::caller(),
+			producer: ANONYMOUS_PRODUCER,
+			weight: 1,
+			enqueued_at: Instant::now(),
+			budgeted: true,
+			meta: None,
+			batch: batch::NO_BATCH,
+			captured: propagate::Captured::default(),
+			handle: handle::NO_HANDLE,
+			requires: 0,
+			admitted: false,
+			run,
+		}
+	}
 
-enum PoolQueue<WorkerData: Send + 'static> {
+	/// Whether a worker with the given capabilities can run the task.
+	fn fits(&self, capabilities: u64) -> bool {
+		self.requires & !capabilities == 0
+	}
+}
+
+// The queue is only ever constructed once, inside a Mutex.
+#[allow(clippy::large_enum_variant)]
+enum PoolQueue<WorkerData: Send> {
 	Done,
-	Todo(VecDeque<Task<WorkerData>>),
+	Todo(TaskQueue<WorkerData>),
 }
 
-impl<WorkerData: Send + 'static> fmt::Debug for PoolQueue<WorkerData> {
+impl<WorkerData: Send> fmt::Debug for PoolQueue<WorkerData> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
 			Self::Done => write!(f, "Done"),
@@ -51,37 +270,145 @@ impl<WorkerData: Send + 'static> fmt::Debug for PoolQueue<WorkerData> {
 
 enum DequeueResult<WorkerData> {
 	Joined,
+	/// The worker is retired, see [`ThreadPool::shrink`].
+	Retired,
 	WaitingForTasks,
 	TaskAvailable {
 		task: Task<WorkerData>,
 		has_more: bool,
+		capped: bool,
 	},
 }
 
-impl<WorkerData: Send + 'static> PoolQueue<WorkerData> {
-	fn dequeue(&mut self) -> DequeueResult<WorkerData> {
+impl<WorkerData: Send> PoolQueue<WorkerData> {
+	fn dequeue(&mut self, worker: usize) -> DequeueResult<WorkerData> {
 		match self {
 			Self::Done => DequeueResult::Joined,
-			Self::Todo(ref mut tasks) => match tasks.pop_front() {
-				Some(task) => DequeueResult::TaskAvailable {
-					task,
-					has_more: !tasks.is_empty(),
-				},
-				None => DequeueResult::WaitingForTasks,
-			},
+			Self::Todo(ref mut tasks) => {
+				if let Some(task) = tasks.pop_next(worker) {
+					DequeueResult::TaskAvailable {
+						capped: tasks.is_capped(task.class),
+						task,
+						has_more: !tasks.is_empty(),
+					}
+				} else if tasks.is_retired(worker) {
+					DequeueResult::Retired
+				} else {
+					tasks.set_idle(worker);
+					DequeueResult::WaitingForTasks
+				}
+			}
 		}
 	}
 }
 
 #[derive(Debug)]
-struct ThreadPoolShared<WorkerData: Send + 'static> {
-	workers_condvar: Condvar,
+struct ThreadPoolShared<WorkerData: Send> {
+	/// The workers waiting for tasks, see [`parked::ParkedWorkers`].
+	parked: parked::ParkedWorkers,
 	pool_condvar: Condvar,
+	flush_condvar: Condvar,
+	/// Number of threads waiting in [`ThreadPool::flush`].
+	flushers: AtomicUsize,
+	/// Number of workers waiting for tasks. Only updated while holding the queue lock.
+	sleeping: AtomicUsize,
+	/// Set by [`ThreadPool::detach`], tells workers to stop once the queue is drained.
+	detached: AtomicBool,
+	/// Number of workers currently checked out, see [`ThreadPool::checkout`].
+	leased: AtomicUsize,
+	/// Raised by [`ThreadPool::abort`].
+	aborted: Arc<AtomicBool>,
+	/// Raised by [`ThreadPoolShared::shutdown`], for the producers that don't look at the queue.
+	shut_down: AtomicBool,
+	/// Whether tasks are still moved over from the [`ThreadPoolBuilder::task_source`].
+	feeding: AtomicBool,
+	/// Set by [`ThreadPoolBuilder::dedicated_worker`].
+	dedicated_workers: Vec<dedicated::DedicatedWorker<WorkerData>>,
+	/// Raised when the pool stops, see [`StopSignal`].
+	stopping: Arc<AtomicBool>,
 	max_pending_tasks: usize,
-	pending_tasks: Mutex<PoolQueue<WorkerData>>,
+	classes: Vec<Cow<'static, str>>,
+	/// Names of the worker groups, whose classes follow the task classes.
+	groups: Vec<Cow<'static, str>>,
+	// The queue and the state of each worker are written to by different threads all the time,
+	// so they're kept on separate cache lines.
+	pending_tasks: CachePadded<Mutex<PoolQueue<WorkerData>>>,
+	/// Only written to by [`ThreadPool::grow`], while holding the queue lock.
+	/// The queue lock must never be taken while holding this one.
+	workers: RwLock<Vec<Arc<CachePadded<WorkerShared>>>>,
+	listeners: events::Listeners,
+	/// Set by [`ThreadPoolBuilder::propagate`].
+	propagators: propagate::Propagators,
+	recycling: Option<worker::WorkerRecycling<WorkerData>>,
+	/// When not `Any`, tasks may be handed to specific workers, see [`TaskQueue::push_back`].
+	idle_worker_policy: IdleWorkerPolicy,
+	/// Set by [`ThreadPoolBuilder::idle_wait`].
+	idle_wait: Box<dyn IdleWait>,
+	/// Number of workers brought down by a panic and not respawned.
+	dead: AtomicUsize,
+	dead_worker_policy: DeadWorkerPolicy,
+	/// Set by [`ThreadPoolBuilder::spawn_failure_policy`].
+	spawn_failure_policy: SpawnFailurePolicy,
+	/// Set by [`ThreadPoolBuilder::thread_spawner`], to spawn threads once the pool is built.
+	thread_spawner: OnceLock<Arc<dyn ThreadSpawner>>,
+	/// Set by [`ThreadPoolBuilder::caller_runs`].
+	caller_runs: Option<caller_runs::CallerRuns<WorkerData>>,
+	/// Set by [`ThreadPoolBuilder::sender_segments`].
+	segment_capacity: Option<usize>,
+	/// The segments of the senders, see [`ThreadPoolBuilder::sender_segments`].
+	/// The queue lock must never be taken while holding this one.
+	segments: RwLock<Vec<Arc<segment::Segment<WorkerData>>>>,
+	/// Number of tasks in the segments, so that workers only look at them when needed.
+	segmented: AtomicUsize,
+	/// The segment the workers last took a task from. Only updated while holding the queue lock.
+	segment_cursor: AtomicUsize,
+	/// Set by [`ThreadPoolBuilder::boost_waited_tasks`].
+	boost_waited: bool,
+	/// Set when the pool lends data to the callers or boosts waited-on tasks, see
+	/// [`TaskHandle::wait`].
+	on_wait: OnceLock<steal::OnWait>,
+	/// Set when no worker thread could be spawned, see [`ThreadPool::is_inline`].
+	inline: OnceLock<degrade::Inline<WorkerData>>,
+	respawn: Option<worker::Respawn<WorkerData>>,
+	/// Set by [`ThreadPoolBuilder::panic_hook`].
+	panic_hook: Option<panic_hook::PanicHook>,
+	thread_budget: Option<ThreadBudget>,
+	hung_workers: Option<hung::HungWorkers<WorkerData>>,
+	qos_class: Option<QosClass>,
+	/// Set by [`ThreadPoolBuilder::strict_start_order`].
+	start_order: Option<start_order::StartOrder>,
+	/// Set by [`ThreadPoolBuilder::task_history`].
+	task_history: usize,
+	/// Set by [`ThreadPoolBuilder::worker_label`].
+	worker_label: Option<label::WorkerLabel<WorkerData>>,
+	/// Set by [`ThreadPoolBuilder::worker_capabilities`].
+	worker_capabilities: Option<capability::WorkerCapabilities<WorkerData>>,
+	/// Set by [`ThreadPoolBuilder::admission`].
+	admission: Option<admission::Admission>,
+	counters: Counters,
+	/// See [`ThreadPool::enqueue_with_retry`].
+	retries: retry::DelayedRetries<WorkerData>,
+	/// The sinks waiting for room in the queue and the futures waiting for the pool to be
+	/// joined, see [`ThreadPool::sink`] and [`ThreadPool::join_async`].
+	#[cfg(feature = "async")]
+	wakers: wakers::Wakers,
+	/// Set by [`ThreadPoolBuilder::clock`].
+	clock: Arc<dyn Clock>,
+	created_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+	/// Written to by the producers.
+	enqueued: CachePadded<AtomicU64>,
+	/// Written to by the workers.
+	completed: CachePadded<AtomicU64>,
+	discarded: AtomicU64,
+	/// Tasks that ran past their timeout, see [`ThreadPool::enqueue_with_timeout`].
+	timed_out: AtomicU64,
 }
 
-impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+impl<WorkerData: Send> ThreadPool<WorkerData> {
 	/// Construct a thread pool given a Vec of `WorkerData`. The number of workers
 	/// will correspond to the length of the Vec, so will the queue size for pending tasks.
 	/// Note that `WorkerData` can be any type. Each worker thread will own its corresponding `WorkerData`.
@@ -94,7 +421,7 @@ impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
 	/// ```
 	/// use lending_thread_pool::ThreadPool;
 	///
-	/// let mut pool = ThreadPool::new(
+	/// let pool = ThreadPool::new(
 	/// 	(0..4)
 	/// 		.map(|i| format!("Hello from worker {i}"))
 	/// 		.collect::<Vec<_>>(),
@@ -106,9 +433,11 @@ impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
 	/// }
 	/// ```
 	#[must_use]
-	pub fn new(workers_data: Vec<WorkerData>) -> Self {
-		let max_pending = workers_data.len();
-		Self::new_with_queue_size(workers_data, max_pending)
+	pub fn new(workers_data: Vec<WorkerData>) -> Self
+	where
+		WorkerData: 'static,
+	{
+		Self::builder(workers_data).build()
 	}
 
 	/// Construct a thread pool given a Vec of `WorkerData`. The number of workers
@@ -124,7 +453,7 @@ impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
 	/// ```
 	/// use lending_thread_pool::ThreadPool;
 	///
-	/// let mut pool = ThreadPool::new_with_queue_size(
+	/// let pool = ThreadPool::new_with_queue_size(
 	/// 	(0..4)
 	/// 		.map(|i| format!("Hello from worker {i}"))
 	/// 		.collect::<Vec<_>>(),
@@ -137,106 +466,596 @@ impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
 	/// }
 	/// ```
 	#[must_use]
-	pub fn new_with_queue_size(workers_data: Vec<WorkerData>, max_pending_tasks: usize) -> Self {
-		assert_ne!(
-			workers_data.len(),
-			0,
-			"workers_data must contain at least one item"
-		);
-		assert_ne!(
-			max_pending_tasks, 0,
-			"max_pending_tasks must be greater than 0"
-		);
+	pub fn new_with_queue_size(workers_data: Vec<WorkerData>, max_pending_tasks: usize) -> Self
+	where
+		WorkerData: 'static,
+	{
+		Self::builder(workers_data)
+			.max_pending_tasks(max_pending_tasks)
+			.build()
+	}
+
+	/// Construct a thread pool suited to tasks that spend most of their time blocked on IO,
+	/// e.g. synchronous file or network calls. Unlike a pool sized after the available cores,
+	/// it spawns [`BLOCKING_IO_WORKERS_PER_CORE`](Self::BLOCKING_IO_WORKERS_PER_CORE) workers
+	/// per core (as reported by [`std::thread::available_parallelism`]), so that blocked workers
+	/// don't starve the others, and queues up to
+	/// [`BLOCKING_IO_TASKS_PER_WORKER`](Self::BLOCKING_IO_TASKS_PER_WORKER) tasks per worker
+	/// before blocking the callers of [`ThreadPool::enqueue`].
+	///
+	/// `factory` is called with each worker index to build its `WorkerData`.
+	/// Use [`ThreadPool::builder`] for finer control over the pool.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{fs, sync::mpsc};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::for_blocking_io(|_| ());
+	/// let (sender, receiver) = mpsc::channel();
+	///
+	/// for path in ["Cargo.toml", "README.md"] {
+	/// 	let sender = sender.clone();
+	/// 	pool.enqueue(move |()| sender.send(fs::read(path).is_ok()).unwrap());
+	/// }
+	/// drop(sender);
+	///
+	/// assert!(receiver.iter().all(|read| read));
+	/// ```
+	#[must_use]
+	pub fn for_blocking_io(factory: impl FnMut(usize) -> WorkerData) -> Self
+	where
+		WorkerData: 'static,
+	{
+		let workers = thread::available_parallelism().map_or(1, usize::from)
+			* Self::BLOCKING_IO_WORKERS_PER_CORE;
+		Self::builder((0..workers).map(factory).collect())
+			.max_pending_tasks(workers * Self::BLOCKING_IO_TASKS_PER_WORKER)
+			.build()
+	}
 
+	/// Workers spawned per available core by [`ThreadPool::for_blocking_io`].
+	pub const BLOCKING_IO_WORKERS_PER_CORE: usize = 8;
+
+	/// Queue size per worker of the pools constructed by [`ThreadPool::for_blocking_io`].
+	pub const BLOCKING_IO_TASKS_PER_WORKER: usize = 64;
+
+	/// Construct a [`ThreadPoolBuilder`] given a Vec of `WorkerData`. The number of workers
+	/// will correspond to the length of the Vec.
+	pub fn builder(workers_data: Vec<WorkerData>) -> ThreadPoolBuilder<WorkerData> {
+		ThreadPoolBuilder::new(workers_data)
+	}
+
+	fn spawn<'a>(
+		ThreadPoolBuilder {
+			workers_data,
+			max_pending_tasks,
+			max_pending_bytes,
+			task_classes,
+			worker_groups,
+			dedicated_workers,
+			fair_producers,
+			caller_runs,
+			segment_capacity,
+			boost_waited,
+			thread_budget,
+			listeners,
+			propagators,
+			recycling,
+			task_source,
+			strict_start_order,
+			task_history,
+			worker_label,
+			worker_capabilities,
+			admission,
+			idle_worker_policy,
+			idle_wait,
+			warmup,
+			dead_worker_policy,
+			spawn_failure_policy,
+			respawn,
+			panic_hook,
+			hung_workers,
+			qos_class,
+			clock,
+			// already picked by the caller to construct `spawner`
+			thread_spawner: _,
+		}: ThreadPoolBuilder<WorkerData>,
+		spawner: &impl Spawner<'a>,
+	) -> Self
+	where
+		WorkerData: 'a,
+	{
 		let inner = Arc::new(ThreadPoolShared {
-			workers_condvar: Condvar::default(),
+			parked: parked::ParkedWorkers::default(),
 			pool_condvar: Condvar::default(),
-			pending_tasks: Mutex::new(PoolQueue::Todo(VecDeque::new())),
-			max_pending_tasks,
+			flush_condvar: Condvar::default(),
+			flushers: AtomicUsize::new(0),
+			sleeping: AtomicUsize::new(0),
+			detached: AtomicBool::new(false),
+			leased: AtomicUsize::new(0),
+			aborted: Arc::default(),
+			shut_down: AtomicBool::new(false),
+			feeding: AtomicBool::new(false),
+			dedicated_workers,
+			stopping: Arc::default(),
+			pending_tasks: CachePadded::new(Mutex::new(PoolQueue::Todo(TaskQueue::new(
+				workers_data.len(),
+				max_pending_tasks,
+				max_pending_bytes,
+				&task_classes,
+				&worker_groups,
+				fair_producers,
+				idle_worker_policy,
+			)))),
+			max_pending_tasks: max_pending_tasks
+				+ task_classes
+					.iter()
+					.map(|class| class.max_pending_tasks)
+					.sum::<usize>()
+				+ worker_groups
+					.iter()
+					.map(|group| group.workers)
+					.sum::<usize>(),
+			classes: task_classes.into_iter().map(|class| class.name).collect(),
+			groups: worker_groups.into_iter().map(|group| group.name).collect(),
+			workers: RwLock::new(
+				workers_data
+					.iter()
+					.enumerate()
+					.map(|(i, worker_data)| {
+						let worker = WorkerShared::new(i, worker_data, worker_label.as_ref());
+						Arc::new(CachePadded::new(worker))
+					})
+					.collect(),
+			),
+			listeners,
+			propagators,
+			recycling,
+			idle_worker_policy,
+			idle_wait,
+			dead: AtomicUsize::new(0),
+			dead_worker_policy,
+			spawn_failure_policy,
+			caller_runs: caller_runs.map(caller_runs::CallerRuns::new),
+			segment_capacity,
+			segments: RwLock::default(),
+			segmented: AtomicUsize::new(0),
+			segment_cursor: AtomicUsize::new(0),
+			boost_waited,
+			on_wait: OnceLock::new(),
+			inline: OnceLock::new(),
+			thread_spawner: OnceLock::new(),
+			respawn,
+			panic_hook,
+			thread_budget,
+			hung_workers,
+			qos_class,
+			start_order: strict_start_order.then(start_order::StartOrder::default),
+			task_history,
+			worker_label,
+			worker_capabilities,
+			admission,
+			counters: Counters::default(),
+			retries: retry::DelayedRetries::default(),
+			#[cfg(feature = "async")]
+			wakers: wakers::Wakers::default(),
+			created_at: clock.now(),
+			clock,
 		});
-		let workers = workers_data
-			.into_iter()
-			.enumerate()
-			.map(|(i, mut worker_data)| {
-				let inner_clone = inner.clone();
-				thread::Builder::new()
-					.name(format!("w({i})"))
-					.spawn(move || loop {
-						let ThreadPoolShared {
-							pending_tasks,
-							workers_condvar,
-							pool_condvar,
-							..
-						} = &*inner_clone;
-						let mut guard = pending_tasks.lock().unwrap();
-
-						let dequeued = loop {
-							match guard.dequeue() {
-								DequeueResult::Joined => break None,
-								DequeueResult::WaitingForTasks => {
-									debug!("waiting for tasks...");
-									guard = workers_condvar.wait(guard).unwrap();
-								}
-								dequeued @ DequeueResult::TaskAvailable { .. } => {
-									break Some(dequeued)
-								}
-							}
-						};
+		inner.reserve_dedicated_workers();
+		inner.assign_capabilities(&workers_data);
+		// Disconnected once every worker is warmed up.
+		let (warmed_up, warming_up) = mpsc::channel();
+		let warmup = warmup.map(|warmup| (warmup, warmed_up));
+		let workers = degrade::spawn_workers(&inner, workers_data, warmup.as_ref(), spawner);
 
-						if let Some(DequeueResult::TaskAvailable { task, has_more }) = dequeued {
-							pool_condvar.notify_all();
-							drop(guard);
-							if has_more {
-								workers_condvar.notify_all();
-							}
-							debug!("running task...");
-							(task)(&mut worker_data);
-						} else {
-							debug!("quitting...");
-							break;
-						}
-					})
-					.expect("thread to be spawned")
-			})
-			.collect::<Vec<_>>();
+		let feeder = task_source.map(|receiver| source::spawn_feeder(spawner, &inner, receiver));
+		let watchdog = inner
+			.hung_workers
+			.as_ref()
+			.and_then(|_| hung::spawn_watchdog(spawner, &inner));
 
-		Self { inner, workers }
+		if warmup.is_some() {
+			// From now on, only the workers hold a sender.
+			drop(warmup);
+			debug!("waiting for workers to warm up...");
+			let _ = warming_up.recv();
+		}
+
+		Self {
+			inner,
+			workers,
+			feeder,
+			watchdog,
+		}
 	}
 
 	/// Enqueue a task in the pool.
 	///
+	/// This is the fire-and-forget path: nothing is tracked per task beyond the pool-wide
+	/// counters, so it's the cheapest way to submit work. Use
+	/// [`ThreadPool::enqueue_with_handle`] to be able to wait for the task.
+	///
 	/// # Blocking
 	///
 	/// This method is blocking. It waits for the task queue to have at least one empty
-	/// slot before returning.
-
+	/// slot before returning. When multiple threads are blocked on a full queue,
+	/// they're granted room in the order they arrived.
+	///
+	/// If the pool has been shut down (e.g. by a signal, see `shutdown_on_signal`),
+	/// the task is discarded without being run.
+	//
 	// clippy::missing_panics_doc: a panic section in the doc might be misleading, as in order
 	// to actually cause a panic, you would need to call this method after
 	// the thread pool has already been dropped, meaning you're either dereferencing a pointer
 	// to some dropped yet somehow mostly intact thread pool struct, or you found a bug in the type
 	// system.
 	#[allow(clippy::missing_panics_doc)]
-	pub fn enqueue<Task: FnOnce(&mut WorkerData) + Send + 'static>(&mut self, task: Task) {
+	#[track_caller]
+	pub fn enqueue<Task: FnOnce(&mut WorkerData) + Send + 'static>(&self, task: Task) {
+		self.enqueue_task(self::Task::new(task));
+	}
+
+	/// Enqueue an already boxed task, without boxing it again. Also useful to avoid
+	/// instantiating [`ThreadPool::enqueue`] for each closure type at generic call sites.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::{BoxedTask, ThreadPool};
+	///
+	/// let pool = ThreadPool::new(vec![0; 4]);
+	///
+	/// let plugins: Vec<BoxedTask<i32>> = vec![
+	/// 	Box::new(|counter| *counter += 1),
+	/// 	Box::new(|counter| *counter -= 1),
+	/// ];
+	/// for plugin in plugins {
+	/// 	pool.enqueue_boxed(plugin);
+	/// }
+	/// ```
+	#[track_caller]
+	pub fn enqueue_boxed(&self, task: BoxedTask<WorkerData>) {
+		self.enqueue_task(Task::from_boxed(task));
+	}
+
+	/// Enqueue a named task in the pool. The name shows up in the [`Debug`](fmt::Debug)
+	/// output of the pool while the task is running.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	///
+	/// pool.enqueue_named("greet", |()| println!("Hello"));
+	/// println!("{pool:?}");
+	/// ```
+	#[track_caller]
+	pub fn enqueue_named<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		name: impl Into<Cow<'static, str>>,
+		task: Task,
+	) {
+		self.enqueue_task(self::Task {
+			name: Some(name.into()),
+			..self::Task::new(task)
+		});
+	}
+
+	/// Enqueue a task in the pool, providing an estimate of the memory it holds
+	/// (e.g. the length of a buffer it captures). The estimate is checked against
+	/// the byte budget of the queue, see [`ThreadPoolBuilder::max_pending_bytes`].
+	///
+	/// Tasks enqueued through the other methods are estimated by the size of the closure itself,
+	/// which doesn't account for heap allocations.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have enough room
+	/// before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 2])
+	/// 	.max_pending_tasks(1024)
+	/// 	.max_pending_bytes(64 * 1024 * 1024)
+	/// 	.build();
+	///
+	/// for _ in 0..16 {
+	/// 	let buffer = vec![0_u8; 1024 * 1024];
+	/// 	pool.enqueue_sized(buffer.len(), move |()| {
+	/// 		println!("{}", buffer.len());
+	/// 	});
+	/// }
+	/// ```
+	#[track_caller]
+	pub fn enqueue_sized<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		size: usize,
+		task: Task,
+	) {
+		self.enqueue_task(self::Task {
+			size,
+			..self::Task::new(task)
+		});
+	}
+
+	/// Enqueue a task in the sub-queue of the given [`TaskClass`].
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the sub-queue of the class to have at
+	/// least one empty slot before returning.
+	///
+	/// # Panics
+	/// - if no class with the given name was registered on the builder.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::{TaskClass, ThreadPool};
+	///
+	/// let pool = ThreadPool::builder(vec![(); 4])
+	/// 	.task_class(TaskClass::new("batch", 64).max_workers(2))
+	/// 	.build();
+	///
+	/// pool.enqueue_in_class("batch", |()| println!("Hello"));
+	/// ```
+	#[track_caller]
+	pub fn enqueue_in_class<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		class: &str,
+		task: Task,
+	) {
+		self.enqueue_task(self::Task {
+			class: self.class_index(class),
+			..self::Task::new(task)
+		});
+	}
+
+	#[track_caller]
+	fn class_index(&self, class: &str) -> usize {
+		1 + self
+			.inner
+			.classes
+			.iter()
+			.position(|name| name == class)
+			.unwrap_or_else(|| panic!("unknown task class {class:?}"))
+	}
+
+	/// Enqueue a task in the queue of the given worker group, so that it only runs
+	/// on the workers of that group, see [`ThreadPoolBuilder::worker_group`].
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the queue of the group to have at
+	/// least one empty slot before returning.
+	///
+	/// # Panics
+	/// - if no group with the given name was registered on the builder.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 4])
+	/// 	.worker_group("io", 1)
+	/// 	.build();
+	///
+	/// pool.enqueue_to("io", |()| println!("Hello"));
+	/// ```
+	#[track_caller]
+	pub fn enqueue_to<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		group: &str,
+		task: Task,
+	) {
+		let index = self
+			.inner
+			.groups
+			.iter()
+			.position(|name| name == group)
+			.unwrap_or_else(|| panic!("unknown worker group {group:?}"));
+		self.enqueue_task(self::Task {
+			class: 1 + self.inner.classes.len() + index,
+			..self::Task::new(task)
+		});
+	}
+
+	/// Enqueue many tasks at once, taking the queue lock a single time and waking up
+	/// only as many idle workers as needed, rather than once per task. This makes a difference
+	/// when tasks are so small that enqueueing them one by one would dominate their cost.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. Whenever the queue is full, it waits for it to have at least
+	/// one empty slot before pushing the next task.
+	///
+	/// If the pool has been shut down, the remaining tasks are discarded without being run.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new_with_queue_size(vec![0; 4], 256);
+	///
+	/// pool.enqueue_many((0..1000).map(|i| move |sum: &mut i32| *sum += i));
+	/// ```
+	#[track_caller]
+	pub fn enqueue_many<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		tasks: impl IntoIterator<Item = Task>,
+	) {
+		self.inner
+			.enqueue_tasks(tasks.into_iter().map(self::Task::new));
+	}
+
+	/// Enqueue a task, returning whether it was accepted, i.e. the pool wasn't shut down.
+	#[track_caller]
+	fn enqueue_task(&self, task: Task<WorkerData>) -> bool {
+		self.inner.enqueue_task(task)
+	}
+
+	/// Enqueue as many of the given tasks as fit in the queue, without blocking.
+	///
+	/// Returns the tasks that were rejected, in their original order. If the pool has been
+	/// shut down, all tasks are rejected. Tasks are also rejected while other threads are
+	/// blocked waiting for room in the queue, so as not to overtake them, and when the
+	/// [admission predicate](ThreadPoolBuilder::admission) turns them down.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new_with_queue_size(vec![(); 2], 4);
+	///
+	/// let rejected = pool.try_enqueue_many((0..16).map(|i| move |_: &mut ()| println!("task {i}")));
+	/// assert!(rejected.len() >= 12);
+	///
+	/// for task in rejected {
+	/// 	pool.enqueue(task);
+	/// }
+	/// ```
+	#[track_caller]
+	pub fn try_enqueue_many<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		tasks: impl IntoIterator<Item = Task>,
+	) -> Vec<Task> {
+		let Some(admission) = &self.inner.admission else {
+			return self.try_push_many(tasks);
+		};
+		let (admitted, mut rejected): (Vec<_>, Vec<_>) = tasks
+			.into_iter()
+			.enumerate()
+			.partition(|_| admission.admits(None));
+		// The tasks that didn't fit are the last ones admitted.
+		let indices = admitted.iter().map(|&(i, _)| i).collect::<Vec<_>>();
+		let left = self.try_push_many(admitted.into_iter().map(|(_, task)| task));
+		rejected.extend(
+			indices[indices.len() - left.len()..]
+				.iter()
+				.copied()
+				.zip(left),
+		);
+		rejected.sort_by_key(|&(i, _)| i);
+		rejected.into_iter().map(|(_, task)| task).collect()
+	}
+
+	#[allow(clippy::missing_panics_doc)]
+	#[track_caller]
+	fn try_push_many<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		tasks: impl IntoIterator<Item = Task>,
+	) -> Vec<Task> {
+		let mut tasks = tasks.into_iter().peekable();
+		if let Some(inline) = self.inner.inline.get() {
+			let propagators = &self.inner.propagators;
+			inline.run(
+				&self.inner,
+				tasks.map(|task| propagators.wrap(self::Task::new(task))),
+			);
+			return Vec::new();
+		}
 		let mut guard = self.inner.pending_tasks.lock().unwrap();
 
+		let PoolQueue::Todo(pending) = &mut *guard else {
+			debug!("pool is shut down, rejecting tasks");
+			return tasks.collect();
+		};
+		let mut accepted = 0;
+		while let Some(task) = tasks.next_if(|task| {
+			!pending.has_waiters(DEFAULT_CLASS)
+				&& pending.has_room(DEFAULT_CLASS, mem::size_of_val(task))
+		}) {
+			pending.push_back(self.inner.propagators.wrap(self::Task::new(task)));
+			accepted += 1;
+		}
+		self.inner.notify_workers(accepted);
+		drop(guard);
+
+		self.inner
+			.counters
+			.enqueued
+			.fetch_add(accepted as u64, Ordering::Relaxed);
+		trace!("tasks added", count = accepted);
+		tasks.collect()
+	}
+
+	/// Block until all the tasks enqueued before this call have completed. Unlike waiting for
+	/// the pool to be idle, this can't be starved by other threads that keep enqueueing tasks.
+	///
+	/// If the pool has been shut down, this method returns immediately.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::{
+	/// 	atomic::{AtomicUsize, Ordering},
+	/// 	Arc,
+	/// };
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 4]);
+	/// let counter = Arc::new(AtomicUsize::new(0));
+	///
+	/// for _ in 0..16 {
+	/// 	let counter = counter.clone();
+	/// 	pool.enqueue(move |()| {
+	/// 		counter.fetch_add(1, Ordering::Relaxed);
+	/// 	});
+	/// }
+	/// pool.flush();
+	///
+	/// assert_eq!(counter.load(Ordering::Relaxed), 16);
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	pub fn flush(&self) {
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			return;
+		};
+		self.inner.drain_segments(tasks);
+		let target = tasks.next_seq();
+		debug!("flushing", up_to_task = target);
+
+		self.inner.flushers.fetch_add(1, Ordering::SeqCst);
 		loop {
-			match &mut *guard {
-				PoolQueue::Todo(ref mut tasks) => {
-					if tasks.len() >= self.inner.max_pending_tasks {
-						debug!("waiting for available workers...");
-						guard = self.inner.pool_condvar.wait(guard).unwrap();
-					} else {
-						tasks.push_back(Box::new(task));
-						self.inner.workers_condvar.notify_one();
-						debug!("added pending task");
-						return;
-					}
-				}
-				PoolQueue::Done => unreachable!(
-					"enqueue shouldn't be callable on a joined (thus consumed) thread pool"
-				),
+			let queued_before = match &mut *guard {
+				PoolQueue::Done => false,
+				PoolQueue::Todo(tasks) => tasks.min_seq().is_some_and(|seq| seq < target),
+			};
+			let running_before = self
+				.inner
+				.workers()
+				.iter()
+				.any(|worker| worker.running_seq.load(Ordering::SeqCst) < target)
+				|| self.inner.caller_running_before(target);
+			if !queued_before && !running_before {
+				break;
 			}
+			guard = self.inner.flush_condvar.wait(guard).unwrap();
 		}
+		self.inner.flushers.fetch_sub(1, Ordering::SeqCst);
 	}
 
 	/// Signal to all worker threads that they should exit once finished with their current task,
@@ -247,34 +1066,469 @@ impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
 		self.join_by_ref();
 	}
 
+	/// Like [`ThreadPool::join`], but periodically calls `progress` with the number of tasks that
+	/// are yet to complete (queued or running) and the number of tasks there were when draining started,
+	/// e.g. to drive a progress bar during a long shutdown.
+	///
+	/// `progress` is only called when the count changes, and it's guaranteed to be called with
+	/// 0 remaining tasks before the workers are joined.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{thread::sleep, time::Duration};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new_with_queue_size(vec![(); 2], 16);
+	/// for _ in 0..16 {
+	/// 	pool.enqueue(|()| sleep(Duration::from_millis(10)));
+	/// }
+	///
+	/// pool.join_with_progress(|remaining, total| {
+	/// 	println!("draining: {remaining}/{total}");
+	/// });
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	pub fn join_with_progress(mut self, mut progress: impl FnMut(usize, usize)) {
+		const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+		let mut total = 0;
+		let mut last = None;
+		loop {
+			let guard = self.inner.pending_tasks.lock().unwrap();
+			let queued = match &*guard {
+				PoolQueue::Done => 0,
+				PoolQueue::Todo(tasks) => tasks.len(),
+			};
+			let running = self
+				.inner
+				.workers()
+				.iter()
+				.filter(|worker| {
+					matches!(*worker.state.lock().unwrap(), WorkerState::Running { .. })
+				})
+				.count() + usize::from(self.inner.caller_running());
+			let remaining = queued + running;
+			total = total.max(remaining);
+			if last == Some(remaining) {
+				drop(
+					self.inner
+						.pool_condvar
+						.wait_timeout(guard, POLL_INTERVAL)
+						.unwrap(),
+				);
+				continue;
+			}
+			drop(guard);
+			progress(remaining, total);
+			last = Some(remaining);
+			if remaining == 0 {
+				break;
+			}
+		}
+		self.join_by_ref();
+	}
+
+	/// Let the workers drain the queue in the background and exit on their own, without
+	/// blocking the current thread.
+	///
+	/// Unlike dropping the pool, this never waits. Keep in mind that, as with any detached
+	/// thread, the workers are killed when the process exits, e.g. when `main` returns.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	/// let (sender, receiver) = mpsc::channel();
+	///
+	/// for i in 0..4 {
+	/// 	let sender = sender.clone();
+	/// 	pool.enqueue(move |()| sender.send(i).unwrap());
+	/// }
+	/// drop(sender);
+	/// pool.detach();
+	///
+	/// assert_eq!(receiver.iter().sum::<i32>(), 6);
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	pub fn detach(mut self) {
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		match &*guard {
+			PoolQueue::Done => {}
+			PoolQueue::Todo(tasks) if tasks.is_empty() && !self.inner.has_delayed_retries() => {
+				debug!("sending stop request...");
+				*guard = PoolQueue::Done;
+			}
+			PoolQueue::Todo(_) => {
+				debug!("detaching, workers will stop once the queue is drained...");
+			}
+		}
+		// Also tells drop not to join.
+		self.inner.detached.store(true, Ordering::Relaxed);
+		drop(guard);
+		self.inner.stop_services();
+		self.inner.wake_all_workers();
+		self.workers.clear();
+	}
+
 	fn join_by_ref(&mut self) {
+		for (_, result) in self.stop_and_join() {
+			result.unwrap();
+		}
+	}
+
+	/// Wait for the queue to drain, stop the workers and join them, returning
+	/// the result of each join (i.e. the worker data, unless the worker panicked)
+	/// along with the worker index.
+	fn stop_and_join(&mut self) -> Vec<(usize, thread::Result<WorkerData>)> {
+		if let Some(feeder) = self.feeder.take() {
+			feeder.join(&self.inner);
+		}
 		let mut guard = self.inner.pending_tasks.lock().unwrap();
 
 		loop {
 			match &mut *guard {
-				// already joined
-				PoolQueue::Done => return,
-				PoolQueue::Todo(tasks) if tasks.is_empty() => break,
+				// already joined or shut down
+				PoolQueue::Done => break,
+				PoolQueue::Todo(tasks)
+					if tasks.is_empty()
+						&& !self.inner.has_delayed_retries()
+						&& !self.inner.caller_running() =>
+				{
+					debug!("sending stop request...");
+					*guard = PoolQueue::Done;
+					break;
+				}
 				PoolQueue::Todo(_) => {
 					debug!("waiting for idle...");
 					guard = self.inner.pool_condvar.wait(guard).unwrap();
 				}
 			}
 		}
-		debug!("sending stop request...");
-		*guard = PoolQueue::Done;
 		drop(guard);
-		self.inner.workers_condvar.notify_all();
+		self.inner.stop_services();
+		self.inner.wake_all_workers();
+		self.inner.stop_watchdog();
 		debug!("joining...");
-		let workers = mem::take(&mut self.workers);
-		for w in workers {
-			w.join().unwrap();
+		if let Some(worker_data) = self.inner.inline.get().and_then(degrade::Inline::take) {
+			return vec![(0, Ok(worker_data))];
+		}
+		mem::take(&mut self.workers)
+			.into_iter()
+			.enumerate()
+			.map(|(index, worker)| {
+				// Stranded threads are left running.
+				let result = match self.inner.take_replacement(index) {
+					Some(replacement) => replacement.join(),
+					None => worker.join(),
+				};
+				(index, result)
+			})
+			.collect()
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// The state of every worker, see [`ThreadPoolShared::workers`].
+	fn workers(&self) -> RwLockReadGuard<'_, Vec<Arc<CachePadded<WorkerShared>>>> {
+		self.workers.read().unwrap()
+	}
+
+	/// The state of the given worker, which can be held while taking the queue lock.
+	fn worker(&self, index: usize) -> Arc<CachePadded<WorkerShared>> {
+		self.workers()[index].clone()
+	}
+
+	/// Enqueue a task, returning whether it was accepted, i.e. the pool wasn't shut down.
+	#[track_caller]
+	fn enqueue_task(&self, task: Task<WorkerData>) -> bool {
+		self.enqueue_tasks([task]) == 1
+	}
+
+	/// Enqueue tasks under a single lock, waiting for room as needed, and return how many
+	/// were accepted, i.e. how many were admitted and came before the pool was shut down.
+	#[track_caller]
+	fn enqueue_tasks(&self, tasks: impl IntoIterator<Item = Task<WorkerData>>) -> usize {
+		if self.admission.is_none() {
+			return self.enqueue_admitted(tasks);
+		}
+		// The predicate may look at the pool, so it can't run under the queue lock.
+		let admitted = tasks
+			.into_iter()
+			.filter(|task| self.admit(task))
+			.collect::<Vec<_>>();
+		self.enqueue_admitted(admitted)
+	}
+
+	#[track_caller]
+	fn enqueue_admitted(&self, tasks: impl IntoIterator<Item = Task<WorkerData>>) -> usize {
+		let tasks = tasks.into_iter().map(|task| self.propagators.wrap(task));
+		if let Some(inline) = self.inline.get() {
+			return inline.run(self, tasks);
+		}
+		if !self.accepts_despite_dead_workers() {
+			self.report_discarded(&tasks.collect::<Vec<_>>());
+			return 0;
+		}
+
+		// Dropped once the lock is released, as dropping a task may lock its batch.
+		let mut discarded = Vec::new();
+		let mut guard = self.pending_tasks.lock().unwrap();
+		let mut accepted = 0;
+		// Pushed since the workers were last notified.
+		let mut pushed = 0;
+
+		'tasks: for task in tasks {
+			// Blocked enqueuers are served in arrival order: once someone is waiting,
+			// newcomers have to queue up behind them.
+			let mut ticket = None;
+			loop {
+				match &mut *guard {
+					PoolQueue::Todo(ref mut queue) => {
+						let is_turn = match ticket {
+							None => !queue.has_waiters(task.class),
+							Some(ticket) => queue.is_turn(task.class, ticket),
+						};
+						if is_turn && queue.has_room(task.class, task.size) {
+							let class = task.class;
+							if let Some(worker) = self.claim_parked(queue, class) {
+								trace!("handing task to a parked worker", worker = worker);
+								queue.push_to_worker(worker, task);
+								self.worker(worker).unpark();
+							} else {
+								queue.push_back(task);
+								pushed += 1;
+							}
+							self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+							if ticket.is_some() {
+								queue.advance_ticket(class);
+								if queue.has_waiters(class) {
+									self.notify_room();
+								}
+							}
+							accepted += 1;
+							continue 'tasks;
+						}
+						if ticket.is_none() {
+							// The data of the callers may lack the capabilities the task requires.
+							let caller_data = self.caller_data().filter(|_| task.requires == 0);
+							if let Some(worker_data) = caller_data {
+								self.notify_workers(mem::take(&mut pushed));
+								self.start_on_caller(&task);
+								drop(guard);
+								self.run_on_caller(task, worker_data);
+								accepted += 1;
+								guard = self.pending_tasks.lock().unwrap();
+								continue 'tasks;
+							}
+							ticket = Some(queue.take_ticket(task.class));
+						}
+						// The workers must be up to make room.
+						self.notify_workers(mem::take(&mut pushed));
+						debug!("waiting for available workers...");
+						guard = self.pool_condvar.wait(guard).unwrap();
+					}
+					PoolQueue::Done => {
+						debug!("pool is shut down, discarding task");
+						self.counters.discarded.fetch_add(1, Ordering::Relaxed);
+						discarded.push(task);
+						continue 'tasks;
+					}
+				}
+			}
+		}
+		self.notify_workers(pushed);
+		drop(guard);
+		drop(discarded);
+		trace!("tasks added", count = accepted);
+		accepted
+	}
+
+	/// Apply the [`DeadWorkerPolicy`] to tasks about to be enqueued, returning whether
+	/// they're accepted.
+	///
+	/// # Panics
+	/// - if workers are dead and the policy is [`DeadWorkerPolicy::Panic`]
+	#[track_caller]
+	fn accepts_despite_dead_workers(&self) -> bool {
+		let dead = self.dead.load(Ordering::Relaxed);
+		if dead == 0 {
+			return true;
+		}
+		match self.dead_worker_policy {
+			DeadWorkerPolicy::Ignore => true,
+			DeadWorkerPolicy::Reject => false,
+			DeadWorkerPolicy::Panic => {
+				panic!("{dead} worker(s) brought down by a panic, refusing to enqueue tasks")
+			}
+		}
+	}
+
+	/// Release the worker slot taken by a task of a class with capped workers.
+	fn release_class_slot(&self, class: usize) {
+		let mut guard = self
+			.pending_tasks
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner);
+		if let PoolQueue::Todo(tasks) = &mut *guard {
+			if tasks.release(class) {
+				drop(guard);
+				self.wake_all_workers();
+			}
 		}
 	}
+
+	/// Wake up a worker to pick up a newly queued shared task.
+	fn notify_worker(&self) {
+		self.notify_workers(1);
+	}
+
+	/// Wake up as many sleeping workers as needed to pick up `tasks` newly queued shared tasks.
+	/// Must be called while holding the queue lock, for the count of sleeping workers to be
+	/// accurate. Reserved workers and workers in another group ignore such tasks, so they must
+	/// not swallow the notifications: wake everyone while any worker could. The same goes
+	/// for tasks handed to a specific worker by the [`IdleWorkerPolicy`].
+	fn notify_workers(&self, tasks: usize) {
+		if tasks == 0 {
+			return;
+		}
+		if !self.hands_off() {
+			self.wake_all_workers();
+			return;
+		}
+		for _ in 0..tasks {
+			if !self.wake_one_worker() {
+				break;
+			}
+		}
+	}
+
+	/// Whether any parked worker can run any shared task, so that tasks can be handed to
+	/// parked workers and a worker can be woken up for each, see [`ThreadPoolShared::notify_workers`].
+	fn hands_off(&self) -> bool {
+		self.groups.is_empty()
+			&& self.dedicated_workers.is_empty()
+			&& self.worker_capabilities.is_none()
+			&& self.leased.load(Ordering::SeqCst) == 0
+			&& self.idle_worker_policy == IdleWorkerPolicy::Any
+	}
+
+	/// Pop a parked worker to hand a task of the given class straight to, unless other
+	/// tasks are queued, which the task would overtake, or the class caps how many of its
+	/// tasks run at once. Must be called while holding the queue lock.
+	fn claim_parked(&self, queue: &TaskQueue<WorkerData>, class: usize) -> Option<usize> {
+		if !queue.is_empty() || queue.is_capped(class) || !self.hands_off() {
+			return None;
+		}
+		self.parked.pop(&self.workers())
+	}
+
+	/// The time elapsed since the pool was constructed, as measured by its clock.
+	fn uptime(&self) -> Duration {
+		self.clock.now().saturating_duration_since(self.created_at)
+	}
+
+	/// Wake the threads waiting on the pool condvar, which includes those waiting for room in
+	/// the queue or for the pool to drain, and with the `async` feature the sinks and futures
+	/// doing so, see [`ThreadPool::sink`] and [`ThreadPool::join_async`].
+	fn notify_room(&self) {
+		self.pool_condvar.notify_all();
+		#[cfg(feature = "async")]
+		self.wakers.wake();
+	}
+
+	/// Put a task back in the queue, ignoring its capacity. Used by workers, which must never
+	/// block waiting for room in the queue they're supposed to drain.
+	fn requeue(&self, task: Task<WorkerData>) {
+		if let Some(inline) = self.inline.get() {
+			inline.run(self, [task]);
+			return;
+		}
+		let mut guard = self.pending_tasks.lock().unwrap();
+		if let PoolQueue::Todo(tasks) = &mut *guard {
+			tasks.push_back(task);
+			self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+			self.notify_worker();
+			drop(guard);
+		} else {
+			debug!("pool is shut down, discarding task");
+			self.counters.discarded.fetch_add(1, Ordering::Relaxed);
+		}
+	}
+
+	/// Stop accepting tasks and discard the pending ones. Workers exit as soon as they're done
+	/// with their current task, which isn't told to bail out: that's up to [`ThreadPool::abort`].
+	fn shutdown(&self) {
+		self.shut_down.store(true, Ordering::Relaxed);
+		let mut guard = self.pending_tasks.lock().unwrap();
+		let discarded = mem::replace(&mut *guard, PoolQueue::Done);
+		drop(guard);
+		debug!("shutting down...");
+		self.stop_services();
+		self.discard_segments();
+		self.discard_delayed_retries();
+		self.wake_all_workers();
+		self.notify_room();
+		self.flush_condvar.notify_all();
+		if let PoolQueue::Todo(tasks) = discarded {
+			self.report_discarded(tasks.iter());
+		}
+	}
+
+	/// Count pending tasks that are about to be dropped without being run, and make sure
+	/// it doesn't go unnoticed: log a warning and notify the listeners.
+	fn report_discarded<'a>(&self, tasks: impl IntoIterator<Item = &'a Task<WorkerData>>)
+	where
+		WorkerData: 'a,
+	{
+		let mut count = 0;
+		let mut names = Vec::new();
+		for task in tasks {
+			count += 1;
+			names.extend(task.name.as_deref());
+		}
+		if count == 0 {
+			return;
+		}
+		self.counters
+			.discarded
+			.fetch_add(count as u64, Ordering::Relaxed);
+		warn!("discarding pending tasks", count = count, named = names);
+		self.listeners.emit(PoolEvent::TasksDiscarded {
+			count,
+			names: &names,
+		});
+	}
+}
+
+impl<WorkerData: Send> fmt::Debug for ThreadPool<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let queue = match &*self.inner.pending_tasks.lock().unwrap() {
+			PoolQueue::Done => "done".to_string(),
+			PoolQueue::Todo(tasks) => format!("{}/{}", tasks.len(), self.inner.max_pending_tasks),
+		};
+		let workers = self.inner.workers();
+		let workers = workers
+			.iter()
+			.map(|worker| worker.state.lock().unwrap())
+			.collect::<Vec<_>>();
+		f.debug_struct("ThreadPool")
+			.field("queue", &format_args!("{queue}"))
+			.field("workers", &workers)
+			.field("task_source", &self.feeder.is_some())
+			.field("replace_hung_workers", &self.watchdog.is_some())
+			.finish()
+	}
 }
 
-impl<WorkerData: Send + 'static> Drop for ThreadPool<WorkerData> {
+impl<WorkerData: Send> Drop for ThreadPool<WorkerData> {
 	fn drop(&mut self) {
+		if self.inner.detached.load(Ordering::Relaxed) {
+			return;
+		}
 		self.join_by_ref();
 	}
 }
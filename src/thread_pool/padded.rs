@@ -0,0 +1,42 @@
+use std::{
+	fmt,
+	ops::{Deref, DerefMut},
+};
+
+/// Aligns a value to the size of a cache line, so that fields written by different threads
+/// don't end up on the same line and invalidate each other's caches (false sharing).
+///
+/// Modern x86-64 and aarch64 CPUs prefetch cache lines in pairs, hence the 128 bytes there.
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64"), repr(align(128)))]
+#[cfg_attr(
+	not(any(target_arch = "x86_64", target_arch = "aarch64")),
+	repr(align(64))
+)]
+#[derive(Default)]
+pub(crate) struct CachePadded<T>(T);
+
+impl<T> CachePadded<T> {
+	pub(crate) const fn new(value: T) -> Self {
+		Self(value)
+	}
+}
+
+impl<T> Deref for CachePadded<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for CachePadded<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		&mut self.0
+	}
+}
+
+impl<T: fmt::Debug> fmt::Debug for CachePadded<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
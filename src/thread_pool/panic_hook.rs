@@ -0,0 +1,78 @@
+use std::{any::Any, fmt, panic::Location};
+
+use super::ThreadPoolBuilder;
+
+/// A task panic, as reported to the hook registered with
+/// [`ThreadPoolBuilder::panic_hook`].
+#[derive(Debug, Clone, Copy)]
+pub struct TaskPanic<'a> {
+	/// The index of the worker that ran the task, or [`CALLER_WORKER`](crate::CALLER_WORKER)
+	/// if it was run by its caller.
+	pub worker: usize,
+	/// The name of the task, if it was enqueued with one.
+	pub name: Option<&'a str>,
+	/// Where the task was enqueued from.
+	pub location: &'static Location<'static>,
+	/// The panic message, if the payload was a string.
+	pub message: Option<&'a str>,
+	/// The value the task panicked with.
+	pub payload: &'a (dyn Any + Send),
+}
+
+/// Reports task panics, see [`ThreadPoolBuilder::panic_hook`].
+pub(crate) struct PanicHook(Box<dyn Fn(&TaskPanic<'_>) + Send + Sync>);
+
+impl PanicHook {
+	pub(crate) fn call(&self, panic: &TaskPanic<'_>) {
+		(self.0)(panic);
+	}
+}
+
+impl fmt::Debug for PanicHook {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PanicHook").finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Call `hook` on the worker thread whenever a task panics, before the panic brings
+	/// the worker down, with the worker index and what's known about the task. Unlike
+	/// [`std::panic::set_hook`], this only concerns the tasks of this pool and leaves the
+	/// process-wide hook alone, which still runs first, e.g. printing the message.
+	///
+	/// The hook runs once the task has unwound, so a [`Backtrace`](std::backtrace::Backtrace)
+	/// captured there doesn't include the frames of the task.
+	///
+	/// Defaults to no hook.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let (sender, panics) = mpsc::channel();
+	/// let pool = ThreadPool::builder(vec![(); 1])
+	/// 	.respawn_dead_workers(|_| ())
+	/// 	.panic_hook(move |panic| {
+	/// 		let _ = sender.send(format!(
+	/// 			"task {:?} panicked on worker {}: {}",
+	/// 			panic.name,
+	/// 			panic.worker,
+	/// 			panic.message.unwrap_or("unknown cause"),
+	/// 		));
+	/// 	})
+	/// 	.build();
+	///
+	/// pool.enqueue_named("parse", |()| panic!("invalid input"));
+	///
+	/// assert_eq!(
+	/// 	panics.recv().unwrap(),
+	/// 	"task Some(\"parse\") panicked on worker 0: invalid input",
+	/// );
+	/// ```
+	pub fn panic_hook(mut self, hook: impl Fn(&TaskPanic<'_>) + Send + Sync + 'static) -> Self {
+		self.panic_hook = Some(PanicHook(Box::new(hook)));
+		self
+	}
+}
@@ -0,0 +1,98 @@
+use std::sync::{
+	atomic::{AtomicU64, Ordering},
+	Arc,
+};
+
+use super::{padded::CachePadded, worker::WorkerShared, ThreadPoolShared};
+
+/// The top of an empty stack, or the successor of its bottom.
+const NONE: u64 = 0;
+
+const INDEX_BITS: u32 = 32;
+const INDEX_MASK: u64 = (1 << INDEX_BITS) - 1;
+
+/// The workers parked waiting for tasks, as a lock-free (Treiber) stack of worker indices,
+/// so that enqueuers can wake up exactly one of them, the most recently parked one, whose
+/// caches are the warmest, rather than going through a condition variable shared by all.
+#[derive(Debug, Default)]
+pub(crate) struct ParkedWorkers {
+	/// The index of the top worker plus one, or [`NONE`], in the lower half, and the number
+	/// of updates in the upper half, so that a top popped and pushed back in the meantime
+	/// isn't mistaken for an unchanged stack.
+	head: AtomicU64,
+}
+
+type Workers = [Arc<CachePadded<WorkerShared>>];
+
+/// The head of a stack whose top is `top`, after the given update.
+fn head(updates: u64, top: u64) -> u64 {
+	(updates << INDEX_BITS) | top
+}
+
+impl ParkedWorkers {
+	/// Push a worker about to park.
+	pub(crate) fn push(&self, index: usize, workers: &Workers) {
+		let worker = &workers[index];
+		worker.parked.store(true, Ordering::SeqCst);
+		let top = u64::try_from(index).unwrap() + 1;
+		let mut current = self.head.load(Ordering::Acquire);
+		loop {
+			worker
+				.next_parked
+				.store(current & INDEX_MASK, Ordering::Relaxed);
+			let updated = head((current >> INDEX_BITS).wrapping_add(1), top);
+			match self.head.compare_exchange_weak(
+				current,
+				updated,
+				Ordering::AcqRel,
+				Ordering::Acquire,
+			) {
+				Ok(_) => return,
+				Err(head) => current = head,
+			}
+		}
+	}
+
+	/// Pop the most recently parked worker, leaving it to the caller to wake it up.
+	pub(crate) fn pop(&self, workers: &Workers) -> Option<usize> {
+		let mut current = self.head.load(Ordering::Acquire);
+		loop {
+			let top = current & INDEX_MASK;
+			if top == NONE {
+				return None;
+			}
+			let index = usize::try_from(top - 1).unwrap();
+			let next = workers[index].next_parked.load(Ordering::Relaxed);
+			let updated = head((current >> INDEX_BITS).wrapping_add(1), next);
+			match self.head.compare_exchange_weak(
+				current,
+				updated,
+				Ordering::AcqRel,
+				Ordering::Acquire,
+			) {
+				Ok(_) => return Some(index),
+				Err(head) => current = head,
+			}
+		}
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Wake up the most recently parked worker, returning whether there was one.
+	pub(crate) fn wake_one_worker(&self) -> bool {
+		let workers = self.workers();
+		let Some(index) = self.parked.pop(&workers) else {
+			return false;
+		};
+		workers[index].unpark();
+		true
+	}
+
+	/// Wake up all the parked workers, e.g. for them to notice that the pool is stopping.
+	pub(crate) fn wake_all_workers(&self) {
+		let workers = self.workers();
+		while let Some(index) = self.parked.pop(&workers) {
+			workers[index].unpark();
+		}
+	}
+}
@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+
+use super::{Task, ThreadPool, DEFAULT_CLASS};
+
+/// A unit of work that can be enqueued with [`ThreadPool::submit`], for when a task is
+/// better modeled as a struct than as a closure capturing everything it needs.
+///
+/// Closures taking a `&mut WorkerData` implement this trait, with no name and no class.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use lending_thread_pool::{PoolTask, TaskClass, ThreadPool};
+///
+/// struct Resize {
+/// 	path: String,
+/// 	width: u32,
+/// }
+///
+/// impl PoolTask<Vec<u8>> for Resize {
+/// 	fn run(self, buffer: &mut Vec<u8>) {
+/// 		buffer.clear();
+/// 		println!("resizing {} to {}px", self.path, self.width);
+/// 	}
+///
+/// 	fn name(&self) -> Option<Cow<'static, str>> {
+/// 		Some(format!("resize {}", self.path).into())
+/// 	}
+///
+/// 	fn class(&self) -> Option<&str> {
+/// 		Some("images")
+/// 	}
+/// }
+///
+/// let pool = ThreadPool::builder(vec![Vec::new(); 4])
+/// 	.task_class(TaskClass::new("images", 16))
+/// 	.build();
+///
+/// pool.submit(Resize {
+/// 	path: "cat.png".to_string(),
+/// 	width: 640,
+/// });
+/// pool.submit(|buffer: &mut Vec<u8>| buffer.clear());
+/// ```
+pub trait PoolTask<WorkerData>: Send + 'static {
+	/// Run the task, borrowing the data of the worker it was dispatched to.
+	fn run(self, worker_data: &mut WorkerData);
+
+	/// The name of the task, see [`ThreadPool::enqueue_named`].
+	fn name(&self) -> Option<Cow<'static, str>> {
+		None
+	}
+
+	/// The [`TaskClass`](crate::TaskClass) the task should be enqueued in,
+	/// see [`ThreadPool::enqueue_in_class`].
+	fn class(&self) -> Option<&str> {
+		None
+	}
+}
+
+impl<WorkerData, F: FnOnce(&mut WorkerData) + Send + 'static> PoolTask<WorkerData> for F {
+	fn run(self, worker_data: &mut WorkerData) {
+		self(worker_data);
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a [`PoolTask`], honoring its name and class.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the queue (or the sub-queue of the class
+	/// of the task) to have at least one empty slot before returning.
+	///
+	/// # Panics
+	/// - if the task names a class that was not registered on the builder.
+	#[track_caller]
+	pub fn submit(&self, task: impl PoolTask<WorkerData>) {
+		let name = task.name();
+		let class = task
+			.class()
+			.map_or(DEFAULT_CLASS, |class| self.class_index(class));
+		self.enqueue_task(Task {
+			name,
+			class,
+			..Task::new(move |worker_data: &mut WorkerData| task.run(worker_data))
+		});
+	}
+}
@@ -0,0 +1,167 @@
+use std::{
+	fmt,
+	sync::{Arc, Condvar, Mutex},
+};
+
+use super::ThreadPool;
+
+/// Lets a task report its progress, see [`ThreadPool::enqueue_with_progress`].
+pub struct ProgressReporter {
+	shared: Arc<ProgressShared>,
+}
+
+/// Observes the progress of a task, see [`ThreadPool::enqueue_with_progress`].
+///
+/// Cloning a `ProgressHandle` yields a handle to the same task.
+#[derive(Clone)]
+pub struct ProgressHandle {
+	shared: Arc<ProgressShared>,
+}
+
+struct ProgressShared {
+	state: Mutex<ProgressState>,
+	condvar: Condvar,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProgressState {
+	progress: f32,
+	finished: bool,
+}
+
+impl ProgressShared {
+	fn update(&self, f: impl FnOnce(&mut ProgressState)) {
+		f(&mut self.state.lock().unwrap());
+		self.condvar.notify_all();
+	}
+}
+
+impl ProgressReporter {
+	/// Report the progress of the task, as a fraction between 0 and 1.
+	/// Values outside this range are clamped.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn report_progress(&self, progress: f32) {
+		self.shared
+			.update(|state| state.progress = progress.clamp(0.0, 1.0));
+	}
+}
+
+impl ProgressHandle {
+	/// The last progress reported by the task, as a fraction between 0 and 1.
+	/// Once the task has finished, this is always 1.
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn progress(&self) -> f32 {
+		self.shared.state.lock().unwrap().progress
+	}
+
+	/// Whether the task has finished running (or panicked).
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn is_finished(&self) -> bool {
+		self.shared.state.lock().unwrap().finished
+	}
+
+	/// Block until the task reports a progress different from `last_seen` or finishes,
+	/// then return the current progress.
+	#[allow(clippy::missing_panics_doc, clippy::float_cmp)]
+	#[must_use]
+	pub fn wait_change(&self, last_seen: f32) -> f32 {
+		let mut state = self.shared.state.lock().unwrap();
+		while state.progress == last_seen && !state.finished {
+			state = self.shared.condvar.wait(state).unwrap();
+		}
+		state.progress
+	}
+
+	/// Block until the task has finished.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn wait(&self) {
+		let mut state = self.shared.state.lock().unwrap();
+		while !state.finished {
+			state = self.shared.condvar.wait(state).unwrap();
+		}
+	}
+}
+
+impl fmt::Debug for ProgressReporter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ProgressReporter").finish_non_exhaustive()
+	}
+}
+
+impl fmt::Debug for ProgressHandle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let state = *self.shared.state.lock().unwrap();
+		f.debug_struct("ProgressHandle")
+			.field("progress", &state.progress)
+			.field("finished", &state.finished)
+			.finish()
+	}
+}
+
+/// Marks the task as finished when dropped, even if it panicked.
+struct FinishGuard(Arc<ProgressShared>);
+
+impl Drop for FinishGuard {
+	fn drop(&mut self) {
+		self.0.update(|state| {
+			state.progress = 1.0;
+			state.finished = true;
+		});
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Enqueue a task that can report its progress through a [`ProgressReporter`].
+	/// The progress can be polled or awaited from the returned [`ProgressHandle`], so that
+	/// it can be displayed without coupling the `WorkerData` to the UI.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{thread::sleep, time::Duration};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	///
+	/// let handle = pool.enqueue_with_progress(|(), reporter| {
+	/// 	for i in 1..=10 {
+	/// 		sleep(Duration::from_millis(10));
+	/// 		reporter.report_progress(i as f32 / 10.0);
+	/// 	}
+	/// });
+	///
+	/// let mut progress = 0.0;
+	/// while !handle.is_finished() {
+	/// 	progress = handle.wait_change(progress);
+	/// 	println!("{:.0}%", progress * 100.0);
+	/// }
+	/// ```
+	#[track_caller]
+	pub fn enqueue_with_progress<Task>(&self, task: Task) -> ProgressHandle
+	where
+		Task: FnOnce(&mut WorkerData, &ProgressReporter) + Send + 'static,
+	{
+		let shared = Arc::new(ProgressShared {
+			state: Mutex::new(ProgressState {
+				progress: 0.0,
+				finished: false,
+			}),
+			condvar: Condvar::default(),
+		});
+		let reporter = ProgressReporter {
+			shared: shared.clone(),
+		};
+		self.enqueue(move |worker_data| {
+			let _finish_guard = FinishGuard(reporter.shared.clone());
+			task(worker_data, &reporter);
+		});
+		ProgressHandle { shared }
+	}
+}
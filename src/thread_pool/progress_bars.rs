@@ -0,0 +1,107 @@
+use ::indicatif::{MultiProgress, ProgressBar};
+
+use super::ThreadPool;
+
+/// Worker data for a [`ProgressPool`]: a per-worker progress bar, alongside
+/// the actual worker data.
+#[derive(Debug)]
+pub struct ProgressWorker<WorkerData = ()> {
+	/// The progress bar of the worker, reset after each task.
+	pub bar: ProgressBar,
+	/// The data owned by the worker.
+	pub data: WorkerData,
+}
+
+/// A thread pool where each worker owns an [indicatif](https://crates.io/crates/indicatif)
+/// progress bar, plus a main bar tracking the completion of all enqueued tasks.
+///
+/// Worker bars are reset after each task, and the length and position of the main
+/// bar are kept in sync with the number of enqueued and completed tasks.
+///
+/// # Examples
+///
+/// ```
+/// use std::{thread::sleep, time::Duration};
+/// use indicatif::MultiProgress;
+/// use lending_thread_pool::ProgressPool;
+///
+/// let multi_pb = MultiProgress::new();
+/// let pool = ProgressPool::new(&multi_pb, vec![(); 4]);
+///
+/// for _ in 0..16 {
+/// 	pool.enqueue(|worker| {
+/// 		worker.bar.set_length(10);
+/// 		for _ in 0..10 {
+/// 			sleep(Duration::from_millis(1));
+/// 			worker.bar.inc(1);
+/// 		}
+/// 	});
+/// }
+///
+/// pool.join();
+/// ```
+#[derive(Debug)]
+pub struct ProgressPool<WorkerData: Send + 'static = ()> {
+	pool: ThreadPool<ProgressWorker<WorkerData>>,
+	main_bar: ProgressBar,
+}
+
+impl<WorkerData: Send + 'static> ProgressPool<WorkerData> {
+	/// Construct a pool with one worker per item in `workers_data`, adding a main bar followed
+	/// by one bar per worker to the given [`MultiProgress`].
+	///
+	/// # Panics
+	/// - if Vec is empty
+	#[must_use]
+	pub fn new(multi_pb: &MultiProgress, workers_data: Vec<WorkerData>) -> Self {
+		let main_bar = multi_pb.add(ProgressBar::new(0));
+		let pool = ThreadPool::new(
+			workers_data
+				.into_iter()
+				.map(|data| ProgressWorker {
+					bar: multi_pb.add(ProgressBar::new(0)),
+					data,
+				})
+				.collect(),
+		);
+		Self { pool, main_bar }
+	}
+
+	/// The bar tracking the completion of all enqueued tasks.
+	#[must_use]
+	pub fn main_bar(&self) -> &ProgressBar {
+		&self.main_bar
+	}
+
+	/// The underlying thread pool.
+	#[must_use]
+	pub fn pool(&self) -> &ThreadPool<ProgressWorker<WorkerData>> {
+		&self.pool
+	}
+
+	/// Enqueue a task, accounting for it in the main bar.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	#[track_caller]
+	pub fn enqueue<Task>(&self, task: Task)
+	where
+		Task: FnOnce(&mut ProgressWorker<WorkerData>) + Send + 'static,
+	{
+		self.main_bar.inc_length(1);
+		let main_bar = self.main_bar.clone();
+		self.pool.enqueue(move |worker| {
+			task(worker);
+			worker.bar.reset();
+			main_bar.inc(1);
+		});
+	}
+
+	/// Join the underlying pool, then mark the main bar as finished.
+	pub fn join(self) {
+		self.pool.join();
+		self.main_bar.finish();
+	}
+}
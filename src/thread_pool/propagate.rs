@@ -0,0 +1,116 @@
+use std::{any::Any, fmt, sync::Arc};
+
+use super::{Task, ThreadPoolBuilder};
+
+/// Installs a captured value on the worker, returning a guard that restores the previous one.
+type Installer = Box<dyn FnOnce() -> Box<dyn Any> + Send>;
+
+/// The capture/install pairs registered with [`ThreadPoolBuilder::propagate`].
+#[derive(Default)]
+pub(crate) struct Propagators(Vec<Box<dyn Fn() -> Installer + Send + Sync>>);
+
+impl Propagators {
+	/// Capture the values to propagate on the current thread.
+	pub(crate) fn capture(&self) -> Captured {
+		Captured(self.0.iter().map(|capture| capture()).collect())
+	}
+
+	/// Capture the values to propagate on the current thread into `task`.
+	pub(super) fn wrap<WorkerData>(&self, task: Task<WorkerData>) -> Task<WorkerData> {
+		if self.0.is_empty() {
+			return task;
+		}
+		Task {
+			captured: self.capture(),
+			..task
+		}
+	}
+}
+
+impl fmt::Debug for Propagators {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} propagator(s)", self.0.len())
+	}
+}
+
+/// The values captured when a task was enqueued, installed on the worker around its execution.
+#[derive(Default)]
+pub(crate) struct Captured(Vec<Installer>);
+
+impl Captured {
+	pub(crate) fn install(self) -> Installed {
+		Installed(self.0.into_iter().map(|install| install()).collect())
+	}
+}
+
+/// Drops the guards in reverse order, so that the values installed first are restored last.
+pub(crate) struct Installed(Vec<Box<dyn Any>>);
+
+impl Drop for Installed {
+	fn drop(&mut self) {
+		while let Some(guard) = self.0.pop() {
+			drop(guard);
+		}
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Carry some context, e.g. thread-locals holding the current request or locale, from
+	/// the threads enqueueing tasks to the workers running them: `capture` is called when
+	/// a task is enqueued, and `install` is called on the worker with the captured value
+	/// right before the task runs. The value returned by `install` is dropped once the task
+	/// is done, which is the place to restore what the worker had before.
+	///
+	/// Can be called multiple times to propagate multiple values. `capture` is called while
+	/// the task is being enqueued, so it must not enqueue tasks on the same pool.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{cell::RefCell, sync::mpsc};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// thread_local! {
+	/// 	static REQUEST_ID: RefCell<Option<u64>> = const { RefCell::new(None) };
+	/// }
+	///
+	/// /// Restores the previous request id when dropped.
+	/// struct Restore(Option<u64>);
+	///
+	/// impl Drop for Restore {
+	/// 	fn drop(&mut self) {
+	/// 		REQUEST_ID.with(|id| *id.borrow_mut() = self.0);
+	/// 	}
+	/// }
+	///
+	/// let pool = ThreadPool::builder(vec![(); 2])
+	/// 	.propagate(
+	/// 		|| REQUEST_ID.with(|id| *id.borrow()),
+	/// 		|captured| Restore(REQUEST_ID.with(|id| id.replace(captured))),
+	/// 	)
+	/// 	.build();
+	///
+	/// let (sender, receiver) = mpsc::channel();
+	/// REQUEST_ID.with(|id| *id.borrow_mut() = Some(42));
+	/// pool.enqueue(move |()| sender.send(REQUEST_ID.with(|id| *id.borrow())).unwrap());
+	///
+	/// assert_eq!(receiver.recv().unwrap(), Some(42));
+	/// ```
+	pub fn propagate<Captured, Guard>(
+		mut self,
+		capture: impl Fn() -> Captured + Send + Sync + 'static,
+		install: impl Fn(Captured) -> Guard + Send + Sync + 'static,
+	) -> Self
+	where
+		Captured: Send + 'static,
+		Guard: 'static,
+	{
+		let install = Arc::new(install);
+		self.propagators.0.push(Box::new(move || {
+			let captured = capture();
+			let install = install.clone();
+			Box::new(move || Box::new(install(captured)))
+		}));
+		self
+	}
+}
@@ -0,0 +1,37 @@
+/// The quality of service class of the worker threads on Apple platforms, which the scheduler
+/// uses to trade off performance and energy efficiency, e.g. to decide which cores to run them on.
+/// See [`ThreadPoolBuilder::qos_class`](crate::ThreadPoolBuilder::qos_class).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QosClass {
+	/// Work the user is directly interacting with, e.g. rendering the UI.
+	UserInteractive,
+	/// Work the user started and is waiting on, e.g. opening a document.
+	UserInitiated,
+	/// Long-running work whose progress the user is aware of, e.g. a download.
+	Utility,
+	/// Work the user isn't aware of, e.g. indexing or backups.
+	Background,
+}
+
+/// Set the quality of service class of the current thread, if supported on this platform
+/// and the `qos` feature is enabled.
+#[cfg(all(feature = "qos", target_vendor = "apple"))]
+pub(crate) fn set_current_thread_qos(class: QosClass) {
+	let class = match class {
+		QosClass::UserInteractive => libc::qos_class_t::QOS_CLASS_USER_INTERACTIVE,
+		QosClass::UserInitiated => libc::qos_class_t::QOS_CLASS_USER_INITIATED,
+		QosClass::Utility => libc::qos_class_t::QOS_CLASS_UTILITY,
+		QosClass::Background => libc::qos_class_t::QOS_CLASS_BACKGROUND,
+	};
+	// SAFETY: only affects the calling thread, and any class is valid with a relative priority of 0.
+	let result = unsafe { libc::pthread_set_qos_class_self_np(class, 0) };
+	if result != 0 {
+		warn!("failed to set the quality of service class of the worker: error {result}");
+	}
+}
+
+/// Set the quality of service class of the current thread, if supported on this platform
+/// and the `qos` feature is enabled.
+#[cfg(not(all(feature = "qos", target_vendor = "apple")))]
+pub(crate) fn set_current_thread_qos(_class: QosClass) {}
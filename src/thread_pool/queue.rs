@@ -0,0 +1,570 @@
+use std::{collections::VecDeque, mem, time::Instant};
+
+use super::{
+	builder::WorkerGroup,
+	idle::{IdleWorkerPolicy, IdleWorkers},
+	Task, TaskClass,
+};
+
+/// Index of the class tasks are enqueued in unless specified otherwise.
+pub(crate) const DEFAULT_CLASS: usize = 0;
+
+/// The pending tasks, split by class, together with the bookkeeping needed to enforce
+/// the queue limits.
+pub(crate) struct TaskQueue<WorkerData> {
+	classes: Vec<ClassQueue<WorkerData>>,
+	/// Tasks targeting a specific worker, which take precedence over the shared ones.
+	inboxes: Vec<VecDeque<Task<WorkerData>>>,
+	/// For each worker, the class holding the tasks of the group it belongs to, if any.
+	worker_groups: Vec<Option<usize>>,
+	/// Index of the first class holding the tasks of a worker group, see [`TaskQueue::new`].
+	first_group: usize,
+	/// Workers checked out by a [`WorkerLease`](crate::WorkerLease), which only serve their inbox.
+	reserved: Vec<bool>,
+	/// The capabilities of the workers, each being a bit in the masks of the workers and in
+	/// the requirements of the tasks, see [`TaskQueue::set_capabilities`].
+	capabilities: Vec<&'static str>,
+	worker_capabilities: Vec<u64>,
+	/// Workers from this index on are being retired by [`ThreadPool::shrink`](crate::ThreadPool::shrink),
+	/// and exit once their inbox is empty.
+	retired_from: usize,
+	idle: IdleWorkers,
+	/// Next class to look at when dequeuing, so that classes are served round-robin.
+	cursor: usize,
+	len: usize,
+	bytes: usize,
+	max_bytes: Option<usize>,
+	/// Sequence number assigned to the next task pushed to the queue.
+	next_seq: u64,
+	/// Whether a task was moved ahead of older ones since the queue was last empty, see
+	/// [`TaskQueue::boost_handle`].
+	reordered: bool,
+	/// Whether tasks from different producers are interleaved, see
+	/// [`ThreadPoolBuilder::fair_producers`](crate::ThreadPoolBuilder::fair_producers).
+	fair: bool,
+	next_producer: u64,
+}
+
+/// The capabilities of the workers of pools that don't tell them apart, which can run any task.
+const ALL_CAPABILITIES: u64 = u64::MAX;
+
+/// Producer of the tasks enqueued directly on the pool, rather than through a
+/// [`TaskSender`](crate::TaskSender).
+pub(crate) const ANONYMOUS_PRODUCER: u64 = 0;
+
+/// Capacity below which queues are never shrunk, as reallocating them wouldn't be worth it.
+const MIN_RECLAIMED_CAPACITY: usize = 64;
+
+/// Release the memory left over by a burst of tasks once the queue has mostly drained,
+/// rather than pinning it for the lifetime of the pool. Halving the capacity only once
+/// the queue is a quarter full keeps reallocations amortized over the pops, even when
+/// the length hovers around the threshold.
+fn reclaim<T>(tasks: &mut VecDeque<T>) {
+	if tasks.capacity() > MIN_RECLAIMED_CAPACITY && tasks.len() < tasks.capacity() / 4 {
+		tasks.shrink_to(tasks.capacity() / 2);
+	}
+}
+
+struct ClassQueue<WorkerData> {
+	tasks: VecDeque<Task<WorkerData>>,
+	max_tasks: usize,
+	max_workers: Option<usize>,
+	running: usize,
+	/// Producer currently being served when interleaving producers, and how many of its
+	/// tasks were dispatched in its current turn.
+	lane: u64,
+	served: usize,
+	/// Ticket lock granting room in the queue to blocked enqueuers in arrival order.
+	next_ticket: u64,
+	now_serving: u64,
+}
+
+impl<WorkerData> ClassQueue<WorkerData> {
+	fn can_dispatch(&self) -> bool {
+		!self.tasks.is_empty() && self.max_workers.is_none_or(|max| self.running < max)
+	}
+
+	/// Take the next task serving producers round-robin, in order of registration,
+	/// dispatching up to `weight` tasks of each producer per turn. Only the tasks whose
+	/// requirements are within `capabilities` are considered.
+	fn pop_fair(&mut self, capabilities: u64) -> Option<Task<WorkerData>> {
+		let position = self
+			.tasks
+			.iter()
+			.position(|task| task.producer == self.lane && task.fits(capabilities))
+			.filter(|&position| self.served < self.tasks[position].weight);
+		let position = if let Some(position) = position {
+			self.served += 1;
+			position
+		} else {
+			let producers = self
+				.tasks
+				.iter()
+				.filter(|task| task.fits(capabilities))
+				.map(|task| task.producer);
+			self.lane = producers
+				.clone()
+				.filter(|&producer| producer > self.lane)
+				.min()
+				.or_else(|| producers.min())?;
+			self.served = 1;
+			self.tasks
+				.iter()
+				.position(|task| task.producer == self.lane && task.fits(capabilities))?
+		};
+		self.tasks.remove(position)
+	}
+
+	/// Take the first task whose requirements are within `capabilities`.
+	fn pop_fitting(&mut self, capabilities: u64) -> Option<Task<WorkerData>> {
+		let position = self.tasks.iter().position(|task| task.fits(capabilities))?;
+		self.tasks.remove(position)
+	}
+}
+
+impl<WorkerData> TaskQueue<WorkerData> {
+	/// Construct a queue for the given number of workers, with a default class of size `max_tasks`,
+	/// followed by the given classes, followed by one class for each worker group.
+	/// Groups are assigned consecutive workers starting from the first one.
+	pub(crate) fn new(
+		workers: usize,
+		max_tasks: usize,
+		max_bytes: Option<usize>,
+		classes: &[TaskClass],
+		groups: &[WorkerGroup],
+		fair: bool,
+		idle_policy: IdleWorkerPolicy,
+	) -> Self {
+		let default_class = ClassQueue {
+			tasks: VecDeque::new(),
+			max_tasks,
+			max_workers: None,
+			running: 0,
+			lane: ANONYMOUS_PRODUCER,
+			served: 0,
+			next_ticket: 0,
+			now_serving: 0,
+		};
+		Self {
+			classes: std::iter::once(default_class)
+				.chain(classes.iter().map(|class| ClassQueue {
+					tasks: VecDeque::new(),
+					max_tasks: class.max_pending_tasks,
+					max_workers: class.max_workers,
+					running: 0,
+					lane: ANONYMOUS_PRODUCER,
+					served: 0,
+					next_ticket: 0,
+					now_serving: 0,
+				}))
+				.chain(groups.iter().map(|group| ClassQueue {
+					tasks: VecDeque::new(),
+					max_tasks: group.workers,
+					max_workers: None,
+					running: 0,
+					lane: ANONYMOUS_PRODUCER,
+					served: 0,
+					next_ticket: 0,
+					now_serving: 0,
+				}))
+				.collect(),
+			worker_groups: groups
+				.iter()
+				.enumerate()
+				.flat_map(|(i, group)| {
+					std::iter::repeat_n(Some(1 + classes.len() + i), group.workers)
+				})
+				.chain(std::iter::repeat(None))
+				.take(workers)
+				.collect(),
+			first_group: 1 + classes.len(),
+			inboxes: (0..workers).map(|_| VecDeque::new()).collect(),
+			reserved: vec![false; workers],
+			capabilities: Vec::new(),
+			worker_capabilities: vec![ALL_CAPABILITIES; workers],
+			retired_from: workers,
+			idle: IdleWorkers::new(idle_policy, workers),
+			cursor: 0,
+			len: 0,
+			bytes: 0,
+			max_bytes,
+			next_seq: 0,
+			reordered: false,
+			fair,
+			next_producer: ANONYMOUS_PRODUCER + 1,
+		}
+	}
+
+	pub(crate) fn len(&self) -> usize {
+		self.len
+	}
+
+	pub(crate) fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Approximate memory held by the pending tasks, as estimated at enqueue time.
+	pub(crate) fn bytes(&self) -> usize {
+		self.bytes
+	}
+
+	/// Check whether a task of the given size can be queued in the given class without
+	/// exceeding the limits. A task larger than the byte budget is still accepted when
+	/// the queue is empty, otherwise it would never be.
+	pub(crate) fn has_room(&self, class: usize, size: usize) -> bool {
+		let class = &self.classes[class];
+		class.tasks.len() < class.max_tasks
+			&& self
+				.max_bytes
+				.is_none_or(|max| self.is_empty() || self.bytes + size <= max)
+	}
+
+	/// The sequence number that will be assigned to the next task pushed to the queue.
+	pub(crate) fn next_seq(&self) -> u64 {
+		self.next_seq
+	}
+
+	/// The smallest sequence number among the pending tasks. As each class is FIFO, it's the
+	/// smallest among the heads of the classes, unless a task was moved ahead of older ones,
+	/// in which case every task is looked at until the queue drains.
+	pub(crate) fn min_seq(&mut self) -> Option<u64> {
+		if self.is_empty() {
+			self.reordered = false;
+			return None;
+		}
+		if self.reordered {
+			return self.iter().map(|task| task.seq).min();
+		}
+		self.classes
+			.iter()
+			.map(|class| &class.tasks)
+			.chain(&self.inboxes)
+			.filter_map(VecDeque::front)
+			.map(|task| task.seq)
+			.min()
+	}
+
+	/// Whether some enqueuers are blocked waiting for room in the given class.
+	pub(crate) fn has_waiters(&self, class: usize) -> bool {
+		let class = &self.classes[class];
+		class.next_ticket != class.now_serving
+	}
+
+	/// Take a ticket to wait for room in the given class.
+	pub(crate) fn take_ticket(&mut self, class: usize) -> u64 {
+		let class = &mut self.classes[class];
+		let ticket = class.next_ticket;
+		class.next_ticket += 1;
+		ticket
+	}
+
+	/// Whether the holder of the given ticket is the next enqueuer to be served in the given class.
+	pub(crate) fn is_turn(&self, class: usize, ticket: u64) -> bool {
+		self.classes[class].now_serving == ticket
+	}
+
+	/// Pass the turn to the next ticket holder of the given class.
+	pub(crate) fn advance_ticket(&mut self, class: usize) {
+		self.classes[class].now_serving += 1;
+	}
+
+	/// Queue a task in its class, or hand it straight to an idle worker when a policy
+	/// decides which one runs it and nothing is queued ahead of it.
+	pub(crate) fn push_back(&mut self, mut task: Task<WorkerData>) {
+		self.account_push(&mut task);
+		let class = task.class;
+		if self.idle.is_targeted()
+			&& self.classes[class].tasks.is_empty()
+			&& self.classes[class].max_workers.is_none()
+		{
+			let picked = self.idle.pick(|worker| {
+				!self.reserved[worker]
+					&& !self.is_retired(worker)
+					&& self.inboxes[worker].is_empty()
+					&& self.serves(worker, class)
+					&& task.fits(self.worker_capabilities[worker])
+			});
+			if let Some(worker) = picked {
+				self.idle.claim(worker);
+				self.inboxes[worker].push_back(task);
+				return;
+			}
+		}
+		self.classes[class].tasks.push_back(task);
+	}
+
+	/// Record that the given worker found nothing to run, see [`TaskQueue::push_back`].
+	pub(crate) fn set_idle(&mut self, worker: usize) {
+		self.idle.set_idle(worker);
+	}
+
+	/// Queue a task that only the given worker can run, ignoring the queue limits.
+	pub(crate) fn push_to_worker(&mut self, worker: usize, mut task: Task<WorkerData>) {
+		self.account_push(&mut task);
+		self.inboxes[worker].push_back(task);
+	}
+
+	/// Remove the tasks targeting the given worker.
+	pub(crate) fn clear_inbox(&mut self, worker: usize) -> VecDeque<Task<WorkerData>> {
+		let inbox = mem::take(&mut self.inboxes[worker]);
+		for task in &inbox {
+			self.len -= 1;
+			self.bytes -= task.size;
+		}
+		inbox
+	}
+
+	/// Remove the queued tasks of the given batch, see
+	/// [`Batch::abort_on_failure`](crate::Batch::abort_on_failure).
+	pub(crate) fn remove_batch(&mut self, batch: u64) -> Vec<Task<WorkerData>> {
+		let mut removed = Vec::new();
+		for tasks in self
+			.inboxes
+			.iter_mut()
+			.chain(self.classes.iter_mut().map(|class| &mut class.tasks))
+		{
+			let (matching, kept): (VecDeque<_>, _) = mem::take(tasks)
+				.into_iter()
+				.partition(|task| task.batch == batch);
+			*tasks = kept;
+			removed.extend(matching);
+		}
+		for task in &removed {
+			self.len -= 1;
+			self.bytes -= task.size;
+		}
+		removed
+	}
+
+	/// Remove the queued task with the given handle, unless it targets a worker or a group,
+	/// requires capabilities, or its class limits how many of its tasks run at once, see
+	/// [`TaskHandle::wait`](crate::TaskHandle::wait).
+	pub(crate) fn take_handle(&mut self, handle: u64) -> Option<Task<WorkerData>> {
+		let is_handle = |task: &Task<WorkerData>| task.handle == handle && task.requires == 0;
+		let class = self.classes[..self.first_group]
+			.iter_mut()
+			.filter(|class| class.max_workers.is_none())
+			.find(|class| class.tasks.iter().any(is_handle))?;
+		let position = class.tasks.iter().position(is_handle)?;
+		let task = class.tasks.remove(position)?;
+		reclaim(&mut class.tasks);
+		self.len -= 1;
+		self.bytes -= task.size;
+		Some(task)
+	}
+
+	/// Move the queued task with the given handle to the front of its class, or of the inbox
+	/// of its worker, returning it if it was found, see
+	/// [`ThreadPoolBuilder::boost_waited_tasks`](crate::ThreadPoolBuilder::boost_waited_tasks).
+	pub(crate) fn boost_handle(&mut self, handle: u64) -> Option<&Task<WorkerData>> {
+		for tasks in self
+			.inboxes
+			.iter_mut()
+			.chain(self.classes.iter_mut().map(|class| &mut class.tasks))
+		{
+			if let Some(position) = tasks.iter().position(|task| task.handle == handle) {
+				let task = tasks.remove(position).unwrap();
+				tasks.push_front(task);
+				self.reordered |= position > 0;
+				return tasks.front();
+			}
+		}
+		None
+	}
+
+	/// Iterate over the queued tasks, in no particular order.
+	pub(crate) fn iter(&self) -> impl Iterator<Item = &Task<WorkerData>> {
+		self.inboxes
+			.iter()
+			.chain(self.classes.iter().map(|class| &class.tasks))
+			.flatten()
+	}
+
+	/// Whether the given worker runs tasks of the given class. Workers in a group only run
+	/// the tasks of their group, while the others run the rest. When every worker is in
+	/// a group, the tasks outside of groups are run by all of them.
+	fn serves(&self, worker: usize, class: usize) -> bool {
+		match self.worker_groups[worker] {
+			Some(group) if group == class => true,
+			None => class < self.first_group,
+			Some(_) => class < self.first_group && self.worker_groups.iter().all(Option::is_some),
+		}
+	}
+
+	/// Assign an id to a new producer.
+	pub(crate) fn register_producer(&mut self) -> u64 {
+		let id = self.next_producer;
+		self.next_producer += 1;
+		id
+	}
+
+	/// Make room for `count` more workers, which don't belong to any group,
+	/// see [`ThreadPool::grow`](crate::ThreadPool::grow).
+	pub(crate) fn add_workers(&mut self, count: usize) {
+		let workers = self.inboxes.len() + count;
+		self.inboxes.resize_with(workers, VecDeque::new);
+		self.worker_groups.resize(workers, None);
+		self.reserved.resize(workers, false);
+		self.worker_capabilities.resize(workers, ALL_CAPABILITIES);
+		self.retired_from = workers;
+		self.idle.add_workers(count);
+	}
+
+	/// Tell the workers from index `first` on to exit once their inbox is empty,
+	/// see [`ThreadPool::shrink`](crate::ThreadPool::shrink).
+	pub(crate) fn retire_from(&mut self, first: usize) {
+		self.retired_from = first;
+	}
+
+	/// Whether the given worker should exit once its inbox is empty.
+	pub(crate) fn is_retired(&self, worker: usize) -> bool {
+		worker >= self.retired_from
+	}
+
+	/// Forget about the retired workers, returning the tasks left targeting them.
+	pub(crate) fn remove_retired(&mut self) -> Vec<Task<WorkerData>> {
+		let first = self.retired_from;
+		let orphaned = (first..self.inboxes.len())
+			.flat_map(|worker| self.clear_inbox(worker))
+			.collect();
+		self.inboxes.truncate(first);
+		self.worker_groups.truncate(first);
+		self.reserved.truncate(first);
+		self.worker_capabilities.truncate(first);
+		self.idle.remove_workers(first);
+		orphaned
+	}
+
+	/// Whether removing the workers from index `first` on would leave a worker group
+	/// without workers.
+	pub(crate) fn leaves_group_empty(&self, first: usize) -> bool {
+		let (kept, removed) = self.worker_groups.split_at(first);
+		removed
+			.iter()
+			.flatten()
+			.any(|group| !kept.contains(&Some(*group)))
+	}
+
+	/// Whether the given worker belongs to a worker group.
+	pub(crate) fn is_grouped(&self, worker: usize) -> bool {
+		self.worker_groups[worker].is_some()
+	}
+
+	/// Set the capabilities of the given worker, see
+	/// [`ThreadPoolBuilder::worker_capabilities`](crate::ThreadPoolBuilder::worker_capabilities).
+	///
+	/// # Panics
+	/// - if the workers have more than 64 distinct capabilities overall
+	pub(crate) fn set_capabilities(&mut self, worker: usize, capabilities: &[&'static str]) {
+		let mut mask = 0;
+		for &capability in capabilities {
+			let bit = self
+				.capabilities
+				.iter()
+				.position(|&c| c == capability)
+				.unwrap_or_else(|| {
+					assert!(
+						self.capabilities.len() < 64,
+						"workers can't have more than 64 distinct capabilities"
+					);
+					self.capabilities.push(capability);
+					self.capabilities.len() - 1
+				});
+			mask |= 1 << bit;
+		}
+		self.worker_capabilities[worker] = mask;
+	}
+
+	/// The mask of the given requirements, unless no worker satisfies them all.
+	pub(crate) fn requirements(&self, requirements: &[&str]) -> Option<u64> {
+		let mut mask = 0;
+		for requirement in requirements {
+			let bit = self.capabilities.iter().position(|c| c == requirement)?;
+			mask |= 1 << bit;
+		}
+		(0..self.retired_from)
+			.any(|worker| mask & !self.worker_capabilities[worker] == 0)
+			.then_some(mask)
+	}
+
+	pub(crate) fn is_reserved(&self, worker: usize) -> bool {
+		self.reserved[worker]
+	}
+
+	/// Reserve or release a worker, see [`TaskQueue::reserved`].
+	pub(crate) fn set_reserved(&mut self, worker: usize, reserved: bool) {
+		self.reserved[worker] = reserved;
+	}
+
+	fn account_push(&mut self, task: &mut Task<WorkerData>) {
+		task.seq = self.next_seq;
+		task.enqueued_at = Instant::now();
+		self.next_seq += 1;
+		self.len += 1;
+		self.bytes += task.size;
+		trace!(
+			"task enqueued",
+			task = task.seq,
+			class = task.class,
+			location = task.location,
+		);
+	}
+
+	/// Take the next task the given worker should run: tasks targeting it first, then shared ones
+	/// unless the worker is reserved or retired,
+	/// looking at classes round-robin and skipping those that already have as many running tasks
+	/// as they're allowed to.
+	pub(crate) fn pop_next(&mut self, worker: usize) -> Option<Task<WorkerData>> {
+		if let Some(task) = self.inboxes[worker].pop_front() {
+			reclaim(&mut self.inboxes[worker]);
+			self.idle.set_busy(worker);
+			self.len -= 1;
+			self.bytes -= task.size;
+			return Some(task);
+		}
+		if self.reserved[worker] || self.is_retired(worker) {
+			return None;
+		}
+
+		let capabilities = self.worker_capabilities[worker];
+		let n = self.classes.len();
+		let index = (0..n).map(|offset| (self.cursor + offset) % n).find(|&i| {
+			self.serves(worker, i)
+				&& self.classes[i].can_dispatch()
+				&& self.classes[i]
+					.tasks
+					.iter()
+					.any(|task| task.fits(capabilities))
+		})?;
+		self.cursor = (index + 1) % n;
+
+		let class = &mut self.classes[index];
+		let task = if self.fair {
+			class.pop_fair(capabilities)?
+		} else {
+			class.pop_fitting(capabilities)?
+		};
+		reclaim(&mut class.tasks);
+		self.idle.set_busy(worker);
+		if class.max_workers.is_some() {
+			class.running += 1;
+		}
+		self.len -= 1;
+		self.bytes -= task.size;
+		Some(task)
+	}
+
+	/// Whether tasks of the given class take up one of the slots limited by
+	/// [`TaskClass::max_workers`], which must be released with [`TaskQueue::release`].
+	pub(crate) fn is_capped(&self, class: usize) -> bool {
+		self.classes[class].max_workers.is_some()
+	}
+
+	/// Release the slot taken by a task of a capped class, returning whether
+	/// a task of that class became dispatchable.
+	pub(crate) fn release(&mut self, class: usize) -> bool {
+		let class = &mut self.classes[class];
+		class.running -= 1;
+		!class.tasks.is_empty()
+	}
+}
@@ -0,0 +1,89 @@
+use std::{any::Any, panic::Location, sync::atomic::Ordering, time::Duration};
+
+use super::{ThreadPool, WorkerState};
+
+/// A summary of the work done by a pool, as returned by [`ThreadPool::join_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinReport {
+	/// The number of tasks that ran to completion.
+	pub tasks_executed: u64,
+	/// The number of tasks that were dropped without being run, e.g. after a shutdown.
+	pub tasks_dropped: u64,
+	/// The number of tasks each worker ran to completion, indexed like the `WorkerData`
+	/// Vec the pool was constructed with.
+	pub tasks_per_worker: Vec<u64>,
+	/// Time elapsed between the construction of the pool and the moment all its workers exited.
+	pub wall_time: Duration,
+	/// The workers that were brought down by a panicking task.
+	pub worker_panics: Vec<WorkerPanic>,
+}
+
+/// A worker that was brought down by a panicking task, see [`JoinReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerPanic {
+	/// The index of the worker.
+	pub worker: usize,
+	/// The panic message, if the payload was a string.
+	pub message: Option<String>,
+	/// Where the panicking task was enqueued from, if the panic happened while running one.
+	pub location: Option<&'static Location<'static>>,
+}
+
+/// The panic message, if the payload is a string.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> Option<&str> {
+	payload
+		.downcast_ref::<&str>()
+		.copied()
+		.or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Like [`ThreadPool::join`], but returns a summary of the work done by the pool.
+	/// Worker panics are reported instead of being propagated.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 4]);
+	/// for _ in 0..16 {
+	/// 	pool.enqueue(|()| {});
+	/// }
+	///
+	/// let report = pool.join_report();
+	/// assert_eq!(report.tasks_executed, 16);
+	/// assert_eq!(report.tasks_per_worker.iter().sum::<u64>(), 16);
+	/// assert!(report.worker_panics.is_empty());
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn join_report(mut self) -> JoinReport {
+		let worker_panics = self
+			.stop_and_join()
+			.into_iter()
+			.filter_map(|(worker, result)| {
+				result.err().map(|payload| WorkerPanic {
+					worker,
+					message: panic_message(&*payload).map(str::to_string),
+					location: match *self.inner.workers()[worker].state.lock().unwrap() {
+						WorkerState::Panicked { location } => location,
+						_ => None,
+					},
+				})
+			})
+			.collect();
+		let inner = &*self.inner;
+		JoinReport {
+			tasks_executed: inner.counters.completed.load(Ordering::Relaxed),
+			tasks_dropped: inner.counters.discarded.load(Ordering::Relaxed),
+			tasks_per_worker: inner
+				.workers()
+				.iter()
+				.map(|worker| worker.completed.load(Ordering::Relaxed))
+				.collect(),
+			wall_time: inner.uptime(),
+			worker_panics,
+		}
+	}
+}
@@ -0,0 +1,344 @@
+use std::{
+	cmp::Ordering,
+	collections::BinaryHeap,
+	fmt, mem,
+	panic::{self, AssertUnwindSafe, Location},
+	sync::{atomic, Arc, Mutex, PoisonError, Weak},
+	time::{Duration, Instant},
+};
+
+use super::{PoolQueue, Task, ThreadPool, ThreadPoolShared};
+
+/// How many times, and how often, a failing task should be attempted.
+/// See [`ThreadPool::enqueue_with_retry`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use lending_thread_pool::RetryPolicy;
+///
+/// // 1st retry after 10ms, 2nd after 20ms, 3rd after 40ms.
+/// let policy = RetryPolicy::new(4)
+/// 	.backoff(Duration::from_millis(10))
+/// 	.backoff_factor(2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct RetryPolicy {
+	max_attempts: u32,
+	backoff: Duration,
+	backoff_factor: u32,
+}
+
+impl RetryPolicy {
+	/// Construct a policy that runs a task at most `max_attempts` times (including the first one),
+	/// retrying immediately.
+	///
+	/// # Panics
+	/// - if `max_attempts` is 0.
+	pub fn new(max_attempts: u32) -> Self {
+		assert_ne!(max_attempts, 0, "max_attempts must be greater than 0");
+		Self {
+			max_attempts,
+			backoff: Duration::ZERO,
+			backoff_factor: 1,
+		}
+	}
+
+	/// Set the delay before the first retry.
+	pub fn backoff(mut self, backoff: Duration) -> Self {
+		self.backoff = backoff;
+		self
+	}
+
+	/// Set the factor the delay gets multiplied by after each retry. Defaults to 1, i.e. a constant delay.
+	pub fn backoff_factor(mut self, backoff_factor: u32) -> Self {
+		self.backoff_factor = backoff_factor;
+		self
+	}
+
+	/// The maximum number of times a task is run.
+	#[must_use]
+	pub fn max_attempts(&self) -> u32 {
+		self.max_attempts
+	}
+
+	/// The delay before the retry following the given (0-based) attempt.
+	fn delay(&self, attempt: u32) -> Duration {
+		self.backoff
+			.saturating_mul(self.backoff_factor.saturating_pow(attempt))
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Enqueue a fallible task that is retried according to the given [`RetryPolicy`]
+	/// when it returns an error or panics.
+	///
+	/// Each retry is put back at the end of the queue, regardless of its capacity, so that
+	/// workers are never blocked on a full queue. Retries with a backoff delay wait it out
+	/// on a timer thread of the pool, as measured by its [`Clock`](crate::Clock), leaving the
+	/// workers free to run other tasks in the meantime. The thread is spawned when needed,
+	/// through the [`thread_spawner`](crate::ThreadPoolBuilder::thread_spawner) if one is set,
+	/// and exits once no retry is waiting.
+	///
+	/// If the last attempt panics, the panic is propagated as it would be for any other task.
+	/// Joining the pool waits for the retries waiting out their delay, but retries scheduled
+	/// after the pool has been shut down or joined are discarded.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::time::Duration;
+	/// use lending_thread_pool::{RetryPolicy, ThreadPool};
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	///
+	/// let mut attempts = 0;
+	/// pool.enqueue_with_retry(
+	/// 	RetryPolicy::new(3).backoff(Duration::from_millis(10)),
+	/// 	move |()| {
+	/// 		attempts += 1;
+	/// 		if attempts < 3 {
+	/// 			Err("not yet")
+	/// 		} else {
+	/// 			println!("succeeded after {attempts} attempts");
+	/// 			Ok(())
+	/// 		}
+	/// 	},
+	/// );
+	/// ```
+	#[track_caller]
+	pub fn enqueue_with_retry<Error, Task>(&self, policy: RetryPolicy, task: Task)
+	where
+		Task: FnMut(&mut WorkerData) -> Result<(), Error> + Send + 'static,
+	{
+		self.enqueue_task(retrying(
+			Arc::downgrade(&self.inner),
+			Location::caller(),
+			policy,
+			task,
+			0,
+		));
+	}
+}
+
+fn retrying<WorkerData, Error, F>(
+	inner: Weak<ThreadPoolShared<WorkerData>>,
+	location: &'static Location<'static>,
+	policy: RetryPolicy,
+	mut task: F,
+	attempt: u32,
+) -> Task<WorkerData>
+where
+	WorkerData: Send + 'static,
+	F: FnMut(&mut WorkerData) -> Result<(), Error> + Send + 'static,
+{
+	let run = move |worker_data: &mut WorkerData| {
+		let panic_payload = match panic::catch_unwind(AssertUnwindSafe(|| task(&mut *worker_data)))
+		{
+			Ok(Ok(())) => return,
+			Ok(Err(_)) => None,
+			Err(payload) => Some(payload),
+		};
+		if attempt + 1 >= policy.max_attempts {
+			debug!("task failed, giving up", attempts = attempt + 1);
+			if let Some(payload) = panic_payload {
+				panic::resume_unwind(payload);
+			}
+			return;
+		}
+		let Some(inner) = inner.upgrade() else {
+			return;
+		};
+		debug!("task failed, retrying", attempt = attempt + 1);
+		let delay = policy.delay(attempt);
+		let retry = retrying(Arc::downgrade(&inner), location, policy, task, attempt + 1);
+		if delay.is_zero() {
+			inner.requeue(retry);
+		} else {
+			inner.delay_retry(delay, retry);
+		}
+	};
+	Task {
+		location,
+		..Task::new(run)
+	}
+}
+
+/// The retries waiting out their backoff delay, see [`ThreadPool::enqueue_with_retry`].
+pub(crate) struct DelayedRetries<WorkerData> {
+	state: Mutex<DelayedState<WorkerData>>,
+	/// Retries not back in the queue yet, only updated under the queue lock, so that the
+	/// pool is never seen drained while a retry is on its way back.
+	pending: atomic::AtomicUsize,
+}
+
+struct DelayedState<WorkerData> {
+	/// Ordered by due time, then by scheduling order.
+	retries: BinaryHeap<DelayedRetry<WorkerData>>,
+	next_seq: u64,
+	/// Whether the timer thread is running.
+	timer: bool,
+}
+
+struct DelayedRetry<WorkerData> {
+	due: Instant,
+	seq: u64,
+	task: Task<WorkerData>,
+}
+
+impl<WorkerData> PartialEq for DelayedRetry<WorkerData> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl<WorkerData> Eq for DelayedRetry<WorkerData> {}
+
+impl<WorkerData> PartialOrd for DelayedRetry<WorkerData> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<WorkerData> Ord for DelayedRetry<WorkerData> {
+	/// Reversed, as [`BinaryHeap`] is a max-heap.
+	fn cmp(&self, other: &Self) -> Ordering {
+		(other.due, other.seq).cmp(&(self.due, self.seq))
+	}
+}
+
+impl<WorkerData> Default for DelayedRetries<WorkerData> {
+	fn default() -> Self {
+		Self {
+			state: Mutex::new(DelayedState {
+				retries: BinaryHeap::new(),
+				next_seq: 0,
+				timer: false,
+			}),
+			pending: atomic::AtomicUsize::new(0),
+		}
+	}
+}
+
+impl<WorkerData> fmt::Debug for DelayedRetries<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DelayedRetries")
+			.field("pending", &self.pending)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPoolShared<WorkerData> {
+	/// Hand a retry over to the timer thread, which puts it back in the queue once `delay`
+	/// has elapsed, spawning the thread if it isn't running.
+	fn delay_retry(self: &Arc<Self>, delay: Duration, task: Task<WorkerData>) {
+		let guard = self.pending_tasks.lock().unwrap();
+		if matches!(*guard, PoolQueue::Done) {
+			drop(guard);
+			debug!("pool is shut down, discarding task");
+			self.counters
+				.discarded
+				.fetch_add(1, atomic::Ordering::Relaxed);
+			return;
+		}
+		self.retries.pending.fetch_add(1, atomic::Ordering::SeqCst);
+		let mut state = self.retries.state.lock().unwrap();
+		let seq = state.next_seq;
+		state.next_seq += 1;
+		state.retries.push(DelayedRetry {
+			due: self.clock.now() + delay,
+			seq,
+			task,
+		});
+		let spawn = !mem::replace(&mut state.timer, true);
+		drop(state);
+		drop(guard);
+		if !spawn {
+			return;
+		}
+		let inner = self.clone();
+		if let Err(error) = self.spawn_thread("retry timer".to_string(), move || inner.run_timer())
+		{
+			warn!(
+				"failed to spawn the retry timer, retrying right away",
+				error = error
+			);
+			let mut state = self.retries.state.lock().unwrap();
+			state.timer = false;
+			let retries = mem::take(&mut state.retries);
+			drop(state);
+			for retry in retries {
+				self.requeue_delayed(retry.task);
+			}
+		}
+	}
+
+	/// Put the retries back in the queue as they become due, exiting once there's none left.
+	fn run_timer(&self) {
+		/// Upper bound on each sleep, so that retries due before the one slept on aren't
+		/// late by more than that.
+		const TICK: Duration = Duration::from_millis(10);
+
+		loop {
+			let mut state = self.retries.state.lock().unwrap();
+			let Some(next) = state.retries.peek() else {
+				state.timer = false;
+				return;
+			};
+			let now = self.clock.now();
+			if next.due <= now {
+				let retry = state.retries.pop().unwrap();
+				drop(state);
+				trace!("retry is due", location = retry.task.location);
+				self.requeue_delayed(retry.task);
+			} else {
+				let remaining = (next.due - now).min(TICK);
+				drop(state);
+				self.clock.sleep(remaining);
+			}
+		}
+	}
+
+	fn requeue_delayed(&self, task: Task<WorkerData>) {
+		self.requeue(task);
+		let guard = self.pending_tasks.lock().unwrap();
+		self.retries.pending.fetch_sub(1, atomic::Ordering::SeqCst);
+		drop(guard);
+		// Whoever is waiting for the pool to drain has to take another look.
+		self.wake_all_workers();
+		self.notify_room();
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Whether retries are waiting out their delay. Only reliable under the queue lock.
+	pub(crate) fn has_delayed_retries(&self) -> bool {
+		self.retries.pending.load(atomic::Ordering::SeqCst) > 0
+	}
+
+	/// Discard the retries waiting out their delay, once the pool is shut down.
+	pub(crate) fn discard_delayed_retries(&self) {
+		let mut state = self
+			.retries
+			.state
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner);
+		let retries = mem::take(&mut state.retries);
+		drop(state);
+		let tasks = retries
+			.into_iter()
+			.map(|retry| retry.task)
+			.collect::<Vec<_>>();
+		self.retries
+			.pending
+			.fetch_sub(tasks.len(), atomic::Ordering::SeqCst);
+		self.report_discarded(&tasks);
+	}
+}
@@ -0,0 +1,238 @@
+use std::{
+	cmp::Ordering as CmpOrdering,
+	hash::{DefaultHasher, Hash, Hasher},
+	sync::atomic::Ordering,
+};
+
+use super::{worker::NOT_RUNNING, PoolQueue, ThreadPool, ThreadPoolShared};
+
+/// A single submission surface over several pools, e.g. one pool per GPU when the data
+/// of the workers can't live in one pool, routing each task to the least loaded pool
+/// or to the pool picked by a key.
+///
+/// Pools can be added and removed while the router is in use, with
+/// [`PoolRouter::add_pool`] and [`PoolRouter::remove_pool`], only moving the keys that have
+/// to move, see [`PoolRouter::enqueue_keyed`].
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::{PoolRouter, ThreadPool};
+///
+/// struct Gpu {
+/// 	id: usize,
+/// }
+///
+/// let router = PoolRouter::new(
+/// 	(0..2)
+/// 		.map(|id| ThreadPool::new(vec![Gpu { id }]))
+/// 		.collect(),
+/// );
+///
+/// for i in 0..8 {
+/// 	router.enqueue(move |gpu| println!("task {i} on GPU {}", gpu.id));
+/// }
+/// router.enqueue_keyed(&"user-42", |gpu| println!("sticky task on GPU {}", gpu.id));
+/// router.join();
+/// ```
+#[derive(Debug)]
+pub struct PoolRouter<WorkerData: Send = ()> {
+	pools: Vec<ThreadPool<WorkerData>>,
+	/// For each pool, the id keys are hashed with, which stays the same when other pools
+	/// are added or removed.
+	ids: Vec<u64>,
+	next_id: u64,
+}
+
+impl<WorkerData: Send> PoolRouter<WorkerData> {
+	/// Construct a router over the given pools.
+	///
+	/// # Panics
+	///
+	/// - if `pools` is empty
+	#[must_use]
+	pub fn new(pools: Vec<ThreadPool<WorkerData>>) -> Self {
+		assert!(!pools.is_empty(), "pools must contain at least one item");
+		let ids = (0..pools.len() as u64).collect::<Vec<_>>();
+		Self {
+			next_id: ids.len() as u64,
+			pools,
+			ids,
+		}
+	}
+
+	/// Add a pool after the others. Only the keys for which the new pool wins the hashing
+	/// move to it, about one in the number of pools, see [`PoolRouter::enqueue_keyed`].
+	pub fn add_pool(&mut self, pool: ThreadPool<WorkerData>) {
+		self.pools.push(pool);
+		self.ids.push(self.next_id);
+		self.next_id += 1;
+	}
+
+	/// Remove the pool at `index` and return it, shifting the following pools. Only the keys
+	/// that were routed to it move, spread over the remaining pools, see
+	/// [`PoolRouter::enqueue_keyed`].
+	///
+	/// The tasks already enqueued in the pool aren't affected, e.g. to be waited for with
+	/// [`ThreadPool::join`].
+	///
+	/// # Panics
+	///
+	/// - if `index` is out of bounds
+	/// - if it's the last pool of the router
+	pub fn remove_pool(&mut self, index: usize) -> ThreadPool<WorkerData> {
+		assert!(
+			self.pools.len() > 1,
+			"router to keep at least one pool after the removal"
+		);
+		self.ids.remove(index);
+		self.pools.remove(index)
+	}
+
+	/// The pools behind the router, in the order they were passed to [`PoolRouter::new`],
+	/// followed by the ones added with [`PoolRouter::add_pool`].
+	#[must_use]
+	pub fn pools(&self) -> &[ThreadPool<WorkerData>] {
+		&self.pools
+	}
+
+	/// Enqueue a task in the least loaded pool, i.e. the one with the fewest queued
+	/// and running tasks per live worker, preferring the first pools on ties.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue of the chosen pool to have
+	/// at least one empty slot before returning.
+	#[allow(clippy::missing_panics_doc)]
+	#[track_caller]
+	pub fn enqueue<Task: FnOnce(&mut WorkerData) + Send + 'static>(&self, task: Task) {
+		let pool = self
+			.pools
+			.iter()
+			.map(|pool| (pool, pool.inner.load()))
+			.min_by(|(_, a), (_, b)| a.compare(*b))
+			.map(|(pool, _)| pool)
+			.unwrap();
+		pool.enqueue(task);
+	}
+
+	/// Enqueue a task in the pool picked by hashing `key`, so that tasks with the same key
+	/// always go to the same pool, e.g. to benefit from data cached by its workers.
+	///
+	/// Keys are routed with rendezvous hashing: the key is hashed with each pool, and the
+	/// highest hash wins. Adding or removing a pool thus only moves the keys won or lost by
+	/// that pool, leaving the others where their caches are, rather than reshuffling most
+	/// of them as a hash modulo the number of pools would. The pools' own workers aren't
+	/// keyed, so resizing a pool, e.g. with [`ThreadPool::grow`], doesn't move any key.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue of the chosen pool to have
+	/// at least one empty slot before returning.
+	#[track_caller]
+	pub fn enqueue_keyed<Task: FnOnce(&mut WorkerData) + Send + 'static>(
+		&self,
+		key: &impl Hash,
+		task: Task,
+	) {
+		self.pools[self.pool_index_for(key)].enqueue(task);
+	}
+
+	/// The index of the pool [`PoolRouter::enqueue_keyed`] routes `key` to.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::{PoolRouter, ThreadPool};
+	///
+	/// let mut router = PoolRouter::new((0..4).map(|_| ThreadPool::new(vec![()])).collect());
+	/// let before = (0..1000)
+	/// 	.map(|key| router.pool_index_for(&key))
+	/// 	.collect::<Vec<_>>();
+	///
+	/// router.remove_pool(3).join();
+	/// router.add_pool(ThreadPool::new(vec![()]));
+	///
+	/// // The keys that weren't on the removed pool either stay put or move to the new one.
+	/// for (key, before) in (0..1000).zip(before) {
+	/// 	let after = router.pool_index_for(&key);
+	/// 	assert!(before == 3 || after == before || after == 3);
+	/// }
+	/// ```
+	#[must_use]
+	#[allow(clippy::missing_panics_doc)]
+	pub fn pool_index_for(&self, key: &impl Hash) -> usize {
+		let mut hasher = DefaultHasher::new();
+		key.hash(&mut hasher);
+		let key = hasher.finish();
+		let weight = |id: u64| {
+			let mut hasher = DefaultHasher::new();
+			(key, id).hash(&mut hasher);
+			hasher.finish()
+		};
+		self.ids
+			.iter()
+			.enumerate()
+			.max_by_key(|(_, id)| weight(**id))
+			.map(|(index, _)| index)
+			.unwrap()
+	}
+
+	/// Join all the pools, see [`ThreadPool::join`].
+	pub fn join(self) {
+		for pool in self.pools {
+			pool.join();
+		}
+	}
+
+	/// Take the pools back, in the order they were passed to [`PoolRouter::new`].
+	#[must_use]
+	pub fn into_pools(self) -> Vec<ThreadPool<WorkerData>> {
+		self.pools
+	}
+}
+
+/// The tasks queued or running in a pool, relative to its live workers.
+#[derive(Debug, Clone, Copy)]
+struct Load {
+	busy: usize,
+	workers: usize,
+}
+
+impl Load {
+	fn compare(self, other: Self) -> CmpOrdering {
+		match (self.workers, other.workers) {
+			// Pools that can't run tasks come last.
+			(0, 0) => CmpOrdering::Equal,
+			(0, _) => CmpOrdering::Greater,
+			(_, 0) => CmpOrdering::Less,
+			_ => (self.busy * other.workers).cmp(&(other.busy * self.workers)),
+		}
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// An estimate of the load of the pool, see [`PoolRouter::enqueue`].
+	fn load(&self) -> Load {
+		let queued = match &*self.pending_tasks.lock().unwrap() {
+			PoolQueue::Done => {
+				return Load {
+					busy: 0,
+					workers: 0,
+				}
+			}
+			PoolQueue::Todo(tasks) => tasks.len(),
+		};
+		let workers = self.workers();
+		let running = workers
+			.iter()
+			.filter(|worker| worker.running_seq.load(Ordering::SeqCst) != NOT_RUNNING)
+			.count();
+		Load {
+			busy: queued + running,
+			workers: workers
+				.len()
+				.saturating_sub(self.dead.load(Ordering::Relaxed)),
+		}
+	}
+}
@@ -0,0 +1,84 @@
+use std::{
+	io,
+	thread::{self, Scope},
+};
+
+use super::{
+	spawner::{PoolThread, Spawner},
+	ThreadPool, ThreadPoolBuilder,
+};
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Construct a thread pool whose workers are spawned in the given [`Scope`], so that
+	/// `WorkerData` can borrow from the stack frame enclosing the call to [`std::thread::scope`].
+	/// Otherwise equivalent to [`ThreadPool::new`], see [`ThreadPoolBuilder::build_scoped`]
+	/// to configure the pool.
+	///
+	/// Panics of the workers are reported by [`ThreadPool::join_report`] as usual,
+	/// rather than being propagated when the scope ends.
+	///
+	/// # Blocking
+	///
+	/// The pool must be joined or dropped before the end of the scope, as the scope waits
+	/// for all its threads to exit.
+	///
+	/// # Panics
+	/// - if Vec is empty
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::thread;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let mut counts = vec![0; 4];
+	///
+	/// thread::scope(|scope| {
+	/// 	let pool = ThreadPool::new_scoped(scope, counts.iter_mut().collect());
+	/// 	for _ in 0..16 {
+	/// 		pool.enqueue(|count| **count += 1);
+	/// 	}
+	/// });
+	///
+	/// assert_eq!(counts.iter().sum::<i32>(), 16);
+	/// ```
+	#[must_use]
+	pub fn new_scoped<'scope>(
+		scope: &'scope Scope<'scope, '_>,
+		workers_data: Vec<WorkerData>,
+	) -> Self
+	where
+		WorkerData: 'scope,
+	{
+		Self::builder(workers_data).build_scoped(scope)
+	}
+}
+
+impl<'scope> Spawner<'scope> for &'scope Scope<'scope, '_> {
+	fn spawn<T: Send + 'scope>(
+		&self,
+		name: String,
+		f: impl FnOnce() -> T + Send + 'scope,
+	) -> io::Result<PoolThread<T>> {
+		// Catching the panic also keeps the scope from propagating it.
+		let (f, thread) = PoolThread::reporting(f);
+		thread::Builder::new().name(name).spawn_scoped(self, f)?;
+		Ok(thread)
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Like [`ThreadPoolBuilder::build`], but spawn the threads of the pool in the given
+	/// [`Scope`], see [`ThreadPool::new_scoped`].
+	///
+	/// # Panics
+	/// - in the same cases as [`ThreadPoolBuilder::build`]
+	#[must_use]
+	pub fn build_scoped<'scope>(self, scope: &'scope Scope<'scope, '_>) -> ThreadPool<WorkerData>
+	where
+		WorkerData: 'scope,
+	{
+		self.validate();
+		ThreadPool::spawn(self, &scope)
+	}
+}
@@ -0,0 +1,254 @@
+use std::{
+	collections::VecDeque,
+	fmt, mem,
+	sync::{atomic::Ordering, Arc, Condvar, Mutex},
+};
+
+use super::{queue::TaskQueue, PoolQueue, Task, ThreadPoolBuilder, ThreadPoolShared};
+
+/// The tasks enqueued through a [`TaskSender`](crate::TaskSender), kept apart from the queue
+/// of the pool until a worker moves them over, see [`ThreadPoolBuilder::sender_segments`].
+pub(crate) struct Segment<WorkerData> {
+	tasks: Mutex<VecDeque<Task<WorkerData>>>,
+	/// Notified when tasks are moved out of the segment.
+	room: Condvar,
+}
+
+impl<WorkerData> Segment<WorkerData> {
+	/// Take the first task of the segment if `fits` accepts it.
+	fn pop_if(&self, fits: impl FnOnce(&Task<WorkerData>) -> bool) -> Option<Task<WorkerData>> {
+		let mut tasks = self.tasks.lock().unwrap();
+		if !tasks.front().is_some_and(fits) {
+			return None;
+		}
+		let task = tasks.pop_front()?;
+		drop(tasks);
+		self.room.notify_all();
+		Some(task)
+	}
+
+	fn take_all(&self) -> VecDeque<Task<WorkerData>> {
+		let tasks = mem::take(&mut *self.tasks.lock().unwrap());
+		self.room.notify_all();
+		tasks
+	}
+}
+
+impl<WorkerData> fmt::Debug for Segment<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Segment")
+			.field("len", &self.tasks.lock().unwrap().len())
+			.finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Register a segment for a new sender, if the pool has them.
+	pub(crate) fn register_segment(&self) -> Option<Arc<Segment<WorkerData>>> {
+		self.segment_capacity?;
+		let segment = Arc::new(Segment {
+			tasks: Mutex::default(),
+			room: Condvar::new(),
+		});
+		self.segments.write().unwrap().push(segment.clone());
+		Some(segment)
+	}
+
+	/// Unregister the segment of a sender being dropped, moving its tasks to the queue
+	/// regardless of its capacity, as there's no producer left to block.
+	pub(crate) fn unregister_segment(&self, segment: &Arc<Segment<WorkerData>>) {
+		let mut guard = self.pending_tasks.lock().unwrap();
+		self.segments
+			.write()
+			.unwrap()
+			.retain(|registered| !Arc::ptr_eq(registered, segment));
+		let tasks = segment.take_all();
+		self.segmented.fetch_sub(tasks.len(), Ordering::SeqCst);
+		match &mut *guard {
+			PoolQueue::Todo(queue) => {
+				let moved = tasks.len();
+				for task in tasks {
+					queue.push_back(task);
+				}
+				self.counters
+					.enqueued
+					.fetch_add(moved as u64, Ordering::Relaxed);
+				self.notify_workers(moved);
+			}
+			PoolQueue::Done => {
+				drop(guard);
+				self.report_discarded(&tasks);
+			}
+		}
+	}
+
+	/// Push a task to the segment of its sender, waiting for room in the segment, and wake
+	/// a worker to move it to the queue if they're all asleep. Returns whether the task was
+	/// accepted, i.e. whether the pool is still running and its [`DeadWorkerPolicy`]
+	/// doesn't reject it.
+	///
+	/// As when enqueuing on the pool, the task is run by the caller instead of waiting if
+	/// the segment is full and the pool [lends data to it](ThreadPoolBuilder::caller_runs).
+	///
+	/// [`DeadWorkerPolicy`]: crate::DeadWorkerPolicy
+	#[track_caller]
+	pub(crate) fn push_to_segment(
+		&self,
+		segment: &Segment<WorkerData>,
+		task: Task<WorkerData>,
+	) -> bool {
+		if !self.accepts_despite_dead_workers() {
+			self.report_discarded([&task]);
+			return false;
+		}
+		let capacity = self.segment_capacity.unwrap_or(usize::MAX);
+		let tasks = segment.tasks.lock().unwrap();
+		if tasks.len() >= capacity && task.requires == 0 {
+			if let Some(worker_data) = self.caller_data() {
+				self.start_on_caller(&task);
+				drop(tasks);
+				self.run_on_caller(task, worker_data);
+				return true;
+			}
+		}
+		let mut tasks = segment
+			.room
+			.wait_while(tasks, |tasks| {
+				tasks.len() >= capacity && !self.shut_down.load(Ordering::Relaxed)
+			})
+			.unwrap();
+		if self.shut_down.load(Ordering::Relaxed) {
+			debug!("pool is shut down, discarding task");
+			self.counters.discarded.fetch_add(1, Ordering::Relaxed);
+			return false;
+		}
+		trace!("task pushed to its segment", location = task.location);
+		tasks.push_back(task);
+		self.segmented.fetch_add(1, Ordering::SeqCst);
+		drop(tasks);
+		// Pairs with the workers counting themselves as sleeping before a last look
+		// at the segments, so that either they see the task or it sees them.
+		if self.sleeping.load(Ordering::SeqCst) > 0 {
+			let mut guard = self.pending_tasks.lock().unwrap();
+			let moved = self.pull_segments(&mut guard);
+			self.notify_workers(moved);
+		}
+		true
+	}
+
+	/// Move as many tasks as fit from the segments to the queue, taking one task from each
+	/// segment in turn, and return how many were moved. Tasks don't overtake the enqueuers
+	/// blocked waiting for room in their class.
+	pub(crate) fn pull_segments(&self, queue: &mut PoolQueue<WorkerData>) -> usize {
+		let PoolQueue::Todo(tasks) = queue else {
+			return 0;
+		};
+		if self.segmented.load(Ordering::SeqCst) == 0 {
+			return 0;
+		}
+		let segments = self.segments.read().unwrap();
+		let mut moved = 0;
+		let mut cursor = self.segment_cursor.load(Ordering::Relaxed);
+		loop {
+			let moved_before = moved;
+			for _ in 0..segments.len() {
+				cursor = (cursor + 1) % segments.len();
+				let fits = |task: &Task<WorkerData>| {
+					tasks.has_room(task.class, task.size) && !tasks.has_waiters(task.class)
+				};
+				if let Some(task) = segments[cursor].pop_if(fits) {
+					tasks.push_back(task);
+					moved += 1;
+				}
+			}
+			if moved == moved_before {
+				break;
+			}
+		}
+		self.segment_cursor.store(cursor, Ordering::Relaxed);
+		self.segmented.fetch_sub(moved, Ordering::SeqCst);
+		self.counters
+			.enqueued
+			.fetch_add(moved as u64, Ordering::Relaxed);
+		moved
+	}
+
+	/// Move all the tasks from the segments to the queue, regardless of its capacity,
+	/// so that [`ThreadPool::flush`](crate::ThreadPool::flush) accounts for them.
+	pub(crate) fn drain_segments(&self, tasks: &mut TaskQueue<WorkerData>) {
+		if self.segmented.load(Ordering::SeqCst) == 0 {
+			return;
+		}
+		let mut moved = 0;
+		for segment in &*self.segments.read().unwrap() {
+			for task in segment.take_all() {
+				tasks.push_back(task);
+				moved += 1;
+			}
+		}
+		self.segmented.fetch_sub(moved, Ordering::SeqCst);
+		self.counters
+			.enqueued
+			.fetch_add(moved as u64, Ordering::Relaxed);
+		self.notify_workers(moved);
+	}
+
+	/// Discard the tasks of the segments of a pool being shut down, unblocking the senders
+	/// waiting for room.
+	pub(crate) fn discard_segments(&self) {
+		let discarded = self
+			.segments
+			.read()
+			.unwrap()
+			.iter()
+			.flat_map(|segment| segment.take_all())
+			.collect::<Vec<_>>();
+		self.segmented.fetch_sub(discarded.len(), Ordering::SeqCst);
+		self.report_discarded(&discarded);
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolBuilder<WorkerData> {
+	/// Give each [`TaskSender`](crate::TaskSender) its own segment of the queue, holding up
+	/// to `capacity` tasks, so that producers enqueueing through different senders don't
+	/// contend on the lock of the queue. Workers move the tasks from the segments to the
+	/// queue, taking one from each segment in turn, as long as the queue has room for them.
+	/// A sender blocks while its segment is full.
+	///
+	/// Tasks are only counted as enqueued, e.g. by [`ThreadPool::pending`](crate::ThreadPool::pending)
+	/// and [`ThreadPool::status`](crate::ThreadPool::status), once they're moved to the queue.
+	/// The [`dead_worker_policy`](Self::dead_worker_policy) still applies when they're sent,
+	/// and with [`caller_runs`](Self::caller_runs) a sender runs the task itself instead of
+	/// blocking on a full segment.
+	/// The tasks left in the segment of a sender are moved to the queue when it's dropped,
+	/// regardless of its capacity. Tasks enqueued directly on the pool are unaffected.
+	///
+	/// Defaults to senders sharing the queue with the rest of the pool.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::thread;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::builder(vec![(); 4])
+	/// 	.sender_segments(64)
+	/// 	.build();
+	///
+	/// thread::scope(|scope| {
+	/// 	for producer in 0..8 {
+	/// 		let sender = pool.sender();
+	/// 		scope.spawn(move || {
+	/// 			for i in 0..1000 {
+	/// 				sender.enqueue(move |()| drop((producer, i)));
+	/// 			}
+	/// 		});
+	/// 	}
+	/// });
+	/// pool.flush();
+	/// ```
+	pub fn sender_segments(mut self, capacity: usize) -> Self {
+		self.segment_capacity = Some(capacity);
+		self
+	}
+}
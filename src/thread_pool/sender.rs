@@ -0,0 +1,158 @@
+use std::{
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, Instant},
+};
+
+use super::{segment::Segment, PoolQueue, Task, ThreadPool};
+
+/// A handle for a producer of tasks, as returned by [`ThreadPool::sender`].
+///
+/// Tasks enqueued through a sender behave like the ones enqueued directly on the pool,
+/// except that they can be interleaved with the tasks of other producers when the pool is
+/// built with [`ThreadPoolBuilder::fair_producers`](crate::ThreadPoolBuilder::fair_producers),
+/// and that they go through a segment of the queue of their own when the pool is built with
+/// [`ThreadPoolBuilder::sender_segments`](crate::ThreadPoolBuilder::sender_segments).
+///
+/// Each sender keeps its own [`SenderStats`], so that pool usage can be attributed
+/// to the subsystem that owns it.
+#[derive(Debug)]
+pub struct TaskSender<'pool, WorkerData: Send> {
+	pool: &'pool ThreadPool<WorkerData>,
+	id: u64,
+	weight: usize,
+	counters: Arc<SenderCounters>,
+	/// See [`ThreadPoolBuilder::sender_segments`](crate::ThreadPoolBuilder::sender_segments).
+	segment: Option<Arc<Segment<WorkerData>>>,
+}
+
+/// A snapshot of the statistics of a [`TaskSender`], as returned by [`TaskSender::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SenderStats {
+	/// The number of tasks accepted by the pool.
+	pub submitted: u64,
+	/// The number of tasks that ran to completion.
+	pub completed: u64,
+	/// The number of tasks refused because the pool had been shut down.
+	pub rejected: u64,
+	/// The total time tasks spent between being enqueued and starting to run,
+	/// including the time spent blocked waiting for room in the queue.
+	pub queue_wait: Duration,
+}
+
+#[derive(Debug, Default)]
+struct SenderCounters {
+	submitted: AtomicU64,
+	completed: AtomicU64,
+	rejected: AtomicU64,
+	queue_wait_nanos: AtomicU64,
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Construct a [`TaskSender`] with a weight of 1.
+	#[must_use]
+	pub fn sender(&self) -> TaskSender<'_, WorkerData> {
+		self.sender_with_weight(1)
+	}
+
+	/// Construct a [`TaskSender`] that gets to dispatch up to `weight` tasks per turn
+	/// when producers are interleaved.
+	///
+	/// # Panics
+	/// - if `weight` is 0
+	#[must_use]
+	pub fn sender_with_weight(&self, weight: usize) -> TaskSender<'_, WorkerData> {
+		assert_ne!(weight, 0, "weight must be greater than 0");
+		let id = match &mut *self.inner.pending_tasks.lock().unwrap() {
+			PoolQueue::Todo(tasks) => tasks.register_producer(),
+			// tasks are discarded anyway
+			PoolQueue::Done => u64::MAX,
+		};
+		TaskSender {
+			pool: self,
+			id,
+			weight,
+			counters: Arc::default(),
+			segment: self.inner.register_segment(),
+		}
+	}
+}
+
+impl<WorkerData: Send> TaskSender<'_, WorkerData> {
+	/// Enqueue a task in the pool on behalf of this producer.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	#[track_caller]
+	pub fn enqueue(&self, task: impl FnOnce(&mut WorkerData) + Send + 'static) {
+		let counters = self.counters.clone();
+		let enqueued_at = Instant::now();
+		let task = Task {
+			producer: self.id,
+			weight: self.weight,
+			..Task::new(move |worker_data: &mut WorkerData| {
+				let wait = u64::try_from(enqueued_at.elapsed().as_nanos()).unwrap_or(u64::MAX);
+				counters.queue_wait_nanos.fetch_add(wait, Ordering::Relaxed);
+				task(worker_data);
+				counters.completed.fetch_add(1, Ordering::Relaxed);
+			})
+		};
+		let inner = &self.pool.inner;
+		let accepted = match &self.segment {
+			Some(segment) if !inner.is_inline() => {
+				inner.admit(&task) && inner.push_to_segment(segment, inner.propagators.wrap(task))
+			}
+			_ => self.pool.enqueue_task(task),
+		};
+		let counter = if accepted {
+			&self.counters.submitted
+		} else {
+			&self.counters.rejected
+		};
+		counter.fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Take a snapshot of the statistics of this sender.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	/// let sender = pool.sender();
+	///
+	/// for _ in 0..8 {
+	/// 	sender.enqueue(|()| {});
+	/// }
+	/// pool.flush();
+	///
+	/// let stats = sender.stats();
+	/// assert_eq!(stats.submitted, 8);
+	/// assert_eq!(stats.completed, 8);
+	/// assert_eq!(stats.rejected, 0);
+	/// ```
+	#[must_use]
+	pub fn stats(&self) -> SenderStats {
+		SenderStats {
+			submitted: self.counters.submitted.load(Ordering::Relaxed),
+			completed: self.counters.completed.load(Ordering::Relaxed),
+			rejected: self.counters.rejected.load(Ordering::Relaxed),
+			queue_wait: Duration::from_nanos(
+				self.counters.queue_wait_nanos.load(Ordering::Relaxed),
+			),
+		}
+	}
+}
+
+impl<WorkerData: Send> Drop for TaskSender<'_, WorkerData> {
+	fn drop(&mut self) {
+		if let Some(segment) = &self.segment {
+			self.pool.inner.unregister_segment(segment);
+		}
+	}
+}
@@ -0,0 +1,90 @@
+use std::sync::atomic::Ordering;
+
+use super::{worker::WorkerState, PoolQueue, ThreadPool};
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Retire the last `count` workers once they're done with their current task and with
+	/// the tasks targeting them, and return their data, e.g. to hand the connections or
+	/// buffers they own back to an external pool. The remaining workers keep their index.
+	///
+	/// Workers brought down by a panic (see [`ThreadPool::dead_workers`]) are removed
+	/// without returning any data. Returns nothing if the pool has been shut down,
+	/// e.g. by [`ThreadPool::abort`].
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the retired workers to finish their current task.
+	///
+	/// # Panics
+	///
+	/// - if `count` is not smaller than the number of workers
+	/// - if one of the retired workers belongs to a worker group
+	///   (see [`ThreadPoolBuilder::worker_group`](crate::ThreadPoolBuilder::worker_group))
+	///   or is dedicated to a service
+	///   (see [`ThreadPoolBuilder::dedicated_worker`](crate::ThreadPoolBuilder::dedicated_worker))
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let mut pool = ThreadPool::new((0..4).map(|i| format!("connection {i}")).collect());
+	/// pool.enqueue(|connection| println!("querying over {connection}"));
+	///
+	/// let released = pool.shrink(2);
+	///
+	/// assert_eq!(released, vec!["connection 2", "connection 3"]);
+	/// assert_eq!(pool.status().workers.len(), 2);
+	/// ```
+	pub fn shrink(&mut self, count: usize) -> Vec<WorkerData> {
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			debug!("pool is shut down, not shrinking");
+			return Vec::new();
+		};
+		let workers = self.workers.len();
+		assert!(count < workers, "at least one worker must remain");
+		let first = workers - count;
+		assert!(
+			(first..workers).all(|worker| !tasks.is_grouped(worker)),
+			"workers in a group can't be retired"
+		);
+		assert!(
+			(first..workers).all(|worker| !self.inner.is_dedicated(worker)),
+			"dedicated workers can't be retired"
+		);
+		tasks.retire_from(first);
+		drop(guard);
+		self.inner.wake_all_workers();
+
+		debug!("retiring workers", from = first, to = workers);
+		let mut workers_data = Vec::with_capacity(count);
+		for (index, worker) in (first..).zip(self.workers.drain(first..)) {
+			// Stranded threads are left running, unless they were stranded after
+			// being joined, in which case the replacement is joined as well.
+			let mut result = match self.inner.take_replacement(index) {
+				Some(replacement) => replacement.join(),
+				None => worker.join(),
+			};
+			if let Some(replacement) = self.inner.take_replacement(index) {
+				result = replacement.join();
+			}
+			workers_data.extend(result.ok());
+		}
+
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		let orphaned = match &mut *guard {
+			PoolQueue::Done => Vec::new(),
+			PoolQueue::Todo(tasks) => tasks.remove_retired(),
+		};
+		let removed = self.inner.workers.write().unwrap().split_off(first);
+		drop(guard);
+		self.inner.report_discarded(&orphaned);
+		let dead = removed
+			.iter()
+			.filter(|worker| matches!(*worker.state.lock().unwrap(), WorkerState::Panicked { .. }))
+			.count();
+		self.inner.dead.fetch_sub(dead, Ordering::Relaxed);
+		workers_data
+	}
+}
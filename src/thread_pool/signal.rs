@@ -0,0 +1,153 @@
+use std::{
+	io, mem,
+	sync::{
+		atomic::{AtomicI32, Ordering},
+		Arc, Mutex, OnceLock, Weak,
+	},
+	thread,
+};
+
+use super::{ThreadPool, ThreadPoolShared};
+
+/// A pool registered with [`ThreadPool::shutdown_on_signal`].
+trait Registered: Send {
+	fn shutdown(&self);
+
+	fn is_dropped(&self) -> bool;
+}
+
+impl<WorkerData: Send> Registered for Weak<ThreadPoolShared<WorkerData>> {
+	fn shutdown(&self) {
+		if let Some(inner) = self.upgrade() {
+			inner.shutdown();
+		}
+	}
+
+	fn is_dropped(&self) -> bool {
+		self.strong_count() == 0
+	}
+}
+
+struct Registry {
+	pools: Vec<Box<dyn Registered>>,
+	/// Whether the signals are handled, rather than left to their default disposition.
+	armed: bool,
+}
+
+/// Write end of the self-pipe used to forward signals to the watcher thread.
+static PIPE_WRITE: AtomicI32 = AtomicI32::new(-1);
+static WATCHER: OnceLock<Result<(), i32>> = OnceLock::new();
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+	pools: Vec::new(),
+	armed: false,
+});
+
+extern "C" fn on_signal(_: libc::c_int) {
+	let fd = PIPE_WRITE.load(Ordering::Relaxed);
+	// write(2) is async-signal-safe, nothing else should be done here.
+	unsafe {
+		libc::write(fd, [1_u8].as_ptr().cast(), 1);
+	}
+}
+
+fn install() -> Result<(), i32> {
+	let mut fds = [0; 2];
+	if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+		return Err(io::Error::last_os_error().raw_os_error().unwrap_or(0));
+	}
+	let [read_fd, write_fd] = fds;
+	PIPE_WRITE.store(write_fd, Ordering::Relaxed);
+
+	thread::Builder::new()
+		.name("signal".to_string())
+		.spawn(move || loop {
+			let mut buf = [0_u8; 1];
+			let n = unsafe { libc::read(read_fd, buf.as_mut_ptr().cast(), 1) };
+			if n < 0 && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+				continue;
+			}
+			if n <= 0 {
+				break;
+			}
+			let mut registry = REGISTRY.lock().unwrap();
+			if !registry.armed {
+				continue;
+			}
+			debug!("signal received, shutting down pools...");
+			// A second signal terminates the process, e.g. if a task takes too long to complete.
+			let _ = set_disposition(libc::SIG_DFL);
+			registry.armed = false;
+			let pools = mem::take(&mut registry.pools);
+			drop(registry);
+			for pool in pools {
+				pool.shutdown();
+			}
+		})
+		.map_err(|err| err.raw_os_error().unwrap_or(0))?;
+	Ok(())
+}
+
+/// Set the disposition of SIGINT and SIGTERM, either to [`on_signal`] or to a standard one.
+fn set_disposition(handler: libc::sighandler_t) -> Result<(), i32> {
+	for signal in [libc::SIGINT, libc::SIGTERM] {
+		unsafe {
+			let mut action: libc::sigaction = mem::zeroed();
+			action.sa_sigaction = handler;
+			action.sa_flags = libc::SA_RESTART;
+			libc::sigemptyset(&raw mut action.sa_mask);
+			if libc::sigaction(signal, &raw const action, std::ptr::null_mut()) != 0 {
+				return Err(io::Error::last_os_error().raw_os_error().unwrap_or(0));
+			}
+		}
+	}
+	Ok(())
+}
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Shut down the pool when the process receives SIGINT or SIGTERM.
+	///
+	/// On signal, the pool stops accepting tasks and discards the pending ones, while in-flight
+	/// tasks are left to complete: unlike [`ThreadPool::abort`], the
+	/// [`AbortSignal`](crate::AbortSignal) isn't raised, so cooperative tasks don't bail out.
+	/// Any thread blocked in [`ThreadPool::join`] (or in [`ThreadPool::enqueue`]) is released
+	/// as soon as the workers are done.
+	///
+	/// Calling this method replaces the default signal disposition for SIGINT and SIGTERM,
+	/// meaning the process will no longer be terminated by these signals. The default
+	/// disposition is restored as soon as one of them is received, before shutting down the
+	/// registered pools, so that a second signal terminates the process as usual. Calling
+	/// this method again afterwards handles the signals again.
+	///
+	/// # Errors
+	/// - if the signal handlers or the watcher thread couldn't be installed.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 4]);
+	/// pool.shutdown_on_signal().unwrap();
+	///
+	/// for _ in 0..16 {
+	/// 	pool.enqueue(|_| {});
+	/// }
+	/// pool.join();
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	pub fn shutdown_on_signal(&self) -> io::Result<()> {
+		WATCHER
+			.get_or_init(install)
+			.map_err(io::Error::from_raw_os_error)?;
+
+		let mut registry = REGISTRY.lock().unwrap();
+		if !registry.armed {
+			let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+			set_disposition(handler).map_err(io::Error::from_raw_os_error)?;
+			registry.armed = true;
+		}
+		registry.pools.retain(|pool| !pool.is_dropped());
+		registry.pools.push(Box::new(Arc::downgrade(&self.inner)));
+		Ok(())
+	}
+}
@@ -0,0 +1,211 @@
+use std::{
+	convert::Infallible,
+	fmt,
+	pin::Pin,
+	sync::atomic::Ordering,
+	task::{Context, Poll, Waker},
+};
+
+use futures_sink::Sink;
+
+use super::{PoolQueue, Task, ThreadPool, ThreadPoolShared};
+
+/// Feeds the tasks of an async pipeline to a pool, as returned by [`ThreadPool::sink`].
+#[must_use = "sinks do nothing unless tasks are sent to them"]
+pub struct PoolSink<'pool, WorkerData: Send> {
+	pool: &'pool ThreadPool<WorkerData>,
+	/// The task accepted by [`Sink::start_send`] that didn't fit in the queue yet.
+	pending: Option<Task<WorkerData>>,
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Return a [`Sink`] feeding the pool, so that async pipelines can enqueue tasks, e.g.
+	/// with `stream.forward(pool.sink())`. Instead of blocking the executor when the queue
+	/// is full like [`ThreadPool::enqueue`], the sink stays pending until a worker makes
+	/// room, which applies backpressure to the stream upstream.
+	///
+	/// The sink holds at most one task that didn't fit in the queue yet, which is enqueued
+	/// by the next call to `poll_ready`, `poll_flush` or `poll_close`. Tasks go through the
+	/// [admission predicate](crate::ThreadPoolBuilder::admission) and the
+	/// [`DeadWorkerPolicy`] like the enqueued ones, and are discarded once the pool is shut
+	/// down, so the sink never fails. Unlike [`ThreadPool::enqueue`], the
+	/// [caller runs](crate::ThreadPoolBuilder::caller_runs) policy doesn't apply, as running
+	/// tasks on the caller would block the executor.
+	///
+	/// Only available with the `async` feature. The sink doesn't depend on any runtime.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{
+	/// 	future::{poll_fn, Future},
+	/// 	pin::{pin, Pin},
+	/// 	sync::{
+	/// 		atomic::{AtomicUsize, Ordering},
+	/// 		Arc,
+	/// 	},
+	/// 	task::{Context, Poll, Wake},
+	/// 	thread::{self, Thread},
+	/// };
+	/// use futures_sink::Sink;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// // A minimal executor, use your runtime of choice instead.
+	/// struct Unpark(Thread);
+	///
+	/// impl Wake for Unpark {
+	/// 	fn wake(self: Arc<Self>) {
+	/// 		self.0.unpark();
+	/// 	}
+	/// }
+	///
+	/// fn block_on<F: Future>(future: F) -> F::Output {
+	/// 	let mut future = pin!(future);
+	/// 	let waker = Arc::new(Unpark(thread::current())).into();
+	/// 	let mut cx = Context::from_waker(&waker);
+	/// 	loop {
+	/// 		if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+	/// 			return output;
+	/// 		}
+	/// 		thread::park();
+	/// 	}
+	/// }
+	///
+	/// // What `stream.forward(sink)` does, for an iterator instead of a stream.
+	/// async fn forward<T, S: Sink<T> + Unpin>(
+	/// 	items: impl IntoIterator<Item = T>,
+	/// 	mut sink: S,
+	/// ) -> Result<(), S::Error> {
+	/// 	for item in items {
+	/// 		poll_fn(|cx| Pin::new(&mut sink).poll_ready(cx)).await?;
+	/// 		Pin::new(&mut sink).start_send(item)?;
+	/// 	}
+	/// 	poll_fn(|cx| Pin::new(&mut sink).poll_close(cx)).await
+	/// }
+	///
+	/// let pool = ThreadPool::new_with_queue_size(vec![(); 2], 2);
+	/// let done = Arc::new(AtomicUsize::new(0));
+	///
+	/// let tasks = (0..16).map(|_| {
+	/// 	let done = done.clone();
+	/// 	move |_: &mut ()| {
+	/// 		done.fetch_add(1, Ordering::Relaxed);
+	/// 	}
+	/// });
+	/// block_on(forward(tasks, pool.sink())).unwrap();
+	///
+	/// pool.join();
+	/// assert_eq!(done.load(Ordering::Relaxed), 16);
+	/// ```
+	pub fn sink(&self) -> PoolSink<'_, WorkerData> {
+		PoolSink {
+			pool: self,
+			pending: None,
+		}
+	}
+}
+
+impl<WorkerData: Send> PoolSink<'_, WorkerData> {
+	/// Enqueue the pending task, if any, or register the waker if it still doesn't fit.
+	fn poll_enqueue(&mut self, cx: &Context<'_>) -> Poll<()> {
+		let Some(task) = self.pending.take() else {
+			return Poll::Ready(());
+		};
+		self.pending = self.pool.inner.try_enqueue_or_wake(task, cx.waker());
+		if self.pending.is_some() {
+			Poll::Pending
+		} else {
+			Poll::Ready(())
+		}
+	}
+}
+
+impl<WorkerData, F> Sink<F> for PoolSink<'_, WorkerData>
+where
+	WorkerData: Send,
+	F: FnOnce(&mut WorkerData) + Send + 'static,
+{
+	type Error = Infallible;
+
+	fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+		self.get_mut().poll_enqueue(cx).map(Ok)
+	}
+
+	/// # Panics
+	/// - if a task is still pending, i.e. `poll_ready` wasn't called or didn't return `Ready`
+	#[track_caller]
+	fn start_send(self: Pin<&mut Self>, item: F) -> Result<(), Infallible> {
+		let this = self.get_mut();
+		assert!(
+			this.pending.is_none(),
+			"start_send called before poll_ready returned Ready"
+		);
+		let inner = &this.pool.inner;
+		let task = Task::new(item);
+		if inner.admit(&task) {
+			this.pending = Some(inner.propagators.wrap(task));
+		}
+		Ok(())
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+		self.get_mut().poll_enqueue(cx).map(Ok)
+	}
+
+	fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+		self.get_mut().poll_enqueue(cx).map(Ok)
+	}
+}
+
+impl<WorkerData: Send> fmt::Debug for PoolSink<'_, WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PoolSink")
+			.field("pending", &self.pending.is_some())
+			.finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Enqueue an admitted task if there's room for it, or else give it back and have `waker`
+	/// woken once there may be. Tasks are discarded once the pool is shut down.
+	#[track_caller]
+	fn try_enqueue_or_wake(
+		&self,
+		task: Task<WorkerData>,
+		waker: &Waker,
+	) -> Option<Task<WorkerData>> {
+		if let Some(inline) = self.inline.get() {
+			inline.run(self, [task]);
+			return None;
+		}
+		if !self.accepts_despite_dead_workers() {
+			self.report_discarded([&task]);
+			return None;
+		}
+		let mut guard = self.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(queue) = &mut *guard else {
+			drop(guard);
+			debug!("pool is shut down, discarding task");
+			self.counters.discarded.fetch_add(1, Ordering::Relaxed);
+			return None;
+		};
+		// Blocked enqueuers are served first, as a sink only arrives when it's polled.
+		if queue.has_waiters(task.class) || !queue.has_room(task.class, task.size) {
+			// Registered under the queue lock, so no room can be made in between.
+			self.wakers.register(waker);
+			return Some(task);
+		}
+		if let Some(worker) = self.claim_parked(queue, task.class) {
+			trace!("handing task to a parked worker", worker = worker);
+			queue.push_to_worker(worker, task);
+			self.worker(worker).unpark();
+		} else {
+			queue.push_back(task);
+			self.notify_worker();
+		}
+		drop(guard);
+		self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+		trace!("task added");
+		None
+	}
+}
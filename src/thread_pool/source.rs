@@ -0,0 +1,137 @@
+use std::{
+	sync::{
+		atomic::Ordering,
+		mpsc::{Receiver, RecvTimeoutError},
+		Arc, Mutex,
+	},
+	time::Duration,
+};
+
+use super::{
+	spawner::{PoolThread, Spawner},
+	BoxedTask, PoolQueue, Task, ThreadPool, ThreadPoolShared,
+};
+
+/// How often the feeder checks whether the pool was shut down while the channel is idle.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Construct a thread pool that runs the tasks received from an existing channel,
+	/// for when the queue is owned by another component. See
+	/// [`ThreadPoolBuilder::task_source`](crate::ThreadPoolBuilder::task_source).
+	///
+	/// # Panics
+	/// - if Vec is empty
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	/// use lending_thread_pool::{BoxedTask, ThreadPool};
+	///
+	/// let (sender, receiver) = mpsc::channel::<BoxedTask<String>>();
+	/// let pool = ThreadPool::from_receiver(vec!["Hello".to_string(); 4], receiver);
+	///
+	/// for i in 0..16 {
+	/// 	sender.send(Box::new(move |greeting| println!("{greeting} {i}"))).unwrap();
+	/// }
+	/// drop(sender);
+	/// pool.join();
+	/// ```
+	#[must_use]
+	pub fn from_receiver(
+		workers_data: Vec<WorkerData>,
+		receiver: Receiver<BoxedTask<WorkerData>>,
+	) -> Self {
+		Self::builder(workers_data).task_source(receiver).build()
+	}
+}
+
+/// Moves the tasks from the channel passed to
+/// [`ThreadPoolBuilder::task_source`](crate::ThreadPoolBuilder::task_source) to the queue.
+pub(crate) enum Feeder<WorkerData> {
+	Thread(PoolThread<()>),
+	/// The thread couldn't be spawned, the joining thread moves the tasks over instead.
+	Joiner(Mutex<Receiver<BoxedTask<WorkerData>>>),
+}
+
+impl<WorkerData: Send> Feeder<WorkerData> {
+	/// Wait for the task source to disconnect, or for the pool to be shut down.
+	pub(crate) fn join(self, inner: &ThreadPoolShared<WorkerData>) {
+		match self {
+			Self::Thread(feeder) => {
+				debug!("waiting for the task source to disconnect...");
+				let _ = feeder.join();
+			}
+			Self::Joiner(receiver) => {
+				debug!("moving the tasks over until the task source disconnects...");
+				feed(inner, &receiver.into_inner().unwrap());
+			}
+		}
+	}
+}
+
+/// Spawn a thread moving tasks from the channel to the queue of the pool, until either
+/// all senders are dropped or the pool is shut down.
+///
+/// # Panics
+/// - if the thread can't be spawned and the
+///   [`SpawnFailurePolicy`](crate::SpawnFailurePolicy) is
+///   [`Panic`](crate::SpawnFailurePolicy::Panic)
+#[track_caller]
+pub(crate) fn spawn_feeder<'a, WorkerData: Send + 'a>(
+	spawner: &impl Spawner<'a>,
+	inner: &Arc<ThreadPoolShared<WorkerData>>,
+	receiver: Receiver<BoxedTask<WorkerData>>,
+) -> Feeder<WorkerData> {
+	inner.feeding.store(true, Ordering::SeqCst);
+	// Lets the receiver be recovered if the thread can't be spawned.
+	let slot = Arc::new(Mutex::new(Some(receiver)));
+	let thread = spawner.spawn("feeder".to_string(), {
+		let inner = inner.clone();
+		let slot = slot.clone();
+		move || {
+			let receiver = slot.lock().unwrap().take().unwrap();
+			feed(&inner, &receiver);
+		}
+	});
+	match thread {
+		Ok(thread) => Feeder::Thread(thread),
+		Err(error) => {
+			inner.feeding.store(false, Ordering::SeqCst);
+			inner.helper_spawn_failed("feeder", "moving the tasks over when joined", &error);
+			Feeder::Joiner(Mutex::new(slot.lock().unwrap().take().unwrap()))
+		}
+	}
+}
+
+/// Move tasks from the channel to the queue of the pool, until either all senders are
+/// dropped or the pool is shut down.
+fn feed<WorkerData: Send>(
+	inner: &ThreadPoolShared<WorkerData>,
+	receiver: &Receiver<BoxedTask<WorkerData>>,
+) {
+	loop {
+		match receiver.recv_timeout(POLL_INTERVAL) {
+			Ok(task) => {
+				if !inner.enqueue_task(Task::from_boxed(task)) {
+					break;
+				}
+			}
+			Err(RecvTimeoutError::Timeout) => {
+				if matches!(*inner.pending_tasks.lock().unwrap(), PoolQueue::Done)
+					&& !inner.is_inline()
+				{
+					break;
+				}
+			}
+			Err(RecvTimeoutError::Disconnected) => {
+				debug!("task source disconnected");
+				break;
+			}
+		}
+	}
+	inner.feeding.store(false, Ordering::SeqCst);
+	// Whoever is waiting for the pool to drain has to take another look.
+	inner.notify_room();
+}
@@ -0,0 +1,144 @@
+use std::{
+	fmt, io,
+	panic::{self, AssertUnwindSafe},
+	sync::{
+		mpsc::{self, Receiver},
+		Arc, Mutex, PoisonError,
+	},
+	thread::{self, JoinHandle},
+};
+
+use super::ThreadPoolShared;
+
+/// Creates the threads of a pool, for platforms where threads aren't spawned through `std`,
+/// e.g. RTOS bindings or custom runtimes, or to configure them, e.g. with a larger stack.
+/// Registered with [`ThreadPoolBuilder::thread_spawner`](crate::ThreadPoolBuilder::thread_spawner).
+///
+/// Any `Fn(String, Box<dyn FnOnce() + Send>) -> io::Result<()>` closure is a spawner.
+///
+/// # Examples
+///
+/// ```
+/// use std::{io, thread};
+/// use lending_thread_pool::ThreadPool;
+///
+/// let pool = ThreadPool::builder(vec![(); 2])
+/// 	.thread_spawner(|name: String, f: Box<dyn FnOnce() + Send>| -> io::Result<()> {
+/// 		thread::Builder::new()
+/// 			.name(name)
+/// 			.stack_size(8 * 1024 * 1024)
+/// 			.spawn(f)
+/// 			.map(drop)
+/// 	})
+/// 	.build();
+///
+/// pool.enqueue(|()| println!("Hello from a thread with a large stack"));
+/// ```
+pub trait ThreadSpawner: Send + Sync {
+	/// Start a thread running `f`. The thread is never joined: the pool knows it's done
+	/// once `f` returns. `name` identifies the thread, e.g. `w(0)` for the first worker.
+	///
+	/// # Errors
+	/// - if the thread couldn't be created, in which case building the pool panics, unless
+	///   the pool [degrades](crate::SpawnFailurePolicy::Degrade).
+	fn spawn(&self, name: String, f: Box<dyn FnOnce() + Send>) -> io::Result<()>;
+}
+
+impl<F: Fn(String, Box<dyn FnOnce() + Send>) -> io::Result<()> + Send + Sync> ThreadSpawner for F {
+	fn spawn(&self, name: String, f: Box<dyn FnOnce() + Send>) -> io::Result<()> {
+		self(name, f)
+	}
+}
+
+impl fmt::Debug for dyn ThreadSpawner {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ThreadSpawner").finish_non_exhaustive()
+	}
+}
+
+/// A handle to a thread of the pool, either a worker or the feeder.
+pub(crate) enum PoolThread<T> {
+	Detached(JoinHandle<T>),
+	/// Threads that can't be joined, e.g. scoped ones whose [`ScopedJoinHandle`](thread::ScopedJoinHandle)
+	/// would borrow the scope, report back through a channel.
+	/// The receiver is behind a mutex only to keep the pool `Sync`.
+	Reporting(Mutex<Receiver<thread::Result<T>>>),
+}
+
+impl<T> PoolThread<T> {
+	/// Wrap `f` so that it reports its outcome to the returned handle, panics included.
+	pub(crate) fn reporting<'a>(
+		f: impl FnOnce() -> T + Send + 'a,
+	) -> (impl FnOnce() + Send + 'a, Self)
+	where
+		T: Send + 'a,
+	{
+		let (sender, receiver) = mpsc::sync_channel(1);
+		let f = move || {
+			let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+		};
+		(f, Self::Reporting(Mutex::new(receiver)))
+	}
+
+	pub(crate) fn join(self) -> thread::Result<T> {
+		match self {
+			Self::Detached(handle) => handle.join(),
+			Self::Reporting(receiver) => receiver
+				.into_inner()
+				.unwrap_or_else(PoisonError::into_inner)
+				.recv()
+				.expect("thread to report back before exiting"),
+		}
+	}
+}
+
+/// Spawns the threads of a pool, whose closures live for `'a`.
+pub(crate) trait Spawner<'a> {
+	fn spawn<T: Send + 'a>(
+		&self,
+		name: String,
+		f: impl FnOnce() -> T + Send + 'a,
+	) -> io::Result<PoolThread<T>>;
+}
+
+/// Spawns regular threads, see [`ThreadPoolBuilder::build`](crate::ThreadPoolBuilder::build).
+pub(crate) struct Unscoped;
+
+impl Spawner<'static> for Unscoped {
+	fn spawn<T: Send + 'static>(
+		&self,
+		name: String,
+		f: impl FnOnce() -> T + Send + 'static,
+	) -> io::Result<PoolThread<T>> {
+		thread::Builder::new()
+			.name(name)
+			.spawn(f)
+			.map(PoolThread::Detached)
+	}
+}
+
+impl Spawner<'static> for Arc<dyn ThreadSpawner> {
+	fn spawn<T: Send + 'static>(
+		&self,
+		name: String,
+		f: impl FnOnce() -> T + Send + 'static,
+	) -> io::Result<PoolThread<T>> {
+		let (f, thread) = PoolThread::reporting(f);
+		(**self).spawn(name, Box::new(f))?;
+		Ok(thread)
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Spawn a thread once the pool is built, through its [`ThreadSpawner`] if it has one.
+	pub(crate) fn spawn_thread<T: Send + 'static>(
+		&self,
+		name: String,
+		f: impl FnOnce() -> T + Send + 'static,
+	) -> io::Result<PoolThread<T>> {
+		match self.thread_spawner.get() {
+			Some(spawner) => spawner.spawn(name, f),
+			None => Unscoped.spawn(name, f),
+		}
+	}
+}
@@ -0,0 +1,43 @@
+use super::ThreadPool;
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Stop the pool and redistribute its workers, along with their data, into independent pools
+	/// of the given sizes. Workers are assigned in order, e.g. splitting 6 workers with `[2, 4]`
+	/// moves the data of workers 0 and 1 into the first pool, and the rest into the second one.
+	///
+	/// Like [`ThreadPool::join`], this waits for the pending tasks to complete first.
+	/// The new pools are constructed with [`ThreadPool::new`], i.e. with default settings.
+	///
+	/// # Panics
+	/// - if `counts` doesn't add up to the number of workers, or contains a 0
+	/// - if any worker panicked, as its data is lost
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new((0..6).collect::<Vec<_>>());
+	///
+	/// let mut pools = pool.split(&[2, 4]).into_iter();
+	/// let (ingest, process) = (pools.next().unwrap(), pools.next().unwrap());
+	///
+	/// ingest.enqueue(|&mut i| assert!(i < 2));
+	/// process.enqueue(|&mut i| assert!(i >= 2));
+	/// ```
+	#[must_use]
+	pub fn split(self, counts: &[usize]) -> Vec<ThreadPool<WorkerData>> {
+		assert_eq!(
+			counts.iter().sum::<usize>(),
+			self.inner.workers().len(),
+			"counts must add up to the number of workers"
+		);
+		assert!(!counts.contains(&0), "counts must be greater than 0");
+
+		let mut workers_data = self.stop().into_workers_data().into_iter();
+		counts
+			.iter()
+			.map(|&count| ThreadPool::new(workers_data.by_ref().take(count).collect()))
+			.collect()
+	}
+}
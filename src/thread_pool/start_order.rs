@@ -0,0 +1,53 @@
+use std::sync::{
+	atomic::{AtomicU64, Ordering},
+	Condvar, Mutex, PoisonError,
+};
+
+/// Hands tasks to their workers one at a time, in the order they were dequeued,
+/// see [`ThreadPoolBuilder::strict_start_order`](crate::ThreadPoolBuilder::strict_start_order).
+#[derive(Debug, Default)]
+pub(crate) struct StartOrder {
+	/// Tickets handed out so far. Only incremented while holding the queue lock,
+	/// so that tickets follow the dequeue order.
+	dispatched: AtomicU64,
+	/// Ticket whose task is the next one allowed to start.
+	now_starting: Mutex<u64>,
+	condvar: Condvar,
+}
+
+impl StartOrder {
+	/// Take a ticket for a task that was just dequeued. Must be called while holding
+	/// the queue lock.
+	pub(crate) fn take_ticket(&self) -> u64 {
+		self.dispatched.fetch_add(1, Ordering::Relaxed)
+	}
+
+	/// Block until all the tasks dequeued before the one holding `ticket` have started.
+	/// The task is considered started, letting the next one through, when the returned
+	/// turn is dropped.
+	pub(crate) fn wait_turn(&self, ticket: u64) -> StartTurn<'_> {
+		let mut now_starting = self.now_starting.lock().unwrap();
+		while *now_starting != ticket {
+			now_starting = self.condvar.wait(now_starting).unwrap();
+		}
+		StartTurn { order: self }
+	}
+}
+
+pub(crate) struct StartTurn<'a> {
+	order: &'a StartOrder,
+}
+
+impl Drop for StartTurn<'_> {
+	fn drop(&mut self) {
+		// Also called while unwinding, so that a panic doesn't stall the following tasks.
+		let mut now_starting = self
+			.order
+			.now_starting
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner);
+		*now_starting += 1;
+		drop(now_starting);
+		self.order.condvar.notify_all();
+	}
+}
@@ -0,0 +1,316 @@
+use std::{
+	panic::Location,
+	sync::atomic::{AtomicU64, Ordering},
+	time::{Duration, Instant},
+};
+
+use super::{queue::DEFAULT_CLASS, PoolQueue, ThreadPool, WorkerState};
+
+/// A point-in-time snapshot of a pool, as returned by [`ThreadPool::status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolStatus {
+	/// The state of each worker, indexed like the `WorkerData` Vec the pool was constructed with.
+	pub workers: Vec<WorkerStatus>,
+	/// The number of tasks waiting in the queue.
+	pub queue_depth: usize,
+	/// The maximum number of tasks that can wait in the queue.
+	pub max_pending_tasks: usize,
+	/// The approximate memory held by the tasks waiting in the queue, see
+	/// [`ThreadPool::enqueue_sized`].
+	pub queue_bytes: usize,
+	/// Whether the pool has stopped accepting tasks.
+	pub shut_down: bool,
+	/// The number of tasks accepted by the pool since its construction, or since the counters
+	/// were last reset, see [`ThreadPool::status_and_reset`]. The same goes for the counters below.
+	pub tasks_enqueued: u64,
+	/// The number of tasks that ran to completion.
+	pub tasks_completed: u64,
+	/// The number of tasks that were dropped without being run, e.g. after a shutdown.
+	pub tasks_discarded: u64,
+	/// The number of tasks that completed past their timeout, see
+	/// [`ThreadPool::enqueue_with_timeout`].
+	pub tasks_timed_out: u64,
+	/// Time elapsed since the pool was constructed.
+	pub uptime: Duration,
+	/// The last tasks run by each worker, oldest first, indexed like `workers`. Empty unless
+	/// enabled with [`ThreadPoolBuilder::task_history`](crate::ThreadPoolBuilder::task_history).
+	pub recent_tasks: Vec<Vec<TaskRecord>>,
+	/// The state of the callers, which run one task at a time, if the pool lends them data,
+	/// see [`ThreadPoolBuilder::caller_runs`](crate::ThreadPoolBuilder::caller_runs).
+	pub caller: Option<WorkerStatus>,
+	/// The last tasks run by the callers, like `recent_tasks`.
+	pub caller_recent_tasks: Vec<TaskRecord>,
+}
+
+/// A task run by a worker, see [`PoolStatus::recent_tasks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskRecord {
+	/// The name of the task, if it was enqueued with one.
+	pub name: Option<String>,
+	/// Where the task was enqueued from.
+	pub location: &'static Location<'static>,
+	/// How long the task ran for.
+	pub elapsed: Duration,
+	/// How the task ended.
+	pub outcome: TaskOutcome,
+}
+
+/// How a task ended, see [`TaskRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TaskOutcome {
+	/// The task returned.
+	Completed,
+	/// The task panicked.
+	Panicked,
+	/// The worker was replaced while still running the task, see
+	/// [`ThreadPoolBuilder::replace_hung_workers`](crate::ThreadPoolBuilder::replace_hung_workers).
+	Abandoned,
+}
+
+/// The state of a single worker, see [`PoolStatus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkerStatus {
+	/// Waiting for a task.
+	Idle,
+	/// Running a task.
+	Running {
+		/// The name of the task, if it was enqueued with one.
+		name: Option<String>,
+		/// Where the task was enqueued from.
+		location: &'static Location<'static>,
+		/// How long the task has been running for.
+		elapsed: Duration,
+	},
+	/// The worker thread has terminated after the pool was shut down.
+	Exited,
+	/// The worker thread has terminated because a task panicked.
+	Panicked,
+}
+
+/// A task waiting in the queue, as returned by [`ThreadPool::pending`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskInfo {
+	/// The position of the task in the overall enqueue order.
+	pub id: u64,
+	/// The name of the task, if it was enqueued with one.
+	pub name: Option<String>,
+	/// The [`TaskClass`](crate::TaskClass) or worker group the task was enqueued in, if any.
+	pub class: Option<String>,
+	/// Where the task was enqueued from.
+	pub location: &'static Location<'static>,
+	/// When the task entered the queue.
+	pub enqueued_at: Instant,
+}
+
+impl WorkerStatus {
+	/// The status of a worker in the given state, `now` being read from the pool clock.
+	fn new(state: &WorkerState, now: Instant) -> Self {
+		match state {
+			WorkerState::Idle => Self::Idle,
+			WorkerState::Running {
+				name,
+				location,
+				since,
+			} => Self::Running {
+				name: name.as_deref().map(str::to_string),
+				location,
+				elapsed: now.saturating_duration_since(*since),
+			},
+			WorkerState::Exited => Self::Exited,
+			WorkerState::Panicked { .. } => Self::Panicked,
+		}
+	}
+}
+
+/// Read a counter, resetting it if asked to.
+fn read(counter: &AtomicU64, reset: bool) -> u64 {
+	if reset {
+		counter.swap(0, Ordering::Relaxed)
+	} else {
+		counter.load(Ordering::Relaxed)
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Take a snapshot of the pool state: what each worker is doing, queue occupancy,
+	/// task counters and uptime.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 4]);
+	/// pool.enqueue(|()| {});
+	///
+	/// let status = pool.status();
+	/// assert_eq!(status.workers.len(), 4);
+	/// assert_eq!(status.tasks_enqueued, 1);
+	/// ```
+	#[must_use]
+	pub fn status(&self) -> PoolStatus {
+		self.snapshot(false)
+	}
+
+	/// Like [`ThreadPool::status`], but also reset the task counters, atomically for each
+	/// of them, so that the next snapshot only counts the tasks since this one, e.g. for
+	/// per-interval reporting. The counters of [`ThreadPool::join_report`] are reset as well.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	/// pool.enqueue(|()| {});
+	/// pool.flush();
+	///
+	/// assert_eq!(pool.status_and_reset().tasks_completed, 1);
+	/// assert_eq!(pool.status().tasks_completed, 0);
+	/// ```
+	#[must_use]
+	pub fn status_and_reset(&self) -> PoolStatus {
+		self.snapshot(true)
+	}
+
+	/// Reset the task counters, see [`ThreadPool::status_and_reset`].
+	pub fn reset_stats(&self) {
+		let _ = self.snapshot(true);
+	}
+
+	fn snapshot(&self, reset: bool) -> PoolStatus {
+		let inner = &*self.inner;
+		let (queue_depth, queue_bytes, shut_down) = match &*inner.pending_tasks.lock().unwrap() {
+			PoolQueue::Done => (0, 0, true),
+			PoolQueue::Todo(tasks) => (tasks.len(), tasks.bytes(), false),
+		};
+		let workers = inner.workers();
+		if reset {
+			for worker in workers.iter() {
+				worker.completed.store(0, Ordering::Relaxed);
+			}
+		}
+		let now = inner.clock.now();
+		PoolStatus {
+			workers: workers
+				.iter()
+				.map(|worker| WorkerStatus::new(&worker.state.lock().unwrap(), now))
+				.collect(),
+			recent_tasks: if inner.task_history == 0 {
+				Vec::new()
+			} else {
+				workers.iter().map(|worker| worker.recent_tasks()).collect()
+			},
+			caller: inner.caller_runs.as_ref().map(|caller_runs| {
+				WorkerStatus::new(&caller_runs.worker.state.lock().unwrap(), now)
+			}),
+			caller_recent_tasks: inner
+				.caller_runs
+				.as_ref()
+				.map(|caller_runs| caller_runs.worker.recent_tasks())
+				.unwrap_or_default(),
+			queue_depth,
+			max_pending_tasks: inner.max_pending_tasks,
+			queue_bytes,
+			shut_down,
+			tasks_enqueued: read(&inner.counters.enqueued, reset),
+			tasks_completed: read(&inner.counters.completed, reset),
+			tasks_discarded: read(&inner.counters.discarded, reset),
+			tasks_timed_out: read(&inner.counters.timed_out, reset),
+			uptime: inner.uptime(),
+		}
+	}
+
+	/// List the tasks waiting in the queue, oldest first.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::sync::mpsc;
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new_with_queue_size(vec![(); 1], 4);
+	/// let (sender, receiver) = mpsc::channel::<()>();
+	/// pool.enqueue(move |()| {
+	/// 	let _ = receiver.recv();
+	/// });
+	/// pool.enqueue_named("resize thumbnails", |()| {});
+	///
+	/// let pending = pool.pending();
+	/// assert!(pending
+	/// 	.iter()
+	/// 	.any(|task| task.name.as_deref() == Some("resize thumbnails")));
+	/// # drop(sender);
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn pending(&self) -> Vec<TaskInfo> {
+		let PoolQueue::Todo(tasks) = &*self.inner.pending_tasks.lock().unwrap() else {
+			return Vec::new();
+		};
+		let mut pending: Vec<_> = tasks
+			.iter()
+			.map(|task| TaskInfo {
+				id: task.seq,
+				name: task.name.as_deref().map(str::to_string),
+				class: (task.class != DEFAULT_CLASS).then(|| {
+					let mut names = self.inner.classes.iter().chain(&self.inner.groups);
+					names.nth(task.class - 1).unwrap().to_string()
+				}),
+				location: task.location,
+				enqueued_at: task.enqueued_at,
+			})
+			.collect();
+		pending.sort_unstable_by_key(|task| task.id);
+		pending
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Check whether all workers are alive, i.e. none of them was brought down by a panicking task.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 4]);
+	/// assert!(pool.healthy());
+	/// ```
+	#[must_use]
+	pub fn healthy(&self) -> bool {
+		self.dead_workers().is_empty()
+	}
+
+	/// Get the indices of the workers that were brought down by a panicking task. Tasks
+	/// are still processed by the remaining workers, but with reduced parallelism.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{thread::sleep, time::Duration};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new((0..4).collect::<Vec<_>>());
+	/// pool.enqueue(|&mut i| panic!("worker {i} is going down"));
+	/// sleep(Duration::from_millis(100));
+	///
+	/// assert_eq!(pool.dead_workers().len(), 1);
+	/// assert!(!pool.healthy());
+	/// # let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || pool.join()));
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn dead_workers(&self) -> Vec<usize> {
+		self.inner
+			.workers()
+			.iter()
+			.enumerate()
+			.filter(|(_, worker)| {
+				matches!(*worker.state.lock().unwrap(), WorkerState::Panicked { .. })
+			})
+			.map(|(i, _)| i)
+			.collect()
+	}
+}
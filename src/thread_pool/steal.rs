@@ -0,0 +1,75 @@
+use std::{fmt, sync::Arc};
+
+use super::{PoolQueue, Task, TaskHandle, ThreadPoolShared};
+
+/// Called with the handle of a task when a thread starts waiting on it, to run the task
+/// on that thread or to move it up the queue, see [`TaskHandle::wait`].
+#[derive(Clone)]
+pub(crate) struct OnWait(Arc<dyn Fn(u64) + Send + Sync>);
+
+impl OnWait {
+	pub(crate) fn run(&self, handle: u64) {
+		(self.0)(handle);
+	}
+}
+
+impl fmt::Debug for OnWait {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("OnWait").finish_non_exhaustive()
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPoolShared<WorkerData> {
+	/// Let the threads waiting on a [`TaskHandle`] run its task, if the pool lends data
+	/// to the callers, or else boost it, if the pool boosts waited-on tasks, unless the pool
+	/// enforces the start order of its tasks.
+	pub(crate) fn enable_on_wait(self: &Arc<Self>) {
+		if (self.caller_runs.is_none() && !self.boost_waited) || self.start_order.is_some() {
+			return;
+		}
+		let inner = Arc::downgrade(self);
+		let _ = self.on_wait.set(OnWait(Arc::new(move |handle| {
+			if let Some(inner) = inner.upgrade() {
+				if !inner.steal(handle) && inner.boost_waited {
+					inner.boost(handle);
+				}
+			}
+		})));
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Tag `task` with `handle`, letting the threads waiting on the latter run or boost it.
+	pub(super) fn waitable(&self, handle: &TaskHandle, task: Task<WorkerData>) -> Task<WorkerData> {
+		if let Some(on_wait) = self.on_wait.get() {
+			handle.set_on_wait(on_wait.clone());
+		}
+		Task {
+			handle: handle.id(),
+			..task
+		}
+	}
+
+	/// Run the task with the given handle on the calling thread, if it's still queued and
+	/// the data lent to the callers is available.
+	fn steal(&self, handle: u64) -> bool {
+		let Some(worker_data) = self.caller_data() else {
+			return false;
+		};
+		let mut guard = self.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			return false;
+		};
+		let Some(task) = tasks.take_handle(handle) else {
+			return false;
+		};
+		// Still counted by the threads flushing or joining the pool once off the queue.
+		self.start_on_caller(&task);
+		drop(guard);
+		// Blocked enqueuers may fit now.
+		self.notify_room();
+		trace!("running the task on its waiter", task = task.seq);
+		self.run_with_caller_data(task, worker_data);
+		true
+	}
+}
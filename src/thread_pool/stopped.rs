@@ -0,0 +1,69 @@
+use super::{ThreadPool, ThreadPoolBuilder};
+
+/// A pool whose workers have been joined, holding on to their data so that it can be
+/// inspected and reused, as returned by [`ThreadPool::stop`].
+#[derive(Debug)]
+pub struct StoppedPool<WorkerData: Send> {
+	workers_data: Vec<WorkerData>,
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Like [`ThreadPool::join`], but hands back the data of the workers so that they can be
+	/// re-spawned later with [`StoppedPool::resume`], e.g. between the phases of a program,
+	/// without reconstructing expensive `WorkerData`.
+	///
+	/// # Panics
+	/// - if any worker panicked, as its data is lost
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![0; 4]);
+	/// for _ in 0..16 {
+	/// 	pool.enqueue(|count| *count += 1);
+	/// }
+	///
+	/// let mut stopped = pool.stop();
+	/// assert_eq!(stopped.workers_data().iter().sum::<i32>(), 16);
+	///
+	/// let pool = stopped.resume();
+	/// pool.enqueue(|count| *count += 1);
+	/// ```
+	#[must_use]
+	pub fn stop(mut self) -> StoppedPool<WorkerData> {
+		StoppedPool {
+			workers_data: self
+				.stop_and_join()
+				.into_iter()
+				.map(|(_, result)| result.unwrap())
+				.collect(),
+		}
+	}
+}
+
+impl<WorkerData: Send + 'static> StoppedPool<WorkerData> {
+	/// The data of each worker, indexed like the `WorkerData` Vec the pool was constructed with.
+	pub fn workers_data(&mut self) -> &mut [WorkerData] {
+		&mut self.workers_data
+	}
+
+	/// Re-spawn the workers with their data, using the default settings of [`ThreadPool::new`].
+	#[must_use]
+	pub fn resume(self) -> ThreadPool<WorkerData> {
+		ThreadPool::new(self.workers_data)
+	}
+
+	/// Turn back into a [`ThreadPoolBuilder`] holding the data of the workers,
+	/// to configure the resumed pool.
+	pub fn into_builder(self) -> ThreadPoolBuilder<WorkerData> {
+		ThreadPool::builder(self.workers_data)
+	}
+
+	/// Give up on resuming the pool, taking the data of the workers.
+	#[must_use]
+	pub fn into_workers_data(self) -> Vec<WorkerData> {
+		self.workers_data
+	}
+}
@@ -0,0 +1,85 @@
+use std::{
+	ops::ControlFlow,
+	panic::Location,
+	sync::{Arc, Weak},
+};
+
+use super::{Task, ThreadPool, ThreadPoolShared};
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Enqueue a task that is called repeatedly until it returns [`ControlFlow::Break`],
+	/// e.g. to pull items from a stream one at a time without holding a worker for the
+	/// whole stream.
+	///
+	/// After each call returning [`ControlFlow::Continue`], the task is put back at the end
+	/// of the queue, regardless of its capacity, so that the tasks queued in the meantime
+	/// run before the next call, which may happen on another worker. Each call is counted
+	/// as a task of its own, e.g. by [`ThreadPool::status`].
+	///
+	/// If a call panics, the panic is handled as it would be for any other task, and the
+	/// task isn't called again. Calls scheduled after the pool has been shut down or joined
+	/// are discarded.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{ops::ControlFlow, sync::mpsc};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	///
+	/// let (sender, receiver) = mpsc::channel();
+	/// let mut lines = vec!["first", "second", "third"].into_iter();
+	/// pool.enqueue_stream(move |()| match lines.next() {
+	/// 	Some(line) => {
+	/// 		sender.send(line.len()).unwrap();
+	/// 		ControlFlow::Continue(())
+	/// 	}
+	/// 	None => ControlFlow::Break(()),
+	/// });
+	/// pool.join();
+	///
+	/// assert_eq!(receiver.iter().sum::<usize>(), 16);
+	/// ```
+	#[track_caller]
+	pub fn enqueue_stream<Task>(&self, task: Task)
+	where
+		Task: FnMut(&mut WorkerData) -> ControlFlow<()> + Send + 'static,
+	{
+		self.enqueue_task(streaming(
+			Arc::downgrade(&self.inner),
+			Location::caller(),
+			task,
+		));
+	}
+}
+
+fn streaming<WorkerData, F>(
+	inner: Weak<ThreadPoolShared<WorkerData>>,
+	location: &'static Location<'static>,
+	mut task: F,
+) -> Task<WorkerData>
+where
+	WorkerData: Send + 'static,
+	F: FnMut(&mut WorkerData) -> ControlFlow<()> + Send + 'static,
+{
+	let run = move |worker_data: &mut WorkerData| {
+		if task(worker_data).is_break() {
+			return;
+		}
+		let Some(inner) = inner.upgrade() else {
+			return;
+		};
+		trace!("streaming task continues", location = location);
+		inner.requeue(streaming(Arc::downgrade(&inner), location, task));
+	};
+	Task {
+		location,
+		..Task::new(run)
+	}
+}
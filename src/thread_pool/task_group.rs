@@ -0,0 +1,169 @@
+use std::{
+	fmt,
+	sync::{Arc, Condvar, Mutex},
+};
+
+use super::ThreadPool;
+
+/// A set of tasks whose lifetime is bound to a scope: [`TaskGroup::wait`] blocks until all
+/// of them have completed, and dropping the group cancels the ones that haven't started yet,
+/// waiting for the running ones. Once the group is gone, none of its tasks is running or
+/// will run. This can be constructed using the [`ThreadPool::task_group`] method.
+///
+/// Cancelled tasks are dropped without running when a worker dequeues them.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{
+/// 	atomic::{AtomicUsize, Ordering},
+/// 	Arc,
+/// };
+/// use lending_thread_pool::ThreadPool;
+///
+/// let pool = ThreadPool::new(vec![(); 4]);
+/// let done = Arc::new(AtomicUsize::new(0));
+///
+/// let group = pool.task_group();
+/// for _ in 0..16 {
+/// 	let done = done.clone();
+/// 	group.enqueue(move |()| {
+/// 		done.fetch_add(1, Ordering::Relaxed);
+/// 	});
+/// }
+/// group.wait();
+/// assert_eq!(done.load(Ordering::Relaxed), 16);
+///
+/// {
+/// 	let group = pool.task_group();
+/// 	group.enqueue(|()| println!("may or may not run"));
+/// }
+/// // The task above has either completed or been cancelled.
+/// ```
+pub struct TaskGroup<'pool, WorkerData: Send> {
+	pool: &'pool ThreadPool<WorkerData>,
+	shared: Arc<GroupShared>,
+}
+
+#[derive(Default)]
+struct GroupShared {
+	state: Mutex<GroupState>,
+	condvar: Condvar,
+}
+
+#[derive(Default)]
+struct GroupState {
+	queued: usize,
+	running: usize,
+	cancelled: bool,
+}
+
+/// A task of a group, counted as queued until it starts and as running until it completes.
+/// Dropping it, e.g. when its task is discarded by the pool or panics, updates the counts.
+struct Member {
+	shared: Arc<GroupShared>,
+	started: bool,
+}
+
+impl Member {
+	fn new(shared: Arc<GroupShared>) -> Self {
+		shared.state.lock().unwrap().queued += 1;
+		Self {
+			shared,
+			started: false,
+		}
+	}
+
+	/// Mark the task as running, unless the group has been cancelled.
+	fn start(&mut self) -> bool {
+		let mut state = self.shared.state.lock().unwrap();
+		if state.cancelled {
+			debug!("task group dropped, skipping task");
+			return false;
+		}
+		state.queued -= 1;
+		state.running += 1;
+		self.started = true;
+		true
+	}
+}
+
+impl Drop for Member {
+	fn drop(&mut self) {
+		let mut state = self.shared.state.lock().unwrap();
+		if self.started {
+			state.running -= 1;
+		} else {
+			state.queued -= 1;
+		}
+		drop(state);
+		self.shared.condvar.notify_all();
+	}
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Construct a [`TaskGroup`] of tasks running on this pool.
+	#[must_use]
+	pub fn task_group(&self) -> TaskGroup<'_, WorkerData> {
+		TaskGroup {
+			pool: self,
+			shared: Arc::default(),
+		}
+	}
+}
+
+impl<WorkerData: Send> TaskGroup<'_, WorkerData> {
+	/// Enqueue a task as part of this group.
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	#[track_caller]
+	pub fn enqueue<Task>(&self, task: Task)
+	where
+		Task: FnOnce(&mut WorkerData) + Send + 'static,
+	{
+		let mut member = Member::new(self.shared.clone());
+		self.pool.enqueue(move |worker_data| {
+			if member.start() {
+				task(worker_data);
+			}
+		});
+	}
+
+	/// Block until all the tasks enqueued through this group have completed, or have been
+	/// discarded by the pool. Tasks enqueued in the pool by other means are not waited for.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn wait(&self) {
+		let _state = self
+			.shared
+			.condvar
+			.wait_while(self.shared.state.lock().unwrap(), |state| {
+				state.queued + state.running > 0
+			})
+			.unwrap();
+	}
+}
+
+impl<WorkerData: Send> Drop for TaskGroup<'_, WorkerData> {
+	fn drop(&mut self) {
+		let mut state = self.shared.state.lock().unwrap();
+		state.cancelled = true;
+		let _state = self
+			.shared
+			.condvar
+			.wait_while(state, |state| state.running > 0)
+			.unwrap();
+	}
+}
+
+impl<WorkerData: Send> fmt::Debug for TaskGroup<'_, WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let state = self.shared.state.lock().unwrap();
+		f.debug_struct("TaskGroup")
+			.field("queued", &state.queued)
+			.field("running", &state.running)
+			.finish()
+	}
+}
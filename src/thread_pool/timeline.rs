@@ -0,0 +1,206 @@
+use std::{
+	collections::BTreeMap,
+	fmt::Write as _,
+	io::{self, Write},
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+use super::{PoolEvent, PoolEventListener, CALLER_WORKER};
+
+/// Records when each task starts and ends on each worker, to be exported as a timeline with
+/// [`TimelineRecorder::write_chrome_trace`], e.g. to inspect the utilization of the pool and
+/// look for scheduling gaps after a run.
+///
+/// It's a [`PoolEventListener`], to be registered with
+/// [`ThreadPoolBuilder::event_listener`](crate::ThreadPoolBuilder::event_listener). Every
+/// event takes a lock shared by all the workers, and the recorded tasks are kept until
+/// [`TimelineRecorder::clear`] is called, so it's meant for diagnostics rather than to be
+/// left on in production.
+///
+/// Cloning a `TimelineRecorder` yields a handle to the same recording.
+///
+/// # Examples
+///
+/// ```
+/// use lending_thread_pool::{ThreadPool, TimelineRecorder};
+///
+/// let timeline = TimelineRecorder::new();
+/// let pool = ThreadPool::builder(vec![(); 2])
+/// 	.event_listener(timeline.clone())
+/// 	.build();
+///
+/// for i in 0..8 {
+/// 	pool.enqueue_named(format!("task {i}"), |()| {});
+/// }
+/// pool.join();
+///
+/// // e.g. to a file, to be opened with chrome://tracing or https://ui.perfetto.dev
+/// let mut trace = Vec::new();
+/// timeline.write_chrome_trace(&mut trace).unwrap();
+/// let trace = String::from_utf8(trace).unwrap();
+/// assert!(trace.contains(r#""name":"task 7""#));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TimelineRecorder {
+	shared: Arc<Mutex<Timeline>>,
+}
+
+#[derive(Debug)]
+struct Timeline {
+	/// Where the timeline starts.
+	origin: Instant,
+	/// For each worker seen so far, the task it's running, if any, and when it started.
+	running: BTreeMap<usize, Option<(Instant, Option<String>)>>,
+	spans: Vec<Span>,
+}
+
+impl Default for Timeline {
+	fn default() -> Self {
+		Self {
+			origin: Instant::now(),
+			running: BTreeMap::new(),
+			spans: Vec::new(),
+		}
+	}
+}
+
+/// A task run by a worker.
+#[derive(Debug)]
+struct Span {
+	worker: usize,
+	name: Option<String>,
+	/// Since the origin of the timeline.
+	start: Duration,
+	duration: Duration,
+	panicked: bool,
+}
+
+impl TimelineRecorder {
+	/// Construct a recorder whose timeline starts now.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Forget the tasks recorded so far.
+	#[allow(clippy::missing_panics_doc)]
+	pub fn clear(&self) {
+		self.shared.lock().unwrap().spans.clear();
+	}
+
+	/// Write the tasks recorded so far in the
+	/// [Trace Event Format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+	/// which can be loaded in `chrome://tracing` or [Perfetto](https://ui.perfetto.dev), with
+	/// a track per worker, followed by one for the tasks run by their caller, if any, see
+	/// [`ThreadPoolBuilder::caller_runs`](crate::ThreadPoolBuilder::caller_runs). Tasks still
+	/// running aren't included.
+	///
+	/// # Errors
+	/// - if writing to `writer` failed
+	#[allow(clippy::missing_panics_doc)]
+	pub fn write_chrome_trace(&self, mut writer: impl Write) -> io::Result<()> {
+		let timeline = self.shared.lock().unwrap();
+		let mut events = Vec::with_capacity(timeline.running.len() + timeline.spans.len());
+		// The callers get the track after the last worker.
+		let caller_tid = timeline
+			.running
+			.keys()
+			.rfind(|worker| **worker != CALLER_WORKER)
+			.map_or(0, |worker| worker + 1);
+		let tid = |worker| {
+			if worker == CALLER_WORKER {
+				caller_tid
+			} else {
+				worker
+			}
+		};
+		for &worker in timeline.running.keys() {
+			let name = if worker == CALLER_WORKER {
+				"caller".to_string()
+			} else {
+				format!("w({worker})")
+			};
+			events.push(format!(
+				r#"{{"name":"thread_name","ph":"M","pid":1,"tid":{},"args":{{"name":"{name}"}}}}"#,
+				tid(worker),
+			));
+		}
+		for span in &timeline.spans {
+			let mut event = String::from(r#"{"name":"#);
+			write_json_string(&mut event, span.name.as_deref().unwrap_or("task"));
+			let _ = write!(
+				event,
+				r#","cat":"task","ph":"X","pid":1,"tid":{},"ts":{:.3},"dur":{:.3}"#,
+				tid(span.worker),
+				micros(span.start),
+				micros(span.duration),
+			);
+			if span.panicked {
+				event.push_str(r#","args":{"panicked":true}"#);
+			}
+			event.push('}');
+			events.push(event);
+		}
+		drop(timeline);
+		write!(
+			writer,
+			r#"{{"traceEvents":[{}],"displayTimeUnit":"ms"}}"#,
+			events.join(",")
+		)?;
+		writer.flush()
+	}
+}
+
+impl PoolEventListener for TimelineRecorder {
+	fn on_event(&self, event: PoolEvent<'_>) {
+		let now = Instant::now();
+		let (worker, panicked) = match event {
+			PoolEvent::TaskDequeued { worker, name, .. } => {
+				let mut timeline = self.shared.lock().unwrap();
+				timeline
+					.running
+					.insert(worker, Some((now, name.map(str::to_string))));
+				return;
+			}
+			PoolEvent::TaskCompleted { worker, .. } => (worker, false),
+			PoolEvent::TaskPanicked { worker, .. } => (worker, true),
+			_ => return,
+		};
+		let mut timeline = self.shared.lock().unwrap();
+		let Some((started, name)) = timeline.running.get_mut(&worker).and_then(Option::take) else {
+			return;
+		};
+		let start = started.saturating_duration_since(timeline.origin);
+		timeline.spans.push(Span {
+			worker,
+			name,
+			start,
+			duration: now.saturating_duration_since(started),
+			panicked,
+		});
+	}
+}
+
+fn micros(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1e6
+}
+
+/// Append `s` to `out` as a JSON string literal.
+fn write_json_string(out: &mut String, s: &str) {
+	out.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => out.push_str(r#"\""#),
+			'\\' => out.push_str(r"\\"),
+			'\n' => out.push_str(r"\n"),
+			'\r' => out.push_str(r"\r"),
+			'\t' => out.push_str(r"\t"),
+			c if u32::from(c) < 0x20 => {
+				let _ = write!(out, r"\u{:04x}", u32::from(c));
+			}
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+}
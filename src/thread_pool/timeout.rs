@@ -0,0 +1,88 @@
+use std::{
+	sync::{atomic::Ordering, Arc},
+	time::{Duration, Instant},
+};
+
+use super::{Clock, ThreadPool};
+
+/// Tells a task enqueued with [`ThreadPool::enqueue_with_timeout`] whether it has run past
+/// its timeout, as measured by the [`Clock`] of the pool since the task started.
+///
+/// Well-behaved tasks periodically check [`TimeoutSignal::is_timed_out`] to abandon work
+/// that's no longer worth finishing. Cloning a `TimeoutSignal` yields a handle to the same timeout,
+/// e.g. to pass it down to the functions doing the work.
+#[derive(Debug, Clone)]
+pub struct TimeoutSignal {
+	deadline: Instant,
+	clock: Arc<dyn Clock>,
+}
+
+impl TimeoutSignal {
+	/// Whether the task has run past its timeout.
+	#[must_use]
+	pub fn is_timed_out(&self) -> bool {
+		self.clock.now() >= self.deadline
+	}
+
+	/// The time left before the task runs past its timeout.
+	#[must_use]
+	pub fn remaining(&self) -> Duration {
+		self.deadline.saturating_duration_since(self.clock.now())
+	}
+}
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Enqueue a task that's expected to complete within `timeout` once started. The task is
+	/// passed a [`TimeoutSignal`] raised when the timeout is exceeded, which is up to the task
+	/// to check, as it's never interrupted. Tasks completing past their timeout are counted
+	/// in [`PoolStatus::tasks_timed_out`](crate::PoolStatus::tasks_timed_out).
+	///
+	/// # Blocking
+	///
+	/// This method is blocking. It waits for the task queue to have at least one empty
+	/// slot before returning.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::{thread::sleep, time::Duration};
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 1]);
+	///
+	/// pool.enqueue_with_timeout(Duration::from_millis(10), |(), timeout| {
+	/// 	for _ in 0..1000 {
+	/// 		if timeout.is_timed_out() {
+	/// 			println!("giving up, the result would come too late");
+	/// 			return;
+	/// 		}
+	/// 		sleep(Duration::from_millis(1));
+	/// 	}
+	/// });
+	/// pool.flush();
+	///
+	/// assert_eq!(pool.status().tasks_timed_out, 1);
+	/// ```
+	#[track_caller]
+	pub fn enqueue_with_timeout<Task>(&self, timeout: Duration, task: Task)
+	where
+		Task: FnOnce(&mut WorkerData, &TimeoutSignal) + Send + 'static,
+	{
+		let inner = Arc::downgrade(&self.inner);
+		let clock = self.inner.clock.clone();
+		self.enqueue(move |worker_data| {
+			let signal = TimeoutSignal {
+				deadline: clock.now() + timeout,
+				clock,
+			};
+			task(worker_data, &signal);
+			let now = signal.clock.now();
+			if now >= signal.deadline {
+				debug!("task ran past its timeout", overrun = now - signal.deadline);
+				if let Some(inner) = inner.upgrade() {
+					inner.counters.timed_out.fetch_add(1, Ordering::Relaxed);
+				}
+			}
+		});
+	}
+}
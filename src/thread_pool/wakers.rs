@@ -0,0 +1,42 @@
+use std::{
+	mem,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex,
+	},
+	task::Waker,
+};
+
+/// The wakers of the async code waiting on the pool, i.e. of the sinks waiting for room in
+/// the queue and of the futures waiting for the pool to be joined. Woken whenever the queue
+/// shrinks or a worker exits, see [`ThreadPoolShared::notify_room`](super::ThreadPoolShared::notify_room).
+#[derive(Debug, Default)]
+pub(crate) struct Wakers {
+	wakers: Mutex<Vec<Waker>>,
+	/// Whether `wakers` is not empty, so that making room doesn't take the lock for nothing.
+	waiting: AtomicBool,
+}
+
+impl Wakers {
+	pub(crate) fn register(&self, waker: &Waker) {
+		let mut wakers = self.wakers.lock().unwrap();
+		if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+			wakers.push(waker.clone());
+		}
+		self.waiting.store(true, Ordering::SeqCst);
+	}
+
+	/// Wake the async code, which checks the pool again.
+	pub(crate) fn wake(&self) {
+		if !self.waiting.load(Ordering::SeqCst) {
+			return;
+		}
+		let mut guard = self.wakers.lock().unwrap();
+		self.waiting.store(false, Ordering::SeqCst);
+		let wakers = mem::take(&mut *guard);
+		drop(guard);
+		for waker in wakers {
+			waker.wake();
+		}
+	}
+}
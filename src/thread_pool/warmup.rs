@@ -0,0 +1,44 @@
+use std::{
+	fmt,
+	sync::{mpsc::Sender, Arc},
+};
+
+type WarmupFn<WorkerData> = dyn Fn(usize, &mut WorkerData) + Send + Sync;
+
+/// Prepares each worker before the pool is handed to the caller,
+/// see [`ThreadPoolBuilder::warmup`](crate::ThreadPoolBuilder::warmup).
+pub(crate) struct Warmup<WorkerData>(Arc<WarmupFn<WorkerData>>);
+
+impl<WorkerData> Warmup<WorkerData> {
+	pub(crate) fn new(warmup: impl Fn(usize, &mut WorkerData) + Send + Sync + 'static) -> Self {
+		Self(Arc::new(warmup))
+	}
+
+	/// Hand the warmup to a worker, which drops `done` once warmed up.
+	pub(crate) fn for_worker(&self, done: Sender<()>) -> WorkerWarmup<WorkerData> {
+		WorkerWarmup {
+			warmup: self.0.clone(),
+			_done: done,
+		}
+	}
+}
+
+impl<WorkerData> fmt::Debug for Warmup<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Warmup").finish_non_exhaustive()
+	}
+}
+
+/// The warmup of a single worker. The pool waits for every sender to be dropped,
+/// which also happens if the warmup panics.
+pub(crate) struct WorkerWarmup<WorkerData> {
+	warmup: Arc<WarmupFn<WorkerData>>,
+	_done: Sender<()>,
+}
+
+impl<WorkerData> WorkerWarmup<WorkerData> {
+	pub(crate) fn run(self, index: usize, worker_data: &mut WorkerData) {
+		debug!("warming up...");
+		(self.warmup)(index, worker_data);
+	}
+}
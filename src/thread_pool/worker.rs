@@ -0,0 +1,777 @@
+use std::{
+	borrow::Cow,
+	collections::VecDeque,
+	fmt, io,
+	panic::{self, AssertUnwindSafe, Location},
+	sync::{
+		atomic::{AtomicBool, AtomicU64, Ordering},
+		Arc, Mutex, MutexGuard, PoisonError,
+	},
+	thread::{self, Thread},
+	time::{Duration, Instant},
+};
+
+use super::{
+	cpu_time,
+	label::{LabelSpan, WorkerLabel},
+	meta::{Metadata, TaskMeta},
+	panic_hook::{PanicHook, TaskPanic},
+	qos,
+	report::panic_message,
+	warmup::WorkerWarmup,
+	worker_thread::WorkerThread,
+	Clock, DequeueResult, IdleAction, PoolEvent, PoolQueue, StrandedWorker, Task, TaskOutcome,
+	TaskRecord, ThreadBudget, ThreadPoolShared,
+};
+
+/// The part of a worker that is shared with the pool.
+#[derive(Debug)]
+pub(crate) struct WorkerShared {
+	pub(crate) state: Mutex<WorkerState>,
+	/// Tasks run to completion by this worker.
+	pub(crate) completed: AtomicU64,
+	/// Sequence number of the task being run, or [`NOT_RUNNING`].
+	pub(crate) running_seq: AtomicU64,
+	/// Bumped when the worker is handed over to a new thread, see [`WorkerShared::strand`].
+	generation: AtomicU64,
+	/// The thread currently serving the worker.
+	thread: Mutex<Option<Thread>>,
+	/// The same thread, as reported by [`ThreadPool::worker_threads`](crate::ThreadPool::worker_threads).
+	pub(crate) os_thread: Mutex<Option<WorkerThread>>,
+	/// The last tasks run, see [`ThreadPoolBuilder::task_history`](crate::ThreadPoolBuilder::task_history).
+	history: Mutex<VecDeque<TaskRecord>>,
+	/// Set by [`ThreadPoolBuilder::worker_label`](crate::ThreadPoolBuilder::worker_label).
+	pub(crate) label: Option<String>,
+	/// Whether the worker is parked waiting for tasks, until popped from the
+	/// [`ParkedWorkers`](super::parked::ParkedWorkers).
+	pub(crate) parked: AtomicBool,
+	/// The worker below this one in the [`ParkedWorkers`](super::parked::ParkedWorkers).
+	pub(crate) next_parked: AtomicU64,
+}
+
+pub(crate) const NOT_RUNNING: u64 = u64::MAX;
+
+impl WorkerShared {
+	/// The shared part of the worker at `index`, named by `label`, if any.
+	pub(crate) fn new<WorkerData>(
+		index: usize,
+		worker_data: &WorkerData,
+		label: Option<&WorkerLabel<WorkerData>>,
+	) -> Self {
+		let label = label.map(|label| label.label(index, worker_data));
+		Self {
+			state: Mutex::new(WorkerState::Idle),
+			completed: AtomicU64::new(0),
+			running_seq: AtomicU64::new(NOT_RUNNING),
+			generation: AtomicU64::new(0),
+			thread: Mutex::new(None),
+			os_thread: Mutex::new(None),
+			history: Mutex::default(),
+			label,
+			parked: AtomicBool::new(false),
+			next_parked: AtomicU64::new(0),
+		}
+	}
+
+	/// Wake up the worker after popping it from the [`ParkedWorkers`](super::parked::ParkedWorkers).
+	pub(crate) fn unpark(&self) {
+		self.parked.store(false, Ordering::SeqCst);
+		if let Some(thread) = &*self.thread.lock().unwrap() {
+			thread.unpark();
+		}
+	}
+
+	/// The last tasks run, oldest first.
+	pub(crate) fn recent_tasks(&self) -> Vec<TaskRecord> {
+		self.history.lock().unwrap().iter().cloned().collect()
+	}
+
+	/// Record the end of the task the worker is running, if any, keeping the last `len` ones.
+	/// `now` is read from the pool clock, like the start of the task.
+	fn record(&self, len: usize, now: Instant, state: &WorkerState, outcome: TaskOutcome) {
+		let WorkerState::Running {
+			name,
+			location,
+			since,
+		} = state
+		else {
+			return;
+		};
+		if len == 0 {
+			return;
+		}
+		let mut history = self.history.lock().unwrap();
+		if history.len() == len {
+			history.pop_front();
+		}
+		history.push_back(TaskRecord {
+			name: name.as_deref().map(str::to_string),
+			location,
+			elapsed: now.saturating_duration_since(*since),
+			outcome,
+		});
+	}
+
+	fn is_replaced(&self, generation: u64) -> bool {
+		self.generation.load(Ordering::SeqCst) != generation
+	}
+
+	/// Hand the worker over to a new thread, spawned by `replace`, if it has been running
+	/// the same task for at least `threshold`, telling the thread running the task to exit
+	/// once it's done. Nothing is stranded if the new thread can't be spawned.
+	pub(crate) fn strand<WorkerData: Send>(
+		&self,
+		index: usize,
+		inner: &ThreadPoolShared<WorkerData>,
+		threshold: Duration,
+		replace: impl FnOnce() -> io::Result<()>,
+	) -> Option<io::Result<StrandedWorker>> {
+		let mut state = self.state.lock().unwrap();
+		let WorkerState::Running {
+			name,
+			location,
+			since,
+		} = &*state
+		else {
+			return None;
+		};
+		let now = inner.clock.now();
+		let elapsed = now.saturating_duration_since(*since);
+		if elapsed < threshold {
+			return None;
+		}
+		let stranded = StrandedWorker {
+			worker: index,
+			thread: self.thread.lock().unwrap().clone()?,
+			name: name.as_deref().map(str::to_string),
+			location,
+			elapsed,
+		};
+		// The new thread waits for the state to be released before serving, see `run`.
+		if let Err(error) = replace() {
+			return Some(Err(error));
+		}
+		self.generation.fetch_add(1, Ordering::SeqCst);
+		self.record(inner.task_history, now, &state, TaskOutcome::Abandoned);
+		*state = WorkerState::Idle;
+		drop(state);
+		// The task is given up on, so flushing the pool doesn't wait for it.
+		drop(RunningTask {
+			inner,
+			worker: self,
+			seq: self.running_seq.load(Ordering::SeqCst),
+		});
+		Some(Ok(stranded))
+	}
+
+	/// Run the given task, marking the worker as running it since `clock` says so in the
+	/// meantime. Returns when the task started.
+	fn run<WorkerData>(
+		&self,
+		index: usize,
+		task: Task<WorkerData>,
+		worker_data: &mut WorkerData,
+		panic_hook: Option<&PanicHook>,
+		clock: &dyn Clock,
+	) -> Instant {
+		let started = Instant::now();
+		trace!(
+			"task started",
+			task = task.seq,
+			worker = index,
+			task_name = task.name,
+			queue_wait = started.saturating_duration_since(task.enqueued_at),
+		);
+		profile_scope!("task", task.name.as_deref().unwrap_or_default());
+		let name = panic_hook.and(task.name.clone());
+		*self.state.lock().unwrap() = WorkerState::Running {
+			name: task.name,
+			location: task.location,
+			since: clock.now(),
+		};
+		let _installed = task.captured.install();
+		let Some(panic_hook) = panic_hook else {
+			(task.run)(worker_data);
+			return started;
+		};
+		if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| (task.run)(worker_data))) {
+			panic_hook.call(&TaskPanic {
+				worker: index,
+				name: name.as_deref(),
+				location: task.location,
+				message: panic_message(&*payload),
+				payload: &*payload,
+			});
+			panic::resume_unwind(payload);
+		}
+		started
+	}
+
+	/// Mark the worker as idle after completing the task started at `started`, unless it was
+	/// handed over to a new thread in the meantime. Returns whether it's still served by this thread.
+	fn finish_running(
+		&self,
+		index: usize,
+		seq: u64,
+		started: Instant,
+		generation: u64,
+		history: usize,
+		clock: &dyn Clock,
+	) -> bool {
+		trace!(
+			"task completed",
+			task = seq,
+			worker = index,
+			elapsed = started.elapsed(),
+		);
+		self.completed.fetch_add(1, Ordering::Relaxed);
+		let mut state = self.state.lock().unwrap();
+		if self.is_replaced(generation) {
+			return false;
+		}
+		self.record(history, clock.now(), &state, TaskOutcome::Completed);
+		*state = WorkerState::Idle;
+		true
+	}
+}
+
+/// Replaces the data of a worker after it has run a given number of tasks,
+/// see [`ThreadPoolBuilder::max_tasks_per_worker`](crate::ThreadPoolBuilder::max_tasks_per_worker).
+pub(crate) struct WorkerRecycling<WorkerData> {
+	pub(crate) max_tasks: usize,
+	pub(crate) factory: Box<dyn Fn(usize) -> WorkerData + Send + Sync>,
+}
+
+impl<WorkerData> WorkerRecycling<WorkerData> {
+	/// Count a task run by the given worker, rebuilding its data once it has run enough of them.
+	fn after_task(
+		&self,
+		index: usize,
+		tasks_since_recycle: &mut usize,
+		worker_data: &mut WorkerData,
+	) {
+		*tasks_since_recycle += 1;
+		if *tasks_since_recycle == self.max_tasks {
+			debug!("recycling worker data", worker = index);
+			*tasks_since_recycle = 0;
+			*worker_data = (self.factory)(index);
+		}
+	}
+}
+
+impl<WorkerData> fmt::Debug for WorkerRecycling<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("WorkerRecycling")
+			.field("max_tasks", &self.max_tasks)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Replaces workers brought down by a panic,
+/// see [`ThreadPoolBuilder::respawn_dead_workers`](crate::ThreadPoolBuilder::respawn_dead_workers).
+pub(crate) struct Respawn<WorkerData> {
+	pub(crate) factory: Box<dyn Fn(usize) -> WorkerData + Send + Sync>,
+}
+
+impl<WorkerData> fmt::Debug for Respawn<WorkerData> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Respawn").finish_non_exhaustive()
+	}
+}
+
+/// What a worker is currently up to.
+pub(crate) enum WorkerState {
+	Idle,
+	Running {
+		name: Option<Cow<'static, str>>,
+		location: &'static Location<'static>,
+		since: Instant,
+	},
+	Exited,
+	/// Where the task that brought the worker down was enqueued from, if the panic
+	/// happened while running one.
+	Panicked {
+		location: Option<&'static Location<'static>>,
+	},
+}
+
+impl fmt::Debug for WorkerState {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Idle => write!(f, "idle"),
+			Self::Running {
+				name: Some(name),
+				location,
+				since,
+			} => write!(
+				f,
+				"running {name:?} from {location} for {:?}",
+				since.elapsed()
+			),
+			Self::Running {
+				name: None,
+				location,
+				since,
+			} => write!(
+				f,
+				"running unnamed task from {location} for {:?}",
+				since.elapsed()
+			),
+			Self::Exited => write!(f, "exited"),
+			Self::Panicked {
+				location: Some(location),
+			} => write!(f, "panicked running task from {location}"),
+			Self::Panicked { location: None } => write!(f, "panicked"),
+		}
+	}
+}
+
+pub(crate) fn run<WorkerData: Send>(
+	index: usize,
+	inner: &Arc<ThreadPoolShared<WorkerData>>,
+	mut warmup: Option<WorkerWarmup<WorkerData>>,
+	mut worker_data: WorkerData,
+) -> WorkerData {
+	if let Some(class) = inner.qos_class {
+		qos::set_current_thread_qos(class);
+	}
+	#[cfg(feature = "profiling")]
+	::profiling::register_thread!();
+	let worker = inner.worker(index);
+	let _span = LabelSpan::enter(worker.label.as_deref());
+	*worker.thread.lock().unwrap() = Some(thread::current());
+	*worker.os_thread.lock().unwrap() = Some(WorkerThread::current());
+	// Read under the state lock, as a replacement is spawned before the worker is handed
+	// over to it, see `WorkerShared::strand`.
+	let generation = {
+		let _state = worker.state.lock().unwrap();
+		worker.generation.load(Ordering::SeqCst)
+	};
+	let Some(respawn) = &inner.respawn else {
+		return serve(index, generation, inner, warmup, worker_data);
+	};
+	loop {
+		let served = panic::catch_unwind(AssertUnwindSafe(|| {
+			serve(index, generation, inner, warmup.take(), worker_data)
+		}));
+		let payload = match served {
+			Ok(worker_data) => return worker_data,
+			Err(payload) => payload,
+		};
+		if worker.is_replaced(generation) {
+			// The worker is served by another thread now.
+			panic::resume_unwind(payload);
+		}
+		warn!("respawning worker brought down by a panic", worker = index);
+		worker_data = (respawn.factory)(index);
+		*worker.state.lock().unwrap() = WorkerState::Idle;
+		inner.dead.fetch_sub(1, Ordering::Relaxed);
+	}
+}
+
+fn serve<WorkerData: Send>(
+	index: usize,
+	generation: u64,
+	inner: &Arc<ThreadPoolShared<WorkerData>>,
+	warmup: Option<WorkerWarmup<WorkerData>>,
+	mut worker_data: WorkerData,
+) -> WorkerData {
+	let ThreadPoolShared {
+		counters,
+		thread_budget,
+		..
+	} = &**inner;
+	let worker = &*inner.worker(index);
+	let mut tasks_since_recycle = 0;
+	let _exit_guard = ExitGuard {
+		inner,
+		index,
+		generation,
+	};
+	inner
+		.listeners
+		.emit(PoolEvent::WorkerSpawned { worker: index });
+	if let Some(warmup) = warmup {
+		warmup.run(index, &mut worker_data);
+	}
+	inner.run_service(index, &mut worker_data);
+
+	loop {
+		let (guard, dequeued) = next_task(index, inner);
+
+		if let Some(DequeueResult::TaskAvailable {
+			task,
+			has_more,
+			capped,
+		}) = dequeued
+		{
+			worker.running_seq.store(task.seq, Ordering::SeqCst);
+			let running = RunningTask {
+				inner,
+				worker,
+				seq: task.seq,
+			};
+			let ticket = inner
+				.start_order
+				.as_ref()
+				.map(|order| (order, order.take_ticket()));
+			drop(guard);
+			inner.notify_room();
+			if has_more {
+				inner.wake_all_workers();
+			}
+			let class_slot = capped.then(|| ClassSlot {
+				inner,
+				class: task.class,
+			});
+			// Waiting for the turn before acquiring a permit keeps permits from being held
+			// by workers that can't start yet.
+			let turn = ticket.map(|(order, ticket)| order.wait_turn(ticket));
+			let permit = thread_budget
+				.as_ref()
+				.filter(|_| task.budgeted)
+				.map(ThreadBudget::acquire);
+			let events = (!inner.listeners.is_empty()).then(|| TaskEvents {
+				inner,
+				worker: index,
+				name: task.name.clone(),
+				meta: task.meta.clone(),
+				since: Instant::now(),
+				cpu_since: cpu_time::thread_cpu_time(),
+			});
+			if let Some(events) = &events {
+				events.emit_dequeued();
+			}
+			drop(turn);
+			let seq = task.seq;
+			let started = worker.run(
+				index,
+				task,
+				&mut worker_data,
+				inner.panic_hook.as_ref(),
+				&*inner.clock,
+			);
+			drop(events);
+			counters.completed.fetch_add(1, Ordering::Relaxed);
+			let replaced = !worker.finish_running(
+				index,
+				seq,
+				started,
+				generation,
+				inner.task_history,
+				&*inner.clock,
+			);
+			drop(permit);
+			drop(class_slot);
+			drop(running);
+			if replaced {
+				debug!("replaced while running a task, exiting", worker = index);
+				break;
+			}
+
+			if let Some(recycling) = &inner.recycling {
+				recycling.after_task(index, &mut tasks_since_recycle, &mut worker_data);
+			}
+		} else {
+			debug!("quitting", worker = index);
+			break;
+		}
+	}
+	worker_data
+}
+
+/// Run a task on the calling thread, as the worker at `index`: either as the only worker
+/// of a pool whose threads couldn't be spawned, see
+/// [`SpawnFailurePolicy::Degrade`](crate::SpawnFailurePolicy::Degrade), or on behalf of
+/// the callers, see [`ThreadPoolBuilder::caller_runs`](crate::ThreadPoolBuilder::caller_runs).
+pub(crate) fn run_inline<WorkerData: Send>(
+	inner: &ThreadPoolShared<WorkerData>,
+	index: usize,
+	worker: &WorkerShared,
+	task: Task<WorkerData>,
+	worker_data: &mut WorkerData,
+) {
+	let generation = worker.generation.load(Ordering::SeqCst);
+	worker.running_seq.store(task.seq, Ordering::SeqCst);
+	let running = RunningTask {
+		inner,
+		worker,
+		seq: task.seq,
+	};
+	let permit = inner
+		.thread_budget
+		.as_ref()
+		.filter(|_| task.budgeted)
+		.map(ThreadBudget::acquire);
+	let events = (!inner.listeners.is_empty()).then(|| TaskEvents {
+		inner,
+		worker: index,
+		name: task.name.clone(),
+		meta: task.meta.clone(),
+		since: Instant::now(),
+		cpu_since: cpu_time::thread_cpu_time(),
+	});
+	if let Some(events) = &events {
+		events.emit_dequeued();
+	}
+	let seq = task.seq;
+	let guard = InlineGuard { inner, worker };
+	let started = worker.run(
+		index,
+		task,
+		worker_data,
+		inner.panic_hook.as_ref(),
+		&*inner.clock,
+	);
+	drop(guard);
+	drop(events);
+	inner.counters.completed.fetch_add(1, Ordering::Relaxed);
+	worker.finish_running(
+		index,
+		seq,
+		started,
+		generation,
+		inner.task_history,
+		&*inner.clock,
+	);
+	drop(permit);
+	drop(running);
+}
+
+/// Marks the worker as idle again if dropped while unwinding, as a task run inline panicking
+/// is propagated to the thread that enqueued it rather than bringing the worker down.
+struct InlineGuard<'a, WorkerData: Send> {
+	inner: &'a ThreadPoolShared<WorkerData>,
+	worker: &'a WorkerShared,
+}
+
+impl<WorkerData: Send> Drop for InlineGuard<'_, WorkerData> {
+	fn drop(&mut self) {
+		if !thread::panicking() {
+			return;
+		}
+		let mut state = self
+			.worker
+			.state
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner);
+		self.worker.record(
+			self.inner.task_history,
+			self.inner.clock.now(),
+			&state,
+			TaskOutcome::Panicked,
+		);
+		*state = WorkerState::Idle;
+	}
+}
+
+/// Wait for a task for the given worker, holding the queue lock once done.
+/// Returns no task once the pool is stopped.
+fn next_task<WorkerData: Send>(
+	index: usize,
+	inner: &ThreadPoolShared<WorkerData>,
+) -> (
+	MutexGuard<'_, PoolQueue<WorkerData>>,
+	Option<DequeueResult<WorkerData>>,
+) {
+	let mut guard = inner.pending_tasks.lock().unwrap();
+	let mut attempt = 0;
+	let mut park = false;
+	loop {
+		inner.pull_segments(&mut guard);
+		match guard.dequeue(index) {
+			DequeueResult::Joined => return (guard, None),
+			DequeueResult::Retired => {
+				debug!("retired", worker = index);
+				return (guard, None);
+			}
+			DequeueResult::WaitingForTasks
+				if inner.detached.load(Ordering::Relaxed)
+					&& matches!(&*guard, PoolQueue::Todo(tasks) if tasks.is_empty())
+					&& !inner.has_delayed_retries() =>
+			{
+				debug!("queue drained, stopping detached pool", worker = index);
+				*guard = PoolQueue::Done;
+				inner.wake_all_workers();
+				inner.notify_room();
+				return (guard, None);
+			}
+			DequeueResult::WaitingForTasks if park => {
+				inner.sleeping.fetch_add(1, Ordering::SeqCst);
+				// A task pushed to a segment by now is either seen here, or its sender
+				// sees this worker sleeping and wakes it up.
+				if inner.pull_segments(&mut guard) > 0 {
+					inner.sleeping.fetch_sub(1, Ordering::SeqCst);
+					continue;
+				}
+				debug!("waiting for tasks", worker = index);
+				let worker = inner.worker(index);
+				inner.parked.push(index, &inner.workers());
+				drop(guard);
+				// Unparking before parking makes the latter return right away, so the wake-up
+				// can't be missed, while spurious ones are told apart by the flag.
+				while worker.parked.load(Ordering::SeqCst) {
+					thread::park();
+				}
+				guard = inner.pending_tasks.lock().unwrap();
+				inner.sleeping.fetch_sub(1, Ordering::SeqCst);
+				park = false;
+			}
+			DequeueResult::WaitingForTasks => {
+				drop(guard);
+				park = inner.idle_wait.wait(attempt) == IdleAction::Park;
+				attempt = attempt.saturating_add(1);
+				guard = inner.pending_tasks.lock().unwrap();
+			}
+			dequeued @ DequeueResult::TaskAvailable { .. } => return (guard, Some(dequeued)),
+		}
+	}
+}
+
+/// Reports the outcome of a task to the listeners of the pool when dropped.
+struct TaskEvents<'a, WorkerData: Send> {
+	inner: &'a ThreadPoolShared<WorkerData>,
+	worker: usize,
+	name: Option<Cow<'static, str>>,
+	meta: Option<Arc<dyn Metadata>>,
+	since: Instant,
+	cpu_since: Option<Duration>,
+}
+
+impl<WorkerData: Send> TaskEvents<'_, WorkerData> {
+	fn emit_dequeued(&self) {
+		self.inner.listeners.emit(PoolEvent::TaskDequeued {
+			worker: self.worker,
+			name: self.name.as_deref(),
+			meta: self.meta.as_ref().map(TaskMeta::new),
+		});
+	}
+}
+
+impl<WorkerData: Send> Drop for TaskEvents<'_, WorkerData> {
+	fn drop(&mut self) {
+		let (worker, name) = (self.worker, self.name.as_deref());
+		let meta = self.meta.as_ref().map(TaskMeta::new);
+		self.inner.listeners.emit(if thread::panicking() {
+			PoolEvent::TaskPanicked { worker, name, meta }
+		} else {
+			PoolEvent::TaskCompleted {
+				worker,
+				name,
+				meta,
+				elapsed: self.since.elapsed(),
+				cpu_time: self
+					.cpu_since
+					.zip(cpu_time::thread_cpu_time())
+					.map(|(since, now)| now.saturating_sub(since)),
+			}
+		});
+	}
+}
+
+/// Marks the worker as exited when dropped, or as panicked if dropped while unwinding.
+/// In the latter case, tasks targeting the worker are dropped, as they would never run.
+/// Does nothing if the worker has been handed over to a new thread.
+struct ExitGuard<'a, WorkerData: Send> {
+	inner: &'a ThreadPoolShared<WorkerData>,
+	index: usize,
+	generation: u64,
+}
+
+impl<WorkerData: Send> Drop for ExitGuard<'_, WorkerData> {
+	fn drop(&mut self) {
+		let worker = self.inner.worker(self.index);
+		if worker.is_replaced(self.generation) {
+			return;
+		}
+		let state = &worker.state;
+		self.inner.listeners.emit(PoolEvent::WorkerExited {
+			worker: self.index,
+			panicked: thread::panicking(),
+		});
+		if thread::panicking() {
+			// The state is updated while holding the queue lock, so that no task can target
+			// this worker after its inbox has been cleared.
+			let mut guard = self
+				.inner
+				.pending_tasks
+				.lock()
+				.unwrap_or_else(PoisonError::into_inner);
+			let mut state = state.lock().unwrap();
+			if worker.is_replaced(self.generation) {
+				return;
+			}
+			let location = match *state {
+				WorkerState::Running { location, .. } => Some(location),
+				_ => None,
+			};
+			debug!("worker panicked", worker = self.index, location = location);
+			worker.record(
+				self.inner.task_history,
+				self.inner.clock.now(),
+				&state,
+				TaskOutcome::Panicked,
+			);
+			*state = WorkerState::Panicked { location };
+			drop(state);
+			self.inner.dead.fetch_add(1, Ordering::Relaxed);
+			let dropped = match &mut *guard {
+				PoolQueue::Todo(tasks) => tasks.clear_inbox(self.index),
+				PoolQueue::Done => VecDeque::new(),
+			};
+			drop(guard);
+			self.inner.notify_room();
+			self.inner.report_discarded(&dropped);
+		} else {
+			*state.lock().unwrap() = WorkerState::Exited;
+			// Whoever is waiting for the workers to exit has to take another look.
+			self.inner.notify_room();
+		}
+	}
+}
+
+/// Marks the worker as no longer running a task when dropped, even if the task panicked,
+/// waking up any thread waiting in [`ThreadPool::flush`](crate::ThreadPool::flush).
+struct RunningTask<'a, WorkerData: Send> {
+	inner: &'a ThreadPoolShared<WorkerData>,
+	worker: &'a WorkerShared,
+	seq: u64,
+}
+
+impl<WorkerData: Send> Drop for RunningTask<'_, WorkerData> {
+	fn drop(&mut self) {
+		let stopped = self.worker.running_seq.compare_exchange(
+			self.seq,
+			NOT_RUNNING,
+			Ordering::SeqCst,
+			Ordering::SeqCst,
+		);
+		if stopped.is_err() {
+			// already given up on, see `WorkerShared::strand`
+			return;
+		}
+		if self.inner.flushers.load(Ordering::SeqCst) > 0 {
+			// taking the lock guarantees flushers are either waiting or yet to check the running tasks
+			drop(
+				self.inner
+					.pending_tasks
+					.lock()
+					.unwrap_or_else(PoisonError::into_inner),
+			);
+			self.inner.flush_condvar.notify_all();
+		}
+	}
+}
+
+/// Releases the worker slot taken by a task of a class with capped workers when dropped,
+/// even if the task panicked.
+struct ClassSlot<'a, WorkerData: Send> {
+	inner: &'a ThreadPoolShared<WorkerData>,
+	class: usize,
+}
+
+impl<WorkerData: Send> Drop for ClassSlot<'_, WorkerData> {
+	fn drop(&mut self) {
+		self.inner.release_class_slot(self.class);
+	}
+}
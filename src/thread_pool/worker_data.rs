@@ -0,0 +1,183 @@
+use std::{mem, sync::mpsc};
+
+use super::{PoolQueue, Task, ThreadPool, WorkerState};
+
+impl<WorkerData: Send + 'static> ThreadPool<WorkerData> {
+	/// Replace the data lent to the worker at the given index, returning the previous value.
+	///
+	/// The swap is performed by the worker itself between two tasks, so it never races with
+	/// a running task. Tasks targeting a single worker take precedence over the ones in the queue.
+	///
+	/// Returns `None` if the worker was brought down by a panic (see [`ThreadPool::dead_workers`])
+	/// or the pool has been shut down, in which case `new_data` is dropped.
+	///
+	/// # Blocking
+	///
+	/// This method blocks until the worker finishes its current task, if any.
+	/// Calling it from a task running on the same worker results in a deadlock.
+	///
+	/// # Panics
+	///
+	/// - if `index` is not smaller than the number of workers
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![String::from("old"); 2]);
+	///
+	/// assert_eq!(pool.replace_worker_data(1, String::from("new")).as_deref(), Some("old"));
+	///
+	/// pool.enqueue(|data| assert!(data == "old" || data == "new"));
+	/// pool.join();
+	/// ```
+	pub fn replace_worker_data(&self, index: usize, new_data: WorkerData) -> Option<WorkerData> {
+		assert!(
+			index < self.inner.workers().len(),
+			"worker index {index} out of range"
+		);
+
+		let (sender, receiver) = mpsc::sync_channel(1);
+		let task = Task {
+			budgeted: false,
+			..Task::new(move |worker_data: &mut WorkerData| {
+				// The receiver is only dropped after a successful swap.
+				let _ = sender.send(mem::replace(worker_data, new_data));
+			})
+		};
+
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			debug!(
+				"pool is shut down, not replacing worker data",
+				worker = index
+			);
+			return None;
+		};
+		if matches!(
+			*self.inner.workers()[index].state.lock().unwrap(),
+			WorkerState::Panicked { .. }
+		) {
+			debug!("worker is dead, not replacing its data", worker = index);
+			return None;
+		}
+		tasks.push_to_worker(index, task);
+		drop(guard);
+		self.inner.wake_all_workers();
+
+		debug!("waiting for worker to swap its data", worker = index);
+		receiver.recv().ok()
+	}
+
+	/// Rebuild the data of every worker from `factory`, which is called with each worker index,
+	/// returning the previous values.
+	///
+	/// The new data is built before touching the pool, then the pool is quiesced: every worker
+	/// finishes its current task and the swap happens only once all of them are idle, and no task
+	/// runs until every worker has been updated. This makes it suitable for reloading
+	/// configuration that worker data derives from, without tasks ever observing a mix
+	/// of old and new data.
+	///
+	/// The returned vector has one element per worker, which is `None` for workers brought down
+	/// by a panic (see [`ThreadPool::dead_workers`]), or for all of them if the pool has been
+	/// shut down.
+	///
+	/// # Blocking
+	///
+	/// This method blocks until all workers finish their current task, if any.
+	/// Calling it from a task running on the same pool results in a deadlock.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![0; 3]);
+	///
+	/// let old = pool.reload_workers(|i| i + 10);
+	///
+	/// assert_eq!(old, vec![Some(0), Some(0), Some(0)]);
+	/// assert_eq!(pool.reload_workers(|_| 0), vec![Some(10), Some(11), Some(12)]);
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	pub fn reload_workers(
+		&self,
+		mut factory: impl FnMut(usize) -> WorkerData,
+	) -> Vec<Option<WorkerData>> {
+		let alive: Vec<bool> = self
+			.inner
+			.workers()
+			.iter()
+			.map(|worker| !matches!(*worker.state.lock().unwrap(), WorkerState::Panicked { .. }))
+			.collect();
+		let mut new_data: Vec<Option<WorkerData>> = alive
+			.iter()
+			.enumerate()
+			.map(|(index, &alive)| alive.then(|| factory(index)))
+			.collect();
+
+		let mut guard = self.inner.pending_tasks.lock().unwrap();
+		let PoolQueue::Todo(tasks) = &mut *guard else {
+			debug!("pool is shut down, not reloading workers");
+			return alive.iter().map(|_| None).collect();
+		};
+		let mut reloads = Vec::with_capacity(alive.len());
+		for (index, alive) in alive.into_iter().enumerate() {
+			let worker_alive = alive
+				&& !matches!(
+					*self.inner.workers()[index].state.lock().unwrap(),
+					WorkerState::Panicked { .. }
+				);
+			if !worker_alive {
+				reloads.push(None);
+				continue;
+			}
+			let (arrived_sender, arrived) = mpsc::sync_channel(1);
+			let (data_sender, data_receiver) = mpsc::sync_channel(1);
+			let (old_sender, old) = mpsc::sync_channel(1);
+			let (resume, resume_receiver) = mpsc::sync_channel::<()>(0);
+			tasks.push_to_worker(
+				index,
+				Task {
+					budgeted: false,
+					..Task::new(move |worker_data: &mut WorkerData| {
+						let _ = arrived_sender.send(());
+						if let Ok(new_data) = data_receiver.recv() {
+							let _ = old_sender.send(mem::replace(worker_data, new_data));
+						}
+						// Returns once the sender is dropped, i.e. when every worker is updated.
+						let _ = resume_receiver.recv();
+					})
+				},
+			);
+			reloads.push(Some((arrived, data_sender, old, resume)));
+		}
+		drop(guard);
+		self.inner.wake_all_workers();
+
+		debug!("waiting for workers to quiesce...");
+		// A worker that panics before reaching its reload task drops it, closing the channel.
+		for reload in &mut reloads {
+			if reload
+				.as_ref()
+				.is_some_and(|(arrived, ..)| arrived.recv().is_err())
+			{
+				*reload = None;
+			}
+		}
+
+		debug!("swapping worker data...");
+		let old_data = reloads
+			.iter()
+			.zip(&mut new_data)
+			.map(|(reload, new_data)| {
+				let (_, data_sender, old, _) = reload.as_ref()?;
+				data_sender.send(new_data.take()?).ok()?;
+				old.recv().ok()
+			})
+			.collect();
+		drop(reloads);
+		old_data
+	}
+}
@@ -0,0 +1,110 @@
+use std::thread::{self, ThreadId};
+
+use super::{ThreadPool, ThreadPoolShared};
+
+/// The thread serving a worker, as returned by [`ThreadPool::worker_threads`], e.g. to
+/// register it with a profiler, apply cgroup or affinity policies from outside the process,
+/// or correlate OS-level metrics with workers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerThread {
+	/// The id of the thread.
+	pub id: ThreadId,
+	/// The name of the thread, if it has one.
+	pub name: Option<String>,
+	/// The id the kernel knows the thread by (as returned by `gettid`), on Linux and Android
+	/// when the `native-thread-id` feature is enabled.
+	pub tid: Option<u64>,
+	/// The `pthread_t` of the thread, as an integer, on unix when the `native-thread-id`
+	/// feature is enabled.
+	pub pthread: Option<u64>,
+}
+
+impl WorkerThread {
+	/// Describe the current thread.
+	pub(crate) fn current() -> Self {
+		let thread = thread::current();
+		Self {
+			id: thread.id(),
+			name: thread.name().map(str::to_string),
+			tid: native_tid(),
+			pthread: native_pthread(),
+		}
+	}
+}
+
+impl<WorkerData: Send> ThreadPoolShared<WorkerData> {
+	/// Whether the current thread is one of the workers, e.g. running a task that enqueues
+	/// more, which must not block on a full queue as that's for the workers to make room.
+	pub(crate) fn on_worker_thread(&self) -> bool {
+		let current = thread::current().id();
+		self.workers().iter().any(|worker| {
+			worker
+				.os_thread
+				.lock()
+				.unwrap()
+				.as_ref()
+				.is_some_and(|thread| thread.id == current)
+		})
+	}
+}
+
+#[cfg(all(
+	feature = "native-thread-id",
+	any(target_os = "linux", target_os = "android")
+))]
+fn native_tid() -> Option<u64> {
+	// SAFETY: gettid takes no arguments and always succeeds.
+	let tid = unsafe { libc::syscall(libc::SYS_gettid) };
+	u64::try_from(tid).ok()
+}
+
+#[cfg(not(all(
+	feature = "native-thread-id",
+	any(target_os = "linux", target_os = "android")
+)))]
+fn native_tid() -> Option<u64> {
+	None
+}
+
+#[cfg(all(feature = "native-thread-id", unix))]
+#[allow(clippy::cast_possible_truncation, clippy::unnecessary_cast)]
+fn native_pthread() -> Option<u64> {
+	// SAFETY: pthread_self takes no arguments and always succeeds.
+	let pthread = unsafe { libc::pthread_self() };
+	// `pthread_t` is a pointer-sized integer on some platforms and a pointer on others.
+	u64::try_from(pthread as usize).ok()
+}
+
+#[cfg(not(all(feature = "native-thread-id", unix)))]
+fn native_pthread() -> Option<u64> {
+	None
+}
+
+impl<WorkerData: Send> ThreadPool<WorkerData> {
+	/// Get the thread currently serving each worker, which is `None` until the worker
+	/// has started. Threads that have exited are still reported.
+	///
+	/// The native ids are only available with the `native-thread-id` feature, see [`WorkerThread`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use lending_thread_pool::ThreadPool;
+	///
+	/// let pool = ThreadPool::new(vec![(); 2]);
+	/// let id = pool.install(|()| std::thread::current().id());
+	///
+	/// let threads = pool.worker_threads();
+	/// assert_eq!(threads.len(), 2);
+	/// assert!(threads.iter().flatten().any(|thread| thread.id == id));
+	/// ```
+	#[allow(clippy::missing_panics_doc)]
+	#[must_use]
+	pub fn worker_threads(&self) -> Vec<Option<WorkerThread>> {
+		self.inner
+			.workers()
+			.iter()
+			.map(|worker| worker.os_thread.lock().unwrap().clone())
+			.collect()
+	}
+}